@@ -0,0 +1,84 @@
+use std::fs;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use dove_core::{DoveOutput, InterpreterOptions, Isolate, Parser, Scanner};
+use dove_core::ast::Stmt;
+
+use crate::output::Silent;
+
+/// Runs of each benchmark before timing it, e.g. so caches are warm.
+const WARMUP_RUNS: usize = 3;
+/// Each benchmark is timed at least this many times, and until it ran for `MIN_TIME`.
+const MIN_RUNS: usize = 10;
+const MAX_RUNS: usize = 10_000;
+const MIN_TIME: Duration = Duration::from_secs(1);
+
+/// Run the script at `path`, then time each of its top level `bench_*` functions, called without arguments.
+/// Prints the mean, median and standard deviation of their run times.
+pub fn run_benchmarks(path: &str, output: Rc<dyn DoveOutput>, options: InterpreterOptions) -> Result<(), String> {
+    let source = fs::read_to_string(path)
+        .map_err(|error| format!("Cannot read '{}': {}", path, error))?;
+
+    let names = benchmark_names(&source);
+    if names.is_empty() {
+        return Err(format!("There are no 'bench_' functions in '{}'.", path));
+    }
+
+    let mut isolate = Isolate::new(output, options);
+    isolate.run(&source).map_err(|error| format!("Cannot run '{}': {}", path, error.message))?;
+
+    println!("{:<30} {:>8} {:>12} {:>12} {:>12}", "benchmark", "runs", "mean (ms)", "median (ms)", "stddev (ms)");
+    for name in names {
+        let function = isolate.global(&name).ok_or_else(|| format!("'{}' is not defined.", name))?;
+        let call = |isolate: &mut Isolate| isolate.call_function(function.clone(), &[])
+            .map_err(|error| format!("'{}' failed: {}", name, error.message));
+
+        for _ in 0..WARMUP_RUNS {
+            call(&mut isolate)?;
+        }
+
+        let mut times = vec![];
+        let started = Instant::now();
+        while times.len() < MAX_RUNS && (times.len() < MIN_RUNS || started.elapsed() < MIN_TIME) {
+            let start = Instant::now();
+            call(&mut isolate)?;
+            times.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        let (mean, median, stddev) = statistics(&mut times);
+        println!("{:<30} {:>8} {:>12.4} {:>12.4} {:>12.4}", name, times.len(), mean, median, stddev);
+    }
+    Ok(())
+}
+
+/// Names of the functions declared at the top level of the source whose names start with `bench_`.
+/// Errors in the source are reported when it runs.
+fn benchmark_names(source: &str) -> Vec<String> {
+    let tokens = Scanner::new(source.chars().collect(), Rc::new(Silent)).scan_tokens();
+    let statements = Parser::new(tokens, Rc::new(Silent)).program();
+
+    statements.iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Function(name, _, _) if name.lexeme.starts_with("bench_") => Some(name.lexeme.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Mean, median and population standard deviation of the times, which are sorted.
+fn statistics(times: &mut [f64]) -> (f64, f64, f64) {
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let count = times.len() as f64;
+    let mean = times.iter().sum::<f64>() / count;
+    let middle = times.len() / 2;
+    let median = if times.len().is_multiple_of(2) {
+        (times[middle - 1] + times[middle]) / 2.0
+    } else {
+        times[middle]
+    };
+    let variance = times.iter().map(|time| (time - mean).powi(2)).sum::<f64>() / count;
+
+    (mean, median, variance.sqrt())
+}
@@ -0,0 +1,177 @@
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+
+use dove_core::{DoveOutput, InterpreterOptions, ReplSession, ReplStatus, Scanner};
+use dove_core::error_handler::{Diagnostic, Severity};
+use dove_core::token::TokenType;
+
+/// An example in a doc comment, e.g.
+///
+/// ```text
+/// /// >>> add(1, 2)
+/// /// 3
+/// ```
+///
+/// Lines starting with `>>>` are run like REPL input, with `...` continuing them, and the lines
+/// after them up to a blank line or the next input are the expected output.
+struct Example {
+    line: usize,
+    source: String,
+    expected: Vec<String>,
+}
+
+/// Keeps printed lines and errors, so they can be compared with the expected output.
+struct Capture {
+    lines: RefCell<Vec<String>>,
+}
+
+impl Capture {
+    fn take(&self) -> Vec<String> {
+        self.lines.replace(vec![])
+    }
+}
+
+impl DoveOutput for Capture {
+    fn print(&self, message: String) {
+        self.lines.borrow_mut().extend(message.lines().map(str::to_string));
+    }
+
+    fn warning(&self, _message: String) {}
+
+    fn error(&self, message: String) {
+        self.lines.borrow_mut().push(message);
+    }
+
+    /// Only the message, since the location of an error in an example is not part of its output.
+    fn diagnostic(&self, diagnostic: Diagnostic) {
+        if diagnostic.severity == Severity::Error {
+            self.error(format!("Error: {}", diagnostic.message));
+        }
+    }
+}
+
+/// Run the examples in the doc comments of the script at `path`, after running the script itself.
+/// Prints the result of each, and returns whether all of them passed.
+pub fn run_doc_tests(path: &str) -> Result<bool, String> {
+    let source = fs::read_to_string(path)
+        .map_err(|error| format!("Cannot read '{}': {}", path, error))?;
+    let examples = examples(&source)
+        .ok_or_else(|| format!("Cannot read the doc comments of '{}', it has errors.", path))?;
+
+    let capture = Rc::new(Capture { lines: RefCell::new(vec![]) });
+    let mut session = ReplSession::new(capture.clone(), InterpreterOptions::default());
+
+    // Define the functions and classes the examples use.
+    session.input(&source);
+    if capture.take().iter().any(|line| line.starts_with("Error: ")) {
+        return Err(format!("Cannot run '{}', it has errors.", path));
+    }
+
+    let mut failed = 0;
+    for example in examples.iter() {
+        let status = session.input(&example.source);
+        let mut actual = capture.take();
+        if status == ReplStatus::Unfinished {
+            session.cancel();
+            actual.push("Error: The example is not finished.".to_string());
+        }
+
+        if actual == example.expected {
+            println!("test {}:{} ... ok", path, example.line);
+        } else {
+            failed += 1;
+            e_red_ln!("test {}:{} ... FAILED", path, example.line);
+            eprintln!("    {}", example.source.trim_end().replace('\n', "\n    "));
+            eprintln!("  expected:");
+            for line in example.expected.iter() {
+                eprintln!("    {}", line);
+            }
+            eprintln!("  got:");
+            for line in actual.iter() {
+                eprintln!("    {}", line);
+            }
+        }
+    }
+
+    println!("\n{} passed; {} failed", examples.len() - failed, failed);
+    Ok(failed == 0)
+}
+
+/// The examples in the doc comments of the source, or `None` if it cannot be scanned.
+fn examples(source: &str) -> Option<Vec<Example>> {
+    let output = Rc::new(Capture { lines: RefCell::new(vec![]) }) as Rc<dyn DoveOutput>;
+    let tokens = Scanner::new(source.chars().collect(), output).keep_comments().try_scan_tokens()?;
+
+    let mut examples: Vec<Example> = vec![];
+    // Whether lines are still added to the output of the last example.
+    let mut in_example = false;
+    let mut previous_line = 0;
+    for token in tokens.iter().filter(|token| token.token_type == TokenType::DOC_COMMENT) {
+        // Examples end with their doc comment.
+        if token.line != previous_line + 1 {
+            in_example = false;
+        }
+        previous_line = token.line;
+
+        let text = &token.lexeme["///".len()..];
+        let text = text.strip_prefix(' ').unwrap_or(text);
+        if let Some(input) = text.strip_prefix(">>>") {
+            examples.push(Example {
+                line: token.line,
+                source: format!("{}\n", input.trim_start()),
+                expected: vec![],
+            });
+            in_example = true;
+        } else if let (Some(input), true) = (text.strip_prefix("..."), in_example) {
+            let example = examples.last_mut().expect("Examples are being read.");
+            example.source.push_str(input.strip_prefix(' ').unwrap_or(input));
+            example.source.push('\n');
+        } else if text.trim().is_empty() {
+            in_example = false;
+        } else if in_example {
+            let example = examples.last_mut().expect("Examples are being read.");
+            example.expected.push(text.trim_end().to_string());
+        }
+    }
+
+    Some(examples)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::fs;
+    use std::rc::Rc;
+
+    use dove_core::{InterpreterOptions, Isolate};
+
+    use super::{run_doc_tests, Capture};
+
+    const PROPERTIES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../test_files/properties.dove");
+    const OPERATORS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../test_files/operators.dove");
+
+    #[test]
+    fn property_examples() {
+        assert_eq!(run_doc_tests(PROPERTIES), Ok(true));
+    }
+
+    #[test]
+    fn operator_examples() {
+        assert_eq!(run_doc_tests(OPERATORS), Ok(true));
+    }
+
+    #[test]
+    fn every_property_has_an_example() {
+        let source = fs::read_to_string(PROPERTIES).unwrap();
+        let mut isolate = Isolate::new(Rc::new(Capture { lines: RefCell::new(vec![]) }), InterpreterOptions::default());
+
+        let values = ["1.5", "1", "true", "nil", "\"a\"", "[1]", "(1,)", "{}", "encode(\"a\")", "deque([])", "heap()"];
+        for value in values.iter() {
+            let value = isolate.run(value).unwrap();
+            for name in value.as_object().property_names() {
+                assert!(source.contains(&format!(".{}(", name)), "'{}' of {} has no example.", name, value.to_string());
+            }
+        }
+    }
+}
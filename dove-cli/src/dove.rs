@@ -1,134 +1,234 @@
 use std::fs::File;
-use std::{ io, process };
-use std::io::{ErrorKind, Read, Write};
+use std::process;
+use std::io::{ErrorKind, Read};
+use std::path::PathBuf;
 use std::rc::Rc;
 
-use chrono::prelude::*;
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
 
-use dove_core::{Scanner, Importer, Interpreter, Parser, Resolver, DoveOutput};
+use dove_core::{Scanner, Parser, Resolver, DoveOutput, InterpreterOptions, ReplSession, ReplStatus};
+use dove_core::ast::Stmt;
+use dove_core::reflection;
+use dove_core::token::Literals;
 
-pub struct Dove {
-    interpreter: Interpreter,
-    pub is_repl_unfinished: bool,
+use crate::output::Output;
 
-    /// Keep track of what files this Dove has visited.
-    visited_imports: Vec<String>,
+pub struct Dove {
+    session: ReplSession,
 
-    output: Rc<dyn DoveOutput>,
+    output: Rc<Output>,
 }
 
 impl Dove {
-    pub fn new(output: Rc<dyn DoveOutput>) -> Self {
+    pub fn new(output: Rc<Output>, options: InterpreterOptions) -> Self {
         Dove {
-            interpreter: Interpreter::new(Rc::clone(&output)),
-            is_repl_unfinished: false,
-            visited_imports: Vec::new(),
+            session: ReplSession::new(output.clone(), options),
             output,
         }
     }
 
+    /// Run the file at `path`. Exits the process if the file cannot be read.
     pub fn run_file(&mut self, path: &str) {
-        let mut f = match File::open(path) {
-            Ok(file) => file,
-            Err(error) => match error.kind() {
-                ErrorKind::NotFound => {
-                    e_red_ln!("File: '{}' not found.", path);
-                    process::exit(53);
-                },
-                _ => {
-                    e_red_ln!("Error while reading file: {} {:?}", path, error);
-                    process::exit(75);
-                }
+        match read_file(path) {
+            Ok(content) => { self.run(content.chars().collect(), path); },
+            Err(status) => process::exit(status),
+        }
+    }
+
+    /// Read and run input until Ctrl-D, `:quit` or `exit()`, and return the status passed to `exit`.
+    /// Lines can be edited, and earlier ones recalled with the arrow keys, from a history kept in
+    /// `~/.dove_history`, which is saved after every line. Ctrl-C cancels the current input.
+    pub fn run_prompt(&mut self) -> i32 {
+        let mut editor = match DefaultEditor::new() {
+            Ok(editor) => editor,
+            Err(error) => {
+                e_red_ln!("Cannot start the REPL: {}", error);
+                return 1;
             }
         };
 
-        let mut content = String::new();
-        match f.read_to_string(&mut content) {
-            Ok(_) => {},
-            Err(_) => {
-                e_red_ln!("Error while reading file '{}' to string.", path);
-                process::exit(92);
-            }
+        let history = history_path();
+        if let Some(path) = &history {
+            // There is no history the first time.
+            let _ = editor.load_history(path);
         }
 
-        self.run(content.chars().collect(), false);
-    }
-
-    pub fn run_prompt(&mut self) {
-        // Print version & time information.
-        let date = Local::now();
-        cyan_ln!("Dove 0.1.1 (default, {})", date.format("%b %e %Y, %H:%M:%S"));
-        cyan_ln!("Visit https://github.com/dove-lang for more information.");
-
-        // Used to store previous lines of code, if encounters unfinished blocks.
-        let mut code_buffer = String::new();
+        for line in self.session.banner() {
+            cyan_ln!("{}", line);
+        }
 
+        let mut status = 0;
         loop {
-            let indicator = format!("{} ", if self.is_repl_unfinished {"..."} else {">>>"});
-            print!("{}", indicator);
-
-            let mut input = String::new();
-            // `stdout` gets flushed on new lines, manually flush it.
-            let _ = io::stdout().flush();
-            match io::stdin().read_line(&mut input) {
-                Ok(_) => {},
-                Err(_) => {
-                    e_red_ln!("Error while reading input to string.");
-                    process::exit(92);
+            let input = match editor.readline(&format!("{} ", self.session.prompt())) {
+                Ok(input) => input,
+                Err(ReadlineError::Interrupted) => {
+                    self.session.cancel();
+                    continue;
+                },
+                Err(ReadlineError::Eof) => break,
+                Err(error) => {
+                    e_red_ln!("Error while reading input: {}", error);
+                    break;
+                }
+            };
+
+            if !input.trim().is_empty() {
+                let _ = editor.add_history_entry(input.as_str());
+                if let Some(path) = &history {
+                    if let Err(error) = editor.append_history(path) {
+                        e_red_ln!("Cannot save the history to '{}': {}", path.display(), error);
+                    }
                 }
             }
 
-            let input = format!("{}{}", code_buffer, input);
-
-            self.run(input.chars().collect(), true);
+            // REPL commands, e.g. `:type expr`. Pasted input can span several lines.
+            let is_line = input.trim_end().lines().count() <= 1;
+            if is_line && !self.session.is_unfinished() && input.trim_start().starts_with(':') {
+                if input.trim() == ":quit" {
+                    break;
+                }
+                self.run_command(input.trim());
+                continue;
+            }
 
-            // If Dove is in an unfinished block, store `input` back in `code_buffer`,
-            // otherwise clear `code_buffer`.
-            if self.is_repl_unfinished {
-                code_buffer = input;
-            } else {
-                code_buffer = String::new();
+            if let ReplStatus::Exited(exit_status) = self.session.input(&input) {
+                status = exit_status;
+                break;
             }
+        }
 
-            // Reset the flag; one mistake from the user shouldn't kill the entire session.
-            // self.had_error = false;
+        if self.session.interpreter().options().repl_banner {
+            cyan_ln!("Goodbye!");
         }
+        status
     }
 
-    pub fn run(&mut self, source: Vec<char>, is_in_repl: bool) {
-        let scanner = Scanner::new(source, Rc::clone(&self.output));
-        let tokens = scanner.scan_tokens();
+    /// The status a script passed to `exit`, if it called it.
+    pub fn exit_status(&mut self) -> Option<i32> {
+        self.session.interpreter().exit_status()
+    }
 
-        let mut importer = Importer::new(tokens, Rc::clone(&self.output));
-        let (tokens, imports) = importer.analyze();
+    /// Run a REPL command: `:type expr` prints the type of a value,
+    /// `:inspect expr` prints its fields, methods or parameters. `:quit` is handled by the prompt.
+    fn run_command(&mut self, command: &str) {
+        let (name, argument) = match command.find(char::is_whitespace) {
+            Some(index) => (&command[..index], command[index..].trim()),
+            None => (command, ""),
+        };
 
-        // Run the import files.
-        for import in imports {
-            if self.visited_imports.contains(&import) {
-                e_red_ln!("Import Error: Cannot import file '{}'.", import);
-                process::exit(92);
+        let describe: fn(&Literals) -> String = match name {
+            ":type" => reflection::type_description,
+            ":inspect" => reflection::inspect,
+            _ => {
+                e_red_ln!("Unknown command '{}'. Available commands: :type, :inspect, :quit.", name);
+                return;
             }
+        };
 
-            self.visited_imports.push(import.clone());
-            self.run_file(&import);
+        if argument.is_empty() {
+            e_red_ln!("Usage: {} <expression>", name);
+            return;
         }
 
-        let mut parser = Parser::new(tokens, is_in_repl, Rc::clone(&self.output));
+        if let Some(value) = self.evaluate(argument) {
+            println!("{}", describe(&value));
+        }
+    }
+
+    /// Evaluate the source of a single expression, reporting any errors.
+    fn evaluate(&mut self, source: &str) -> Option<Literals> {
+        self.output.repl_source(source);
+        let scanner = Scanner::new(source.chars().collect(), self.output.clone());
+        let tokens = scanner.try_scan_tokens()?;
+
+        let mut parser = Parser::new(tokens, self.output.clone());
         let statements = parser.program();
+        if parser.had_error() {
+            return None;
+        }
+
+        let expr = match statements.as_slice() {
+            [Stmt::Expression(expr)] => expr.clone(),
+            [] => return None,
+            _ => {
+                e_red_ln!("Expected a single expression.");
+                return None;
+            }
+        };
+
+        let interpreter = self.session.interpreter();
+        let mut resolver = Resolver::new(interpreter, self.output.clone());
+        resolver.resolve(&statements);
+        if resolver.had_error() {
+            return None;
+        }
 
-        // Check if unfinished status change.
-        if parser.is_in_unfinished_blk != self.is_repl_unfinished {
-            self.is_repl_unfinished = !self.is_repl_unfinished;
+        match interpreter.evaluate_expression(&expr) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                interpreter.error_handler.runtime_error(error);
+                None
+            }
         }
+    }
+
+    /// Run `source`, read from the file at `path`, and return whether it ran without errors.
+    pub fn run(&mut self, source: Vec<char>, path: &str) -> bool {
+        let tokens = match Scanner::with_file(source, path, self.output.clone()).try_scan_tokens() {
+            Some(tokens) => tokens,
+            None => return false,
+        };
+
+        let mut parser = Parser::new(tokens, self.output.clone());
+        let statements = parser.program();
 
         // Stops if there is a syntax error.
-        // if self.had_error {
-        //     return self;
-        // }
+        if parser.had_error() {
+            return false;
+        }
 
-        let mut resolver = Resolver::new(&mut self.interpreter, Rc::clone(&self.output));
+        let interpreter = self.session.interpreter();
+        let mut resolver = Resolver::new(interpreter, self.output.clone());
         resolver.resolve(&statements);
+        if resolver.had_error() {
+            return false;
+        }
+
+        // Earlier files of a batch sharing the interpreter may have failed.
+        interpreter.error_handler.reset();
+        interpreter.interpret(statements);
+        !interpreter.error_handler.had_runtime_error
+    }
+}
 
-        self.interpreter.interpret(statements);
+/// The content of the file at `path`. If it cannot be read, reports why and returns the status to exit with.
+pub fn read_file(path: &str) -> Result<String, i32> {
+    let mut f = match File::open(path) {
+        Ok(file) => file,
+        Err(error) => match error.kind() {
+            ErrorKind::NotFound => {
+                e_red_ln!("File: '{}' not found.", path);
+                return Err(53);
+            },
+            _ => {
+                e_red_ln!("Error while reading file: {} {:?}", path, error);
+                return Err(75);
+            }
+        }
+    };
+
+    let mut content = String::new();
+    match f.read_to_string(&mut content) {
+        Ok(_) => Ok(content),
+        Err(_) => {
+            e_red_ln!("Error while reading file '{}' to string.", path);
+            Err(92)
+        }
     }
 }
+
+/// The file the REPL keeps its history in, if there is a home directory.
+fn history_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".dove_history"))
+}
@@ -0,0 +1,139 @@
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use dove_core::InterpreterHooks;
+use dove_core::ast::Stmt;
+use dove_core::error_handler::RuntimeError;
+use dove_core::token::Literals;
+
+/// Interpreter hooks backing the `--trace`, `--profile` and `--time` flags.
+pub struct Instrumentation {
+    trace: bool,
+    profile: bool,
+    time: bool,
+
+    /// Start times of the functions currently being called.
+    call_stack: RefCell<Vec<(String, Instant)>>,
+    /// Number of calls and total time spent, keyed by function name.
+    profile_data: RefCell<HashMap<String, (usize, Duration)>>,
+
+    /// Counts for the `--time` report.
+    statements: Cell<usize>,
+    collections: Cell<usize>,
+    calls: RefCell<HashMap<String, usize>>,
+}
+
+impl Instrumentation {
+    pub fn new(trace: bool, profile: bool, time: bool) -> Instrumentation {
+        Instrumentation {
+            trace,
+            profile,
+            time,
+            call_stack: RefCell::new(Vec::new()),
+            profile_data: RefCell::new(HashMap::new()),
+            statements: Cell::new(0),
+            collections: Cell::new(0),
+            calls: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Print the wall time of the run with how much work it did, most called functions first.
+    pub fn print_usage(&self, elapsed: Duration) {
+        if !self.time {
+            return;
+        }
+
+        let mut calls: Vec<_> = self.calls.borrow().iter()
+            .map(|(name, calls)| (name.clone(), *calls))
+            .collect();
+        calls.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        eprintln!("{:<30} {:>14.3}", "wall time (ms)", elapsed.as_secs_f64() * 1000.0);
+        eprintln!("{:<30} {:>14}", "statements executed", self.statements.get());
+        eprintln!("{:<30} {:>14}", "collections created", self.collections.get());
+        eprintln!("{:<30} {:>14}", "function calls", calls.iter().map(|(_, calls)| calls).sum::<usize>());
+        for (name, calls) in calls {
+            eprintln!("  {:<28} {:>14}", name, calls);
+        }
+    }
+
+    /// Pop the innermost call and add its time to the profile.
+    fn finish_call(&self) {
+        if !self.profile {
+            return;
+        }
+        if let Some((name, start)) = self.call_stack.borrow_mut().pop() {
+            let mut data = self.profile_data.borrow_mut();
+            let entry = data.entry(name).or_insert((0, Duration::new(0, 0)));
+            entry.0 += 1;
+            entry.1 += start.elapsed();
+        }
+    }
+
+    /// Print the collected profile, slowest functions first.
+    pub fn print_profile(&self) {
+        if !self.profile {
+            return;
+        }
+
+        let mut rows: Vec<_> = self.profile_data.borrow().iter()
+            .map(|(name, (calls, time))| (name.clone(), *calls, *time))
+            .collect();
+        rows.sort_by_key(|row| Reverse(row.2));
+
+        eprintln!("{:<30} {:>10} {:>14}", "function", "calls", "total (ms)");
+        for (name, calls, time) in rows {
+            eprintln!("{:<30} {:>10} {:>14.3}", name, calls, time.as_secs_f64() * 1000.0);
+        }
+    }
+}
+
+impl InterpreterHooks for Instrumentation {
+    fn on_statement(&self, stmt: &Stmt) -> Result<(), RuntimeError> {
+        self.statements.set(self.statements.get() + 1);
+        if self.trace {
+            if let Some(line) = stmt.line() {
+                eprintln!("[trace] line {}", line);
+            }
+        }
+        Ok(())
+    }
+
+    fn on_call(&self, name: &str, arguments: &[Literals]) {
+        if self.trace {
+            eprintln!("[trace] call {} with {} argument(s)", name, arguments.len());
+        }
+        if self.time {
+            *self.calls.borrow_mut().entry(name.to_string()).or_insert(0) += 1;
+        }
+        if self.profile {
+            self.call_stack.borrow_mut().push((name.to_string(), Instant::now()));
+        }
+    }
+
+    fn on_return(&self, name: &str, _value: &Literals) {
+        if self.trace {
+            eprintln!("[trace] return from {}", name);
+        }
+        self.finish_call();
+    }
+
+    fn on_unwind(&self, name: &str, _error: &RuntimeError) {
+        if self.trace {
+            eprintln!("[trace] unwind from {}", name);
+        }
+        self.finish_call();
+    }
+
+    fn on_collection(&self, _value: &Literals) {
+        self.collections.set(self.collections.get() + 1);
+    }
+
+    fn on_error(&self, error: &RuntimeError) {
+        if self.trace {
+            eprintln!("[trace] error: {}", error.message);
+        }
+    }
+}
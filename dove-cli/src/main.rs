@@ -1,40 +1,342 @@
 #[macro_use(e_red_ln, e_yellow_ln, cyan_ln)]
 extern crate colour;
 
+mod bench;
+mod doctest;
 mod dove;
+mod instrument;
+mod manifest;
+mod output;
+mod package;
+mod rename;
 
-use std::env;
+use std::{env, process, thread};
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use dove_core::DoveOutput;
+use dove_core::{InterpreterHooks, InterpreterOptions, FileProvider};
+use dove_core::dove_input::StdinInput;
 use dove::Dove;
+use instrument::Instrumentation;
+use manifest::Project;
+use output::Output;
+use rename::Position;
 
-struct Output;
-impl DoveOutput for Output {
-    fn print(&self, message: String) {
-        println!("{}", message);
+/// Stack used by a Dove function call in a debug build, with some margin. Release builds use about a tenth.
+const STACK_PER_CALL: usize = 128 * 1024;
+
+/// Stack size of the thread running scripts. The usual 8 MB stack of the main thread holds only a few
+/// hundred Dove calls, so without it recursion would abort the process with a stack overflow
+/// instead of stopping at the default maximum call depth with a runtime error.
+const STACK_SIZE: usize = 2 * InterpreterOptions::DEFAULT_MAX_CALL_DEPTH * STACK_PER_CALL;
+
+fn main() {
+    let thread = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run_cli)
+        .expect("Cannot start the interpreter thread.");
+
+    if thread.join().is_err() {
+        process::exit(101);
     }
+}
+
+fn run_cli() {
+    // Collect command line arguments.
+    // Note: The first value is always the name of the binary.
+    let mut arguments = env::args().skip(1);
+    let mut args = vec![];
 
-    fn warning(&self, message: String) {
-        e_yellow_ln!("{}", message);
+    let mut trace = false;
+    let mut profile = false;
+    let mut time = false;
+    let mut interactive = false;
+    let mut shared = false;
+    let mut doc = false;
+    let mut preload = None;
+    let mut position = None;
+    let mut options = InterpreterOptions::default().input(Rc::new(StdinInput));
+    if let Ok(prompt) = env::var("DOVE_PROMPT") {
+        options = options.repl_prompt(&prompt);
     }
+    while let Some(arg) = arguments.next() {
+        if !arg.starts_with("--") {
+            args.push(arg);
+            continue;
+        }
+
+        let (name, value) = match arg.find('=') {
+            Some(index) => (&arg[..index], Some(&arg[index + 1..])),
+            None => (&arg[..], None),
+        };
+
+        match (name, value) {
+            ("--trace", None) => trace = true,
+            ("--profile", None) => profile = true,
+            ("--time", None) => time = true,
+            ("--interactive", None) => interactive = true,
+            ("--shared", None) => shared = true,
+            ("--doc", None) => doc = true,
+            ("--preload", Some(path)) => preload = Some(path.to_string()),
+            ("--preload", None) if arguments.len() > 0 => preload = arguments.next(),
+            ("--file", Some(file)) => position = Some(file.to_string()),
+            ("--file", None) if arguments.len() > 0 => position = arguments.next(),
+            ("--no-echo", None) => options = options.repl_echo(false),
+            ("--quiet", None) => options = options.repl_banner(false),
+            ("--falsy-empty", None) => options = options.falsy_empty(true),
+            ("--strict", None) => options = options.strict(true),
+            ("--deterministic", None) => options = options.deterministic(true),
+            ("--float-precision", Some(digits)) if digits.parse::<usize>().is_ok() => {
+                options = options.float_precision(digits.parse().ok());
+            },
+            ("--timeout", Some(millis)) if millis.parse::<u64>().is_ok() => {
+                options = options.evaluation_timeout(millis.parse().ok().map(Duration::from_millis));
+            },
+            ("--max-call-depth", Some("none")) => options = options.max_call_depth(None),
+            ("--max-call-depth", Some(depth)) if depth.parse::<usize>().is_ok() => {
+                options = options.max_call_depth(depth.parse().ok());
+            },
+            _ => {
+                println!("{}", USAGE);
+                return;
+            },
+        }
+    }
+
+    let instrumentation = Rc::new(Instrumentation::new(trace, profile, time));
+    if trace || profile || time {
+        options = options.hooks(Rc::clone(&instrumentation) as Rc<dyn InterpreterHooks>);
+    }
+
+    let output = Rc::new(Output::new(Rc::clone(&options.file_provider)));
+    match args.as_slice() {
+        [] => run_repl(output, options, preload.as_deref()),
+        [command] if command == "repl" => run_repl(output, options, preload.as_deref()),
+        [command] if command == "run" => {
+            let start = Instant::now();
+            let exit_status = run_project(options);
+            instrumentation.print_profile();
+            instrumentation.print_usage(start.elapsed());
+
+            if let Some(status) = exit_status {
+                process::exit(status);
+            }
+        },
+        [command] if command == "install" => install_packages(),
+        [command, script] if command == "test" && doc => run_doc_tests(script),
+        [command, script] if command == "bench" => run_benchmarks(script, output, options),
+        [command, source] if command == "add" => add_package(source),
+        [command, old, new] if command == "rename" => match position.as_deref() {
+            Some(position) => rename_symbol(position, old, new),
+            None => println!("{}", USAGE),
+        },
+        [script] => {
+            let mut dove = Dove::new(output, options);
+            let start = Instant::now();
+            dove.run_file(script);
+            instrumentation.print_profile();
+            instrumentation.print_usage(start.elapsed());
 
-    fn error(&self, message: String) {
+            match dove.exit_status() {
+                Some(status) => process::exit(status),
+                None if interactive => exit_with(dove.run_prompt()),
+                None => {},
+            }
+        },
+        scripts if !COMMANDS.contains(&scripts[0].as_str()) => {
+            if interactive {
+                e_red_ln!("--interactive starts the REPL after a single script, not several.");
+                process::exit(64);
+            }
+
+            let start = Instant::now();
+            let status = run_batch(scripts, shared, output, options);
+            instrumentation.print_profile();
+            instrumentation.print_usage(start.elapsed());
+            exit_with(status);
+        },
+        _ => println!("{}", USAGE),
+    }
+}
+
+/// Names of the commands, which cannot start a list of scripts.
+const COMMANDS: [&str; 7] = ["add", "bench", "install", "rename", "repl", "run", "test"];
+
+const USAGE: &str = "Usage: dove [options] [script | run]
+       dove [options] [--shared] <script> <script>...
+       dove [options] repl [--preload <script>]
+       dove add <git-url-or-path>
+       dove install
+       dove rename <old> <new> --file <script>:<line>:<column>
+       dove test --doc <script>
+       dove [options] bench <script>
+
+Options:
+    --interactive           Start the REPL after running a single script
+    --shared                Run several scripts in one interpreter, so later ones see earlier definitions
+    --trace                 Print every statement, call and error
+    --profile               Print the time spent in each function
+    --time                  Print the run time, statements executed, collections created and calls
+    --no-echo               Do not print the values of expressions in the REPL
+    --quiet                 Start the REPL without the banner
+    --strict                Report likely mistakes as errors instead of warnings, and dividing by zero
+    --falsy-empty           Treat empty strings and collections as false in conditions
+    --deterministic         Give the same random numbers on every run
+    --float-precision=<n>   Print numbers with this many digits after the decimal point
+    --timeout=<ms>          Stop a script or an input of the REPL running longer than this
+    --max-call-depth=<n>    Nesting limit of function calls, or 'none' (default 1000)
+
+Environment:
+    DOVE_PROMPT             The prompt of the REPL (default '>>>')";
+
+fn current_dir() -> PathBuf {
+    env::current_dir().unwrap_or_else(|error| {
+        e_red_ln!("Cannot read the current directory: {}", error);
+        process::exit(75);
+    })
+}
+
+/// Find the project containing the current directory.
+fn find_project() -> Project {
+    Project::find(&current_dir()).unwrap_or_else(|message| {
         e_red_ln!("{}", message);
+        process::exit(78);
+    })
+}
+
+/// Start the REPL, running the `preload` script first so its definitions are available.
+fn run_repl(output: Rc<Output>, options: InterpreterOptions, preload: Option<&str>) {
+    let mut dove = Dove::new(output, options);
+    if let Some(path) = preload {
+        dove.run_file(path);
+        if let Some(status) = dove.exit_status() {
+            process::exit(status);
+        }
     }
+    exit_with(dove.run_prompt());
 }
 
-fn main() {
-    // Collect command line arguments.
-    // Note: The first value is always the name of the binary.
-    let args: Vec<String> = env::args().collect();
-    let mut dove = Dove::new(Rc::new(Output {}));
-
-    if args.len() > 2 {
-        println!("Usage: dove [script]");
-    } else if args.len() == 2 {
-        dove.run_file(&args[1]);
-    } else {
-        dove.run_prompt();
+/// Run the entry script of the project in the current directory, and return the status it passed to `exit`.
+fn run_project(options: InterpreterOptions) -> Option<i32> {
+    let project = find_project();
+
+    let file_provider = Rc::new(project.file_provider()) as Rc<dyn FileProvider>;
+    let output = Rc::new(Output::new(Rc::clone(&file_provider)));
+    let options = options.file_provider(file_provider);
+    let mut dove = Dove::new(output, options);
+    dove.run_file(&project.entry_path().to_string_lossy());
+    dove.exit_status()
+}
+
+/// Run several scripts in order, each in a new interpreter or all in one if `shared`, and print
+/// whether each ran without errors. Returns the exit status, 1 if any did not. In a shared interpreter,
+/// `exit` stops the remaining scripts too.
+fn run_batch(scripts: &[String], shared: bool, output: Rc<Output>, options: InterpreterOptions) -> i32 {
+    let mut shared_dove = shared.then(|| Dove::new(Rc::clone(&output), options.clone()));
+    let (mut passed, mut failed) = (0, 0);
+    for script in scripts {
+        let mut isolated_dove;
+        let dove = match &mut shared_dove {
+            Some(dove) => dove,
+            None => {
+                isolated_dove = Dove::new(Rc::clone(&output), options.clone());
+                &mut isolated_dove
+            },
+        };
+
+        let ran = match dove::read_file(script) {
+            Ok(source) => dove.run(source.chars().collect(), script),
+            Err(_) => false,
+        };
+        let exit_status = dove.exit_status();
+
+        if ran && exit_status.unwrap_or(0) == 0 {
+            passed += 1;
+            println!("{} ... ok", script);
+        } else {
+            failed += 1;
+            match exit_status {
+                Some(status) => { e_red_ln!("{} ... exited with status {}", script, status); },
+                None => { e_red_ln!("{} ... FAILED", script); },
+            }
+        }
+
+        if shared && exit_status.is_some() {
+            break;
+        }
+    }
+
+    println!("\n{} passed; {} failed", passed, failed);
+    if failed > 0 { 1 } else { 0 }
+}
+
+/// End the process with `status` if it is an error, otherwise let it finish normally.
+fn exit_with(status: i32) {
+    if status != 0 {
+        process::exit(status);
+    }
+}
+
+fn add_package(source: &str) {
+    match package::add(&find_project(), source, &current_dir()) {
+        Ok(name) => println!("Added '{}'.", name),
+        Err(message) => {
+            e_red_ln!("{}", message);
+            process::exit(1);
+        },
+    }
+}
+
+/// Rename the variable at `position` and everything referring to it.
+fn rename_symbol(position: &str, old: &str, new: &str) {
+    let position = match Position::parse(position) {
+        Some(position) => position,
+        None => {
+            e_red_ln!("Expected a position like 'main.dove:3:5' but got '{}'.", position);
+            process::exit(64);
+        },
+    };
+
+    match rename::rename(&position, old, new) {
+        Ok(count) => println!("Renamed {} occurrence(s) of '{}' to '{}'.", count, old, new),
+        Err(message) => {
+            e_red_ln!("{}", message);
+            process::exit(1);
+        },
+    }
+}
+
+/// Run the examples in the doc comments of a script, failing if any output differs.
+fn run_doc_tests(script: &str) {
+    match doctest::run_doc_tests(script) {
+        Ok(true) => {},
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            e_red_ln!("{}", message);
+            process::exit(1);
+        },
+    }
+}
+
+/// Time the `bench_*` functions of a script.
+fn run_benchmarks(script: &str, output: Rc<Output>, options: InterpreterOptions) {
+    if let Err(message) = bench::run_benchmarks(script, output, options) {
+        e_red_ln!("{}", message);
+        process::exit(1);
+    }
+}
+
+fn install_packages() {
+    match package::install(&find_project()) {
+        Ok(installed) => {
+            for name in installed {
+                println!("Installed '{}'.", name);
+            }
+        },
+        Err(message) => {
+            e_red_ln!("{}", message);
+            process::exit(1);
+        },
     }
 }
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+
+use dove_core::FileProvider;
+
+pub const MANIFEST_NAME: &str = "dove.toml";
+/// Directory of the project that dependencies are installed into.
+pub const MODULES_DIR: &str = "dove_modules";
+
+/// Contents of a `dove.toml` file, e.g.
+///
+/// ```toml
+/// [package]
+/// name = "app"
+/// entry = "src/main.dove"
+/// source_roots = ["src"]
+///
+/// [dependencies]
+/// utils = { path = "../utils" }
+/// json = { git = "https://github.com/user/json.git" }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    pub package: Package,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Package {
+    /// The script `dove run` starts from.
+    #[serde(default = "default_entry")]
+    pub entry: String,
+    /// Directories searched for imported files, in order.
+    #[serde(default = "default_source_roots")]
+    pub source_roots: Vec<String>,
+}
+
+/// Where a dependency is fetched from, either a local directory or a git repository.
+#[derive(Debug, Deserialize)]
+pub struct Dependency {
+    pub path: Option<String>,
+    pub git: Option<String>,
+}
+
+fn default_entry() -> String {
+    "main.dove".to_string()
+}
+
+fn default_source_roots() -> Vec<String> {
+    vec![".".to_string()]
+}
+
+/// A project, i.e. a directory with a `dove.toml` file.
+pub struct Project {
+    pub root: PathBuf,
+    pub manifest: Manifest,
+}
+
+impl Project {
+    /// Find the project containing `dir`, looking for a manifest in `dir` and its parents.
+    pub fn find(dir: &Path) -> Result<Project, String> {
+        let mut current = Some(dir);
+        while let Some(dir) = current {
+            let manifest_path = dir.join(MANIFEST_NAME);
+            if manifest_path.is_file() {
+                return Project::load(dir, &manifest_path);
+            }
+            current = dir.parent();
+        }
+
+        Err(format!("Could not find '{}' in '{}' or any parent directory.", MANIFEST_NAME, dir.display()))
+    }
+
+    pub fn manifest_path(&self) -> PathBuf {
+        self.root.join(MANIFEST_NAME)
+    }
+
+    /// Directory the dependency `name` is installed into.
+    pub fn module_dir(&self, name: &str) -> PathBuf {
+        self.root.join(MODULES_DIR).join(name)
+    }
+
+    fn load(root: &Path, manifest_path: &Path) -> Result<Project, String> {
+        let content = fs::read_to_string(manifest_path)
+            .map_err(|error| format!("Error while reading '{}': {}", manifest_path.display(), error))?;
+        let manifest = toml::from_str(&content)
+            .map_err(|error| format!("Invalid manifest '{}': {}", manifest_path.display(), error))?;
+
+        Ok(Project {
+            root: root.to_path_buf(),
+            manifest,
+        })
+    }
+
+    pub fn entry_path(&self) -> PathBuf {
+        self.root.join(&self.manifest.package.entry)
+    }
+
+    /// The provider resolving imports against the source roots and dependencies of this project.
+    pub fn file_provider(&self) -> ProjectFileProvider {
+        ProjectFileProvider {
+            source_roots: self.manifest.package.source_roots.iter().map(|root| self.root.join(root)).collect(),
+            dependencies: self.manifest.dependencies.keys()
+                .map(|name| (name.clone(), self.module_dir(name)))
+                .collect(),
+        }
+    }
+}
+
+/// Resolves `import "name/file.dove"` inside the installed dependency `name`,
+/// and other imports relative to the first source root containing them.
+pub struct ProjectFileProvider {
+    source_roots: Vec<PathBuf>,
+    /// Installation directory of each dependency.
+    dependencies: BTreeMap<String, PathBuf>,
+}
+
+impl ProjectFileProvider {
+    fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        let path = Path::new(path);
+        if path.is_absolute() {
+            return Ok(path.to_path_buf());
+        }
+
+        let mut components = path.components();
+        if let Some(Component::Normal(first)) = components.next() {
+            if let Some((name, dir)) = first.to_str().and_then(|name| self.dependencies.get_key_value(name)) {
+                if !dir.is_dir() {
+                    return Err(format!("dependency '{}' is not installed, run 'dove install'", name));
+                }
+                return Ok(dir.join(components.as_path()));
+            }
+        }
+
+        self.source_roots.iter()
+            .map(|root| root.join(path))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| "file not found in the source roots".to_string())
+    }
+}
+
+impl FileProvider for ProjectFileProvider {
+    fn read_file(&self, path: &str) -> Result<String, String> {
+        let resolved = self.resolve(path)?;
+        fs::read_to_string(&resolved).map_err(|error| error.to_string())
+    }
+}
@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use dove_core::{DoveOutput, FileProvider};
+use dove_core::error_handler::{Diagnostic, Severity, SourceLocation};
+
+/// Prints to the terminal. Errors and warnings are shown with an excerpt of the source
+/// underlining where they happened, unless stderr is not a terminal.
+pub struct Output {
+    excerpts: bool,
+    /// Reads the files excerpts are taken from, the same way the interpreter reads them.
+    files: Rc<dyn FileProvider>,
+    /// The latest input of the REPL, which has no file to read excerpts from.
+    repl_source: RefCell<String>,
+}
+
+impl Output {
+    pub fn new(files: Rc<dyn FileProvider>) -> Output {
+        Output {
+            excerpts: atty::is(atty::Stream::Stderr),
+            files,
+            repl_source: RefCell::new(String::new()),
+        }
+    }
+
+    fn source_line(&self, location: &SourceLocation) -> Option<String> {
+        let index = location.line.checked_sub(1)?;
+        match &location.file {
+            Some(file) => self.files.read_file(file).ok()?.lines().nth(index).map(str::to_string),
+            None => self.repl_source.borrow().lines().nth(index).map(str::to_string),
+        }
+    }
+
+    /// Print the diagnostic in the style of rustc, e.g.
+    ///
+    /// ```text
+    /// error: Undefined variable 'nmae'.
+    ///  --> main.dove:2:7
+    ///   |
+    /// 2 | print(nmae)
+    ///   |       ^^^^
+    /// ```
+    ///
+    /// Returns false if the source line cannot be found.
+    fn print_excerpt(&self, diagnostic: &Diagnostic) -> bool {
+        let location = match &diagnostic.location {
+            Some(location) => location,
+            None => return false,
+        };
+        let line = match self.source_line(location) {
+            Some(line) => line,
+            None => return false,
+        };
+
+        let warning = diagnostic.severity == Severity::Warning;
+        let gutter = " ".repeat(location.line.to_string().len());
+        if warning {
+            e_yellow_ln!("warning: {}", diagnostic.message);
+        } else {
+            e_red_ln!("error: {}", diagnostic.message);
+        }
+        match &diagnostic.function {
+            Some(name) => eprintln!("{}--> {} in function '{}'", gutter, location, name),
+            None => eprintln!("{}--> {}", gutter, location),
+        }
+        eprintln!("{} |", gutter);
+        eprintln!("{} | {}", location.line, line);
+
+        if let Some(column) = location.column {
+            // Keep tabs so the underline lines up with the source.
+            let indent: String = line.chars()
+                .take(column.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let remaining = line.chars().count().saturating_sub(column - 1);
+            let length = location.length.min(remaining).max(1);
+            let underline = "^".repeat(length);
+            if warning {
+                e_yellow_ln!("{} | {}{}", gutter, indent, underline);
+            } else {
+                e_red_ln!("{} | {}{}", gutter, indent, underline);
+            }
+        }
+
+        if let Some(help) = &diagnostic.help {
+            eprintln!("{} = help: {}", gutter, help);
+        }
+        true
+    }
+}
+
+impl DoveOutput for Output {
+    fn print(&self, message: String) {
+        println!("{}", message);
+    }
+
+    fn prompt(&self, prompt: &str) {
+        print!("{}", prompt);
+        io::stdout().flush().ok();
+    }
+
+    fn warning(&self, message: String) {
+        e_yellow_ln!("{}", message);
+    }
+
+    fn error(&self, message: String) {
+        e_red_ln!("{}", message);
+    }
+
+    fn repl_source(&self, source: &str) {
+        *self.repl_source.borrow_mut() = source.to_string();
+    }
+
+    fn diagnostic(&self, diagnostic: Diagnostic) {
+        if self.excerpts && self.print_excerpt(&diagnostic) {
+            return;
+        }
+
+        match diagnostic.severity {
+            Severity::Error => self.error(diagnostic.to_string()),
+            Severity::Warning => self.warning(diagnostic.to_string()),
+        }
+    }
+}
+
+/// Ignores everything, for runs whose errors are detected or reported some other way.
+pub struct Silent;
+
+impl DoveOutput for Silent {
+    fn print(&self, _message: String) {}
+    fn warning(&self, _message: String) {}
+    fn error(&self, _message: String) {}
+}
@@ -0,0 +1,158 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+use crate::manifest::{Dependency, Manifest, Project, MANIFEST_NAME, MODULES_DIR};
+
+/// Fetch the package at `source`, a git url or a directory relative to `current_dir`,
+/// and record it as a dependency of the project. Returns the name of the package.
+pub fn add(project: &Project, source: &str, current_dir: &Path) -> Result<String, String> {
+    if source.starts_with('-') {
+        return Err(format!("Invalid package source '{}'.", source));
+    }
+
+    let name = package_name(source)
+        .ok_or_else(|| format!("Cannot find a package name in '{}'.", source))?;
+
+    if project.manifest.dependencies.contains_key(&name) {
+        return Err(format!("Dependency '{}' is already in '{}'.", name, MANIFEST_NAME));
+    }
+
+    let dependency = if is_git_url(source, current_dir) {
+        Dependency { path: None, git: Some(source.to_string()) }
+    } else {
+        // Paths in the manifest are relative to the project root.
+        let path = current_dir.join(source);
+        let path = path.strip_prefix(&project.root).unwrap_or(&path);
+        Dependency { path: Some(path.to_string_lossy().into_owned()), git: None }
+    };
+
+    fetch(project, &name, &dependency)?;
+    record(project, &name, &dependency)?;
+
+    Ok(name)
+}
+
+/// Fetch every dependency of the project that is not installed yet.
+pub fn install(project: &Project) -> Result<Vec<String>, String> {
+    let mut installed = vec![];
+
+    for (name, dependency) in &project.manifest.dependencies {
+        if project.module_dir(name).is_dir() {
+            continue;
+        }
+
+        fetch(project, name, dependency)?;
+        installed.push(name.clone());
+    }
+
+    Ok(installed)
+}
+
+/// Install a dependency into the modules directory of the project.
+fn fetch(project: &Project, name: &str, dependency: &Dependency) -> Result<(), String> {
+    let destination = project.module_dir(name);
+    fs::create_dir_all(project.root.join(MODULES_DIR))
+        .map_err(|error| format!("Cannot create '{}': {}", MODULES_DIR, error))?;
+
+    match (&dependency.git, &dependency.path) {
+        (Some(url), _) => {
+            // Git would take a url starting with `-` as an option.
+            if url.starts_with('-') {
+                return Err(format!("Invalid git url '{}'.", url));
+            }
+
+            let status = Command::new("git")
+                .args(["clone", "--quiet", "--depth", "1", "--", url])
+                .arg(&destination)
+                .status()
+                .map_err(|error| format!("Cannot run git: {}", error))?;
+
+            if !status.success() {
+                return Err(format!("Cannot clone '{}'.", url));
+            }
+            Ok(())
+        },
+        (None, Some(path)) => copy_dir(&project.root.join(path), &destination)
+            .map_err(|error| format!("Cannot copy '{}': {}", path, error)),
+        (None, None) => Err(format!("Dependency '{}' needs a 'git' or 'path' source.", name)),
+    }
+}
+
+/// Add the dependency to the `[dependencies]` table of the manifest file,
+/// keeping the rest of the file as it is.
+fn record(project: &Project, name: &str, dependency: &Dependency) -> Result<(), String> {
+    let manifest_path = project.manifest_path();
+    let content = fs::read_to_string(&manifest_path)
+        .map_err(|error| format!("Error while reading '{}': {}", manifest_path.display(), error))?;
+
+    let (key, value) = match (&dependency.git, &dependency.path) {
+        (Some(git), _) => ("git", git),
+        (None, Some(path)) => ("path", path),
+        (None, None) => return Err(format!("Dependency '{}' needs a 'git' or 'path' source.", name)),
+    };
+    let is_bare_key = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let name_key = if is_bare_key { name.to_string() } else { toml::Value::String(name.to_string()).to_string() };
+    let entry = format!("{} = {{ {} = {} }}", name_key, key, toml::Value::String(value.clone()));
+
+    let mut lines: Vec<&str> = content.lines().collect();
+    match lines.iter().position(|line| line.trim() == "[dependencies]") {
+        Some(header) => lines.insert(header + 1, &entry),
+        None => lines.extend(&["", "[dependencies]", &entry]),
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+
+    if let Err(error) = toml::from_str::<Manifest>(&content) {
+        return Err(format!("Cannot add '{}' to '{}': {}", name, manifest_path.display(), error));
+    }
+
+    fs::write(&manifest_path, content)
+        .map_err(|error| format!("Error while writing '{}': {}", manifest_path.display(), error))
+}
+
+/// Whether `source` is cloned with git: a url with a scheme git supports, an scp-like
+/// `git@host:path`, or an existing local repository ending in `.git`.
+fn is_git_url(source: &str, current_dir: &Path) -> bool {
+    ["https://", "http://", "ssh://", "git://"].iter().any(|prefix| source.starts_with(prefix))
+        || source.strip_prefix("git@").is_some_and(|rest| rest.contains(':'))
+        || (source.ends_with(".git") && current_dir.join(source).is_dir())
+}
+
+/// The last segment of a path or url, without a `.git` suffix.
+fn package_name(source: &str) -> Option<String> {
+    let is_separator = |c| c == '/' || c == '\\';
+    let name = source.trim_end_matches(is_separator)
+        .trim_end_matches(".git")
+        .trim_end_matches(is_separator)
+        .rsplit(|c| is_separator(c) || c == ':')
+        .next()?;
+
+    if name.is_empty() || name == "." || name == ".." {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Recursively copy a package directory, leaving out version control and installed modules.
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        if file_name == ".git" || file_name == MODULES_DIR {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(&file_name))?;
+        } else {
+            fs::copy(entry.path(), to.join(&file_name))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::fs;
+use std::rc::Rc;
+
+use dove_core::{Scanner, Interpreter, Parser, Resolver, DoveOutput, InterpreterOptions};
+use dove_core::constants::keywords::is_identifier;
+use dove_core::symbols::{Symbol, SymbolTable};
+use dove_core::token::Token;
+
+use crate::output::Silent;
+
+/// A position in a file, given as `path:line:column`.
+pub struct Position {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn parse(position: &str) -> Option<Position> {
+        let mut parts = position.rsplitn(3, ':');
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        let path = parts.next()?.to_string();
+        Some(Position { path, line, column })
+    }
+}
+
+/// Rename the variable named `old` at `position` to `new`, everywhere it is declared or used in the file.
+/// Refuses if the new name would change what any other name refers to. Returns the number of renamed names.
+pub fn rename(position: &Position, old: &str, new: &str) -> Result<usize, String> {
+    if !is_identifier(new) {
+        return Err(format!("'{}' is not a valid variable name.", new));
+    }
+
+    let source = fs::read_to_string(&position.path)
+        .map_err(|error| format!("Cannot read '{}': {}", position.path, error))?;
+    let symbols = resolve_symbols(&source)
+        .ok_or_else(|| format!("Cannot rename in '{}', it has errors.", position.path))?;
+
+    let symbol = symbols.symbol_at(position.line, position.column)
+        .ok_or_else(|| format!("There is no variable at {}:{}:{}.", position.path, position.line, position.column))?;
+    if let Some(token) = symbols.tokens_of(symbol).next() {
+        if token.lexeme != old {
+            return Err(format!("The variable at {}:{}:{} is named '{}', not '{}'.", position.path, position.line, position.column, token.lexeme, old));
+        }
+    }
+
+    let tokens: Vec<&Token> = symbols.tokens_of(symbol).collect();
+    let renamed = replace_tokens(&source, &tokens, new);
+
+    // The program must still resolve the same way, e.g. the new name must not shadow or be shadowed.
+    let still_same = resolve_symbols(&renamed)
+        .is_some_and(|renamed_symbols| references(&symbols) == references(&renamed_symbols));
+    if !still_same {
+        return Err(format!("Renaming '{}' to '{}' would change what other names refer to.", old, new));
+    }
+
+    fs::write(&position.path, renamed)
+        .map_err(|error| format!("Cannot write '{}': {}", position.path, error))?;
+    Ok(tokens.len())
+}
+
+fn resolve_symbols(source: &str) -> Option<SymbolTable> {
+    let output = Rc::new(Silent) as Rc<dyn DoveOutput>;
+
+    let tokens = Scanner::new(source.chars().collect(), Rc::clone(&output)).try_scan_tokens()?;
+    let mut parser = Parser::new(tokens, Rc::clone(&output));
+    let statements = parser.program();
+    if parser.had_error() {
+        return None;
+    }
+
+    let mut interpreter = Interpreter::new(Rc::clone(&output), InterpreterOptions::default());
+    let mut resolver = Resolver::new(&mut interpreter, output).collect_symbols();
+    resolver.resolve(&statements);
+    if resolver.had_error() {
+        return None;
+    }
+    resolver.symbols()
+}
+
+/// For each occurrence, the index of the first occurrence of the same symbol. Equal for two tables
+/// if their names are grouped into symbols the same way, whatever the names are.
+fn references(symbols: &SymbolTable) -> Vec<usize> {
+    let mut first_occurrences: HashMap<&Symbol, usize> = HashMap::new();
+    symbols.occurrences().iter()
+        .enumerate()
+        .map(|(index, (_, symbol))| *first_occurrences.entry(symbol).or_insert(index))
+        .collect()
+}
+
+/// Replace the lexemes of the tokens in the source with `name`.
+fn replace_tokens(source: &str, tokens: &[&Token], name: &str) -> String {
+    let mut ranges: Vec<_> = tokens.iter().map(|token| token.span().range()).collect();
+
+    // Replace from the end, so earlier offsets stay valid.
+    ranges.sort_by_key(|range| range.start);
+    let mut renamed = source.to_string();
+    for range in ranges.into_iter().rev() {
+        renamed.replace_range(range, name);
+    }
+    renamed
+}
@@ -1,5 +1,5 @@
 use crate::token::{Token, Literals};
-use crate::ast::Stmt;
+use crate::ast::{Param, Stmt};
 
 #[derive(Debug, Clone)]
 pub enum Expr {
@@ -7,18 +7,36 @@ pub enum Expr {
     Assign     (Token, Token, Box<Expr>),
     Binary     (Box<Expr>, Token, Box<Expr>),
     Call       (Box<Expr>, Token, Vec<Expr>),
-    Dictionary (Vec<(Expr, Expr)>),
+    Dictionary (Token, Vec<(Expr, Expr)>),
     Get        (Box<Expr>, Token),
     Grouping   (Box<Expr>),
     IfExpr     (Box<Expr>, Box<Stmt>, Box<Stmt>),
-    IndexGet   (Box<Expr>, Box<Expr>),
-    IndexSet   (Box<Expr>, Box<Expr>, Box<Expr>),
-    Lambda     (Vec<Token>, Box<Stmt>),
+    IndexGet   (Box<Expr>, Token, Box<Expr>),
+    IndexSet   (Box<Expr>, Token, Box<Expr>, Token, Box<Expr>),
+    Lambda     (Token, Vec<Param>, Box<Stmt>),
     Literal    (Literals),
-    Set        (Box<Expr>, Token, Box<Expr>),
+    Range      (Box<Expr>, Token, Box<Expr>, Option<Box<Expr>>),
+    Set        (Box<Expr>, Token, Token, Box<Expr>),
     SelfExpr   (Token),
     SuperExpr  (Token, Token),
     Tuple      (Vec<Expr>),
     Unary      (Token, Box<Expr>),
     Variable   (Token),
 }
+
+impl Expr {
+    /// The line of the first token found in this expression, if any.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Expr::Assign(token, _, _) | Expr::SelfExpr(token) | Expr::SuperExpr(token, _) |
+            Expr::Unary(token, _) | Expr::Variable(token) | Expr::Dictionary(token, _) => Some(token.line),
+            Expr::Binary(left, token, _) | Expr::Call(left, token, _) | Expr::Get(left, token) |
+            Expr::IndexGet(left, token, _) | Expr::IndexSet(left, token, _, _, _) |
+            Expr::Range(left, token, _, _) | Expr::Set(left, token, _, _) => left.line().or(Some(token.line)),
+            Expr::Array(exprs) | Expr::Tuple(exprs) => exprs.iter().find_map(Expr::line),
+            Expr::Grouping(expr) | Expr::IfExpr(expr, _, _) => expr.line(),
+            Expr::Lambda(keyword, _, _) => Some(keyword.line),
+            Expr::Literal(_) => None,
+        }
+    }
+}
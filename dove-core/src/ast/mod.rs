@@ -1,7 +1,11 @@
 mod expr;
+mod pattern;
+mod printer;
 mod stmt;
 mod visitor;
 
 pub use expr::*;
+pub use pattern::*;
+pub use printer::to_source;
 pub use stmt::*;
 pub use visitor::*;
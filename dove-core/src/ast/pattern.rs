@@ -0,0 +1,61 @@
+use crate::ast::{Expr, Stmt};
+use crate::token::{DictKey, Literals, Token};
+
+/// A pattern of a `match` case, or of a `let` statement or `for` loop unpacking a value.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// `_`, matches any value without binding it.
+    Wildcard,
+    /// A name, matches any value and binds it to the name.
+    Binding     (Token),
+    /// A number, string, boolean or nil, matches values equal to it.
+    Literal     (Literals),
+    /// `(a, b)`, matches tuples with as many elements.
+    Tuple       (Vec<Pattern>),
+    /// `[a, b]`, matches arrays with as many elements.
+    Array       (Vec<Pattern>),
+    /// `...name` or `name...`, the last element of a tuple or array pattern, matches the remaining elements
+    /// and binds them to the name as a tuple or array. Without a name, written `...`, they are ignored.
+    Rest        (Option<Token>),
+    /// `{key: a}`, matches dictionaries with these keys. In `match`, also no other keys unless there is a rest pattern.
+    /// `{name}` is short for `{name: name}`.
+    Dictionary  (Vec<(DictKey, Pattern)>, DictRest),
+}
+
+/// What a dictionary pattern does with the entries it does not name.
+#[derive(Debug, Clone)]
+pub enum DictRest {
+    /// No rest pattern, other entries fail the match.
+    None,
+    /// `...`, other entries are allowed.
+    Ignored,
+    /// `...name`, other entries are bound to a new dictionary.
+    Bound(Token),
+}
+
+impl Pattern {
+    /// The names bound by the pattern, in order.
+    pub fn bindings(&self) -> Vec<&Token> {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) => vec![],
+            Pattern::Binding(name) | Pattern::Rest(Some(name)) => vec![name],
+            Pattern::Rest(None) => vec![],
+            Pattern::Tuple(patterns) | Pattern::Array(patterns) => patterns.iter().flat_map(Pattern::bindings).collect(),
+            Pattern::Dictionary(entries, rest) => {
+                let mut names: Vec<&Token> = entries.iter().flat_map(|(_, pattern)| pattern.bindings()).collect();
+                if let DictRest::Bound(name) = rest {
+                    names.push(name);
+                }
+                names
+            },
+        }
+    }
+}
+
+/// `case pattern if guard -> body` in a `match` statement, the guard is optional.
+#[derive(Debug, Clone)]
+pub struct MatchCase {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Stmt,
+}
@@ -0,0 +1,361 @@
+use crate::ast::{DictRest, Expr, ImportTarget, MatchCase, Param, Pattern, Stmt};
+use crate::constants::keywords::is_identifier;
+use crate::interpreter::stringify;
+use crate::token::{DictKey, Literals, Token, TokenType};
+
+const INDENT: &str = "    ";
+
+/// Turn statements back into Dove source, which parses into the same statements.
+///
+/// Comments and blank lines are not part of the syntax tree, so they are lost, and expressions are
+/// only parenthesized where precedence requires it. A version pragma is not part of it either,
+/// so names that are keywords in the current version must not be used.
+pub fn to_source(statements: &[Stmt]) -> String {
+    statements.iter()
+        .map(|stmt| format!("{}\n", stmt_source(stmt, 0)))
+        .collect()
+}
+
+fn stmt_source(stmt: &Stmt, indent: usize) -> String {
+    match stmt {
+        Stmt::Block(statements) => block_source(statements, indent),
+        Stmt::Break(_, label) => format!("break{}", label_source(label)),
+        Stmt::Continue(_, label) => format!("continue{}", label_source(label)),
+        Stmt::Delete(_, target) => format!("del {}", expr_source(target, Precedence::Call, indent)),
+        Stmt::Class(name, superclass, members) => {
+            let superclass = match superclass {
+                Some(superclass) => format!(" from {}", superclass.lexeme),
+                None => "".to_string(),
+            };
+            format!("class {}{} {}", name.lexeme, superclass, block_source(members, indent))
+        },
+        Stmt::Expression(expr) => expr_source(expr, Precedence::Assignment, indent),
+        Stmt::For(pattern, iterable, body, label) => format!(
+            "{}for {} in {} {}", loop_label_source(label), pattern_source(pattern), expr_source(iterable, Precedence::Or, indent), stmt_source(body, indent),
+        ),
+        Stmt::Function(name, params, body) => format!("fun {}({}) {}", name.lexeme, params_source(params, indent), stmt_source(body, indent)),
+        Stmt::Import(_, path, target) => {
+            let path = literal_source(path.literal.as_ref().unwrap_or(&Literals::Nil));
+            match target {
+                ImportTarget::Globals => format!("import {}", path),
+                ImportTarget::Module(name) => format!("import {} as {}", path, name.lexeme),
+                ImportTarget::Names(names) => {
+                    let names: Vec<&str> = names.iter().map(|name| name.lexeme.as_str()).collect();
+                    format!("from {} import {}", path, names.join(", "))
+                },
+            }
+        },
+        Stmt::Match(_, value, cases) => {
+            let cases: Vec<String> = cases.iter().map(|case| case_source(case, indent + 1)).collect();
+            format!("match {} {}", expr_source(value, Precedence::Or, indent), lines_source(&cases, indent))
+        },
+        Stmt::Print(_, expr) => format!("print {}", expr_source(expr, Precedence::Assignment, indent)),
+        Stmt::Return(_, None) => "return".to_string(),
+        Stmt::Return(_, Some(expr)) => format!("return {}", expr_source(expr, Precedence::Assignment, indent)),
+        Stmt::Switch(_, value, cases, else_block) => {
+            let mut lines: Vec<String> = cases.iter()
+                .map(|(values, body)| {
+                    let values: Vec<String> = values.iter().map(|value| expr_source(value, Precedence::Or, indent + 1)).collect();
+                    format!("case {} {}", values.join(", "), stmt_source(body, indent + 1))
+                })
+                .collect();
+            if let Some(else_block) = else_block {
+                lines.push(format!("else {}", stmt_source(else_block, indent + 1)));
+            }
+            format!("switch {} {}", expr_source(value, Precedence::Or, indent), lines_source(&lines, indent))
+        },
+        Stmt::Variable(pattern, None) => format!("let {}", pattern_source(pattern)),
+        Stmt::Variable(pattern, Some(value)) => format!("let {} = {}", pattern_source(pattern), expr_source(value, Precedence::Assignment, indent)),
+        Stmt::While(condition, body, label) => format!("{}while {} {}", loop_label_source(label), expr_source(condition, Precedence::Assignment, indent), stmt_source(body, indent)),
+        Stmt::Try(_, body, name, handler) => {
+            format!("try {} catch {} {}", stmt_source(body, indent), name.lexeme, stmt_source(handler, indent))
+        },
+        Stmt::With(_, resource, name, body) => {
+            let name = match name {
+                Some(name) => format!(" as {}", name.lexeme),
+                None => "".to_string(),
+            };
+            format!("with {}{} {}", expr_source(resource, Precedence::Or, indent), name, stmt_source(body, indent))
+        },
+    }
+}
+
+fn block_source(statements: &[Stmt], indent: usize) -> String {
+    let lines: Vec<String> = statements.iter().map(|stmt| stmt_source(stmt, indent + 1)).collect();
+    lines_source(&lines, indent)
+}
+
+/// Lines between braces, indented one level deeper than `indent`.
+fn lines_source(lines: &[String], indent: usize) -> String {
+    if lines.is_empty() {
+        return "{}".to_string();
+    }
+
+    let mut source = "{\n".to_string();
+    for line in lines {
+        source += &format!("{}{}\n", INDENT.repeat(indent + 1), line);
+    }
+    source + &INDENT.repeat(indent) + "}"
+}
+
+fn case_source(case: &MatchCase, indent: usize) -> String {
+    let guard = match &case.guard {
+        Some(guard) => format!(" if {}", expr_source(guard, Precedence::Or, indent)),
+        None => "".to_string(),
+    };
+    format!("case {}{} -> {}", pattern_source(&case.pattern), guard, arrow_body_source(&case.body, indent))
+}
+
+/// The body after `->` in a lambda or a match case. A single statement is written without braces,
+/// unless it starts with a brace itself.
+fn arrow_body_source(body: &Stmt, indent: usize) -> String {
+    if let Stmt::Block(statements) = body {
+        if let [statement] = statements.as_slice() {
+            let source = stmt_source(statement, indent);
+            if !source.starts_with('{') {
+                return source;
+            }
+        }
+    }
+    stmt_source(body, indent)
+}
+
+fn pattern_source(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Wildcard => "_".to_string(),
+        Pattern::Binding(name) => name.lexeme.clone(),
+        Pattern::Literal(literal) => literal_source(literal),
+        Pattern::Tuple(patterns) if patterns.len() == 1 => format!("({},)", pattern_source(&patterns[0])),
+        Pattern::Tuple(patterns) => format!("({})", patterns_source(patterns)),
+        Pattern::Array(patterns) => format!("[{}]", patterns_source(patterns)),
+        Pattern::Rest(Some(name)) => format!("...{}", name.lexeme),
+        Pattern::Rest(None) => "...".to_string(),
+        Pattern::Dictionary(entries, rest) => {
+            let mut parts: Vec<String> = entries.iter()
+                .map(|(key, pattern)| match (key, pattern) {
+                    // `{name}` is short for `{name: name}`.
+                    (DictKey::StringKey(key), Pattern::Binding(name)) if *key == name.lexeme => key.clone(),
+                    (DictKey::StringKey(name), _) if is_identifier(name) => format!("{}: {}", name, pattern_source(pattern)),
+                    _ => format!("{}: {}", key.stringify(), pattern_source(pattern)),
+                })
+                .collect();
+            match rest {
+                DictRest::None => {},
+                DictRest::Ignored => parts.push("...".to_string()),
+                DictRest::Bound(name) => parts.push(format!("...{}", name.lexeme)),
+            }
+            format!("{{{}}}", parts.join(", "))
+        },
+    }
+}
+
+fn patterns_source(patterns: &[Pattern]) -> String {
+    patterns.iter().map(pattern_source).collect::<Vec<_>>().join(", ")
+}
+
+/// How tightly expressions bind, following the parser from the loosest to the tightest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Assignment,
+    Lambda,
+    If,
+    Or,
+    And,
+    Equality,
+    Comparison,
+    Range,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
+    Addition,
+    Multiplication,
+    Unary,
+    Power,
+    Call,
+    Primary,
+}
+
+impl Precedence {
+    fn of_operator(operator: &Token) -> Precedence {
+        match operator.token_type {
+            TokenType::OR => Precedence::Or,
+            TokenType::AND => Precedence::And,
+            TokenType::EQUAL_EQUAL | TokenType::BANG_EQUAL => Precedence::Equality,
+            TokenType::LESS | TokenType::LESS_EQUAL | TokenType::GREATER | TokenType::GREATER_EQUAL => Precedence::Comparison,
+            TokenType::PIPE => Precedence::BitOr,
+            TokenType::CARET => Precedence::BitXor,
+            TokenType::AMPERSAND => Precedence::BitAnd,
+            TokenType::LESS_LESS | TokenType::GREATER_GREATER => Precedence::Shift,
+            TokenType::PLUS | TokenType::MINUS => Precedence::Addition,
+            TokenType::STAR_STAR => Precedence::Power,
+            _ => Precedence::Multiplication,
+        }
+    }
+
+    fn of_expr(expr: &Expr) -> Precedence {
+        match expr {
+            Expr::Assign(_, _, _) | Expr::IndexSet(_, _, _, _, _) | Expr::Set(_, _, _, _) => Precedence::Assignment,
+            Expr::Lambda(_, _, _) => Precedence::Lambda,
+            Expr::IfExpr(_, _, _) => Precedence::If,
+            Expr::Binary(_, operator, _) => Precedence::of_operator(operator),
+            Expr::Range(_, _, _, _) => Precedence::Range,
+            Expr::Unary(_, _) => Precedence::Unary,
+            Expr::Call(_, _, _) | Expr::Get(_, _) | Expr::IndexGet(_, _, _) => Precedence::Call,
+            // Negative numbers are written as a unary minus.
+            Expr::Literal(Literals::Number(n)) if n.is_sign_negative() => Precedence::Unary,
+            Expr::Literal(Literals::Int(n)) if *n < 0 => Precedence::Unary,
+            _ => Precedence::Primary,
+        }
+    }
+
+    fn next(self) -> Precedence {
+        match self {
+            Precedence::Assignment => Precedence::Lambda,
+            Precedence::Lambda => Precedence::If,
+            Precedence::If => Precedence::Or,
+            Precedence::Or => Precedence::And,
+            Precedence::And => Precedence::Equality,
+            Precedence::Equality => Precedence::Comparison,
+            Precedence::Comparison => Precedence::Range,
+            Precedence::Range => Precedence::BitOr,
+            Precedence::BitOr => Precedence::BitXor,
+            Precedence::BitXor => Precedence::BitAnd,
+            Precedence::BitAnd => Precedence::Shift,
+            Precedence::Shift => Precedence::Addition,
+            Precedence::Addition => Precedence::Multiplication,
+            Precedence::Multiplication => Precedence::Unary,
+            Precedence::Unary => Precedence::Power,
+            Precedence::Power => Precedence::Call,
+            Precedence::Call | Precedence::Primary => Precedence::Primary,
+        }
+    }
+}
+
+/// The source of an expression, parenthesized if it binds looser than `min` requires.
+fn expr_source(expr: &Expr, min: Precedence, indent: usize) -> String {
+    let source = match expr {
+        Expr::Array(items) => format!("[{}]", exprs_source(items, indent)),
+        Expr::Assign(name, operator, value) => assignment_source(name.lexeme.clone(), operator, value, indent),
+        Expr::Binary(left, operator, right) => {
+            let precedence = Precedence::of_operator(operator);
+            // `**` is right-associative, and its right operand may be a unary expression.
+            let (left_min, right_min) = match precedence {
+                Precedence::Power => (Precedence::Call, Precedence::Unary),
+                _ => (precedence, precedence.next()),
+            };
+            format!(
+                "{} {} {}",
+                expr_source(left, left_min, indent), operator.lexeme, expr_source(right, right_min, indent),
+            )
+        },
+        Expr::Call(callee, _, arguments) => format!("{}({})", expr_source(callee, Precedence::Call, indent), exprs_source(arguments, indent)),
+        Expr::Dictionary(_, pairs) => {
+            let pairs: Vec<String> = pairs.iter()
+                .map(|(key, value)| format!("{}: {}", expr_source(key, Precedence::Assignment, indent), expr_source(value, Precedence::Assignment, indent)))
+                .collect();
+            format!("{{{}}}", pairs.join(", "))
+        },
+        Expr::Get(object, name) => format!("{}.{}", expr_source(object, Precedence::Call, indent), name.lexeme),
+        Expr::Grouping(expr) => format!("({})", expr_source(expr, Precedence::Assignment, indent)),
+        Expr::IfExpr(condition, then_branch, else_branch) => {
+            let mut source = format!("if {} {}", expr_source(condition, Precedence::Or, indent), stmt_source(then_branch, indent));
+            match &**else_branch {
+                Stmt::Block(statements) if statements.is_empty() => {},
+                Stmt::Expression(else_if @ Expr::IfExpr(_, _, _)) => source += &format!(" else {}", expr_source(else_if, Precedence::If, indent)),
+                else_branch => source += &format!(" else {}", stmt_source(else_branch, indent)),
+            }
+            source
+        },
+        Expr::IndexGet(object, _, index) => format!("{}[{}]", expr_source(object, Precedence::Call, indent), expr_source(index, Precedence::Assignment, indent)),
+        Expr::IndexSet(object, _, index, operator, value) => {
+            let target = format!("{}[{}]", expr_source(object, Precedence::Call, indent), expr_source(index, Precedence::Assignment, indent));
+            assignment_source(target, operator, value, indent)
+        },
+        Expr::Lambda(_, params, body) if params.is_empty() => format!("lambda -> {}", arrow_body_source(body, indent)),
+        Expr::Lambda(_, params, body) => format!("lambda {} -> {}", params_source(params, indent), arrow_body_source(body, indent)),
+        Expr::Literal(literal) => literal_source(literal),
+        Expr::Range(start, operator, end, step) => {
+            let mut source = format!(
+                "{}{}{}",
+                expr_source(start, Precedence::BitOr, indent), operator.lexeme, expr_source(end, Precedence::BitOr, indent),
+            );
+            if let Some(step) = step {
+                source += &format!(" by {}", expr_source(step, Precedence::BitOr, indent));
+            }
+            source
+        },
+        Expr::Set(object, name, operator, value) => {
+            assignment_source(format!("{}.{}", expr_source(object, Precedence::Call, indent), name.lexeme), operator, value, indent)
+        },
+        Expr::SelfExpr(_) => "self".to_string(),
+        Expr::SuperExpr(_, method) => format!("super.{}", method.lexeme),
+        Expr::Tuple(items) if items.len() == 1 => format!("({},)", expr_source(&items[0], Precedence::Assignment, indent)),
+        Expr::Tuple(items) => format!("({})", exprs_source(items, indent)),
+        Expr::Unary(operator, right) => {
+            // `not` is a word, the other operators are written right before the operand.
+            let separator = if operator.token_type == TokenType::NOT { " " } else { "" };
+            let mut operand = expr_source(right, Precedence::Unary, indent);
+            // `- -x` must not become `--x`, which is a decrement.
+            if operator.token_type == TokenType::MINUS && operand.starts_with('-') {
+                operand = format!("({})", operand);
+            }
+            format!("{}{}{}", operator.lexeme, separator, operand)
+        },
+        Expr::Variable(name) => name.lexeme.clone(),
+    };
+
+    if Precedence::of_expr(expr) < min {
+        format!("({})", source)
+    } else {
+        source
+    }
+}
+
+/// `target = value`, `target += value` or `target++`, where the value is the implicit 1.
+fn assignment_source(target: String, operator: &Token, value: &Expr, indent: usize) -> String {
+    match operator.token_type {
+        TokenType::PLUS_PLUS | TokenType::MINUS_MINUS => format!("{}{}", target, operator.lexeme),
+        _ => format!("{} {} {}", target, operator.lexeme, expr_source(value, Precedence::Assignment, indent)),
+    }
+}
+
+fn exprs_source(exprs: &[Expr], indent: usize) -> String {
+    exprs.iter().map(|expr| expr_source(expr, Precedence::Assignment, indent)).collect::<Vec<_>>().join(", ")
+}
+
+fn literal_source(literal: &Literals) -> String {
+    match literal {
+        Literals::String(s) => format!("\"{}\"", s),
+        // Whole numbers keep their fractional part, so they are not read back as integers.
+        Literals::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => format!("{:.1}", n),
+        other => stringify(other.clone()),
+    }
+}
+
+fn params_source(params: &[Param], indent: usize) -> String {
+    params.iter()
+        .map(|param| match &param.default {
+            None if param.rest => format!("...{}", param.name.lexeme),
+            Some(default) => format!("{} = {}", param.name.lexeme, expr_source(default, Precedence::If, indent)),
+            None => param.name.lexeme.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The label after `break` or `continue`, e.g. ` outer`.
+fn label_source(label: &Option<Token>) -> String {
+    match label {
+        Some(label) => format!(" {}", label.lexeme),
+        None => "".to_string(),
+    }
+}
+
+/// The label before a loop, e.g. `outer: `.
+fn loop_label_source(label: &Option<Token>) -> String {
+    match label {
+        Some(label) => format!("{}: ", label.lexeme),
+        None => "".to_string(),
+    }
+}
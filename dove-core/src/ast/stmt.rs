@@ -1,17 +1,83 @@
-use crate::ast::Expr;
-use crate::token::Token;
+use crate::ast::{Expr, MatchCase, Pattern};
+use crate::token::{Literals, Token};
 
 #[derive(Debug, Clone)]
 pub enum Stmt {
     Block       (Vec<Stmt>),
-    Break       (Token),
-    Continue    (Token),
+    /// `break` or `break label`, which stops the enclosing loop with that label.
+    Break       (Token, Option<Token>),
+    Continue    (Token, Option<Token>),
+    /// `del name` or `del value[index]`.
+    Delete      (Token, Expr),
+    /// `class Name from Superclass {...}`, whose members are field declarations (`let`) and methods (`fun`),
+    /// after an optional docstring.
     Class       (Token, Option<Token>, Vec<Stmt>),
     Expression  (Expr),
-    For         (Token, Expr, Box<Stmt>),
-    Function    (Token, Vec<Token>, Box<Stmt>),
+    /// `for name in iterable {...}` or `for (key, value) in dict {...}`, unpacking each item with a pattern,
+    /// with the label of the loop, e.g. `outer: for ...`.
+    For         (Pattern, Expr, Box<Stmt>, Option<Token>),
+    Function    (Token, Vec<Param>, Box<Stmt>),
+    /// `import "path"`, `import "path" as name` or `from "path" import a, b`.
+    Import      (Token, Token, ImportTarget),
+    /// `match value { case pattern -> ... }`, only the first matching case runs.
+    Match       (Token, Expr, Vec<MatchCase>),
     Print       (Token, Expr),
     Return      (Token, Option<Expr>),
-    Variable    (Token, Option<Expr>),
-    While       (Expr, Box<Stmt>),
+    /// `switch value { case a, b {...} else {...} }`, cases are compared by equality and never fall through.
+    Switch      (Token, Expr, Vec<(Vec<Expr>, Stmt)>, Option<Box<Stmt>>),
+    /// `try {...} catch name {...}`, the name is bound to a dictionary with the error's `message` and `line`.
+    Try         (Token, Box<Stmt>, Token, Box<Stmt>),
+    /// `let name = value`, or `let (a, b) = value` unpacking the value with a pattern.
+    Variable    (Pattern, Option<Expr>),
+    While       (Expr, Box<Stmt>, Option<Token>),
+    /// `with resource as name { ... }`, the name is optional.
+    With        (Token, Expr, Option<Token>, Box<Stmt>),
+}
+
+/// The docstring of a function body or class, a string literal written as its first statement or member,
+/// e.g. `fun area(r) { "The area of a circle." ... }`.
+pub fn docstring(statements: &[Stmt]) -> Option<&str> {
+    match statements.first() {
+        Some(Stmt::Expression(Expr::Literal(Literals::String(doc)))) => Some(doc),
+        _ => None,
+    }
+}
+
+/// A parameter of a function or lambda, e.g. `greeting = "hi"` or `...args`.
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: Token,
+    /// Used when the argument is left out, evaluated in the closure of the function on each call.
+    pub default: Option<Expr>,
+    /// Whether this is a rest parameter, bound to an array of the remaining arguments.
+    /// Only the last parameter can be one, and it has no default.
+    pub rest: bool,
+}
+
+/// Where the definitions of an imported file go.
+#[derive(Debug, Clone)]
+pub enum ImportTarget {
+    /// The global scope of the importing file, for `import "path"`.
+    Globals,
+    /// A module bound to the name, for `import "path" as name`.
+    Module(Token),
+    /// The named definitions of the module, for `from "path" import a, b`.
+    Names(Vec<Token>),
+}
+
+impl Stmt {
+    /// The line of the first token found in this statement, if any.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Stmt::Break(token, _) | Stmt::Continue(token, _) | Stmt::Class(token, _, _) | Stmt::Delete(token, _) |
+            Stmt::Function(token, _, _) | Stmt::Import(token, _, _) | Stmt::Match(token, _, _) | Stmt::Print(token, _) |
+            Stmt::Return(token, _) | Stmt::Switch(token, _, _, _) | Stmt::Try(token, _, _, _) | Stmt::With(token, _, _, _) => Some(token.line),
+            Stmt::Variable(pattern, value) => pattern.bindings().first().map(|name| name.line)
+                .or_else(|| value.as_ref().and_then(Expr::line)),
+            Stmt::For(pattern, iterable, _, _) => pattern.bindings().first().map(|name| name.line)
+                .or_else(|| iterable.line()),
+            Stmt::Block(statements) => statements.iter().find_map(Stmt::line),
+            Stmt::Expression(expr) | Stmt::While(expr, _, _) => expr.line(),
+        }
+    }
 }
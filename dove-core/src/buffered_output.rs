@@ -0,0 +1,88 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::dove_output::DoveOutput;
+use crate::error_handler::Diagnostic;
+
+enum Message {
+    Print(String),
+    Warning(String),
+    Error(String),
+    Diagnostic(Diagnostic),
+}
+
+/// Collects output until `flush` passes it on to another output, for hosts that show output
+/// all at once or cannot afford unbounded memory, e.g. the web playground.
+///
+/// With a limit, printed text beyond `max_bytes` until the next flush is dropped and the running script
+/// stops with an error `try` cannot catch, so a program printing in an endless loop stops.
+/// Errors and warnings are always kept.
+pub struct BufferedOutput {
+    inner: Rc<dyn DoveOutput>,
+    messages: RefCell<Vec<Message>>,
+    /// Bytes printed since the last flush.
+    printed_bytes: Cell<usize>,
+    max_bytes: Option<usize>,
+}
+
+impl BufferedOutput {
+    pub fn new(inner: Rc<dyn DoveOutput>, max_bytes: Option<usize>) -> BufferedOutput {
+        BufferedOutput {
+            inner,
+            messages: RefCell::new(vec![]),
+            printed_bytes: Cell::new(0),
+            max_bytes,
+        }
+    }
+
+    /// Pass the collected output on, in the order it was written.
+    pub fn flush(&self) {
+        let messages = self.messages.replace(vec![]);
+        self.printed_bytes.set(0);
+        for message in messages {
+            match message {
+                Message::Print(message) => self.inner.print(message),
+                Message::Warning(message) => self.inner.warning(message),
+                Message::Error(message) => self.inner.error(message),
+                Message::Diagnostic(diagnostic) => self.inner.diagnostic(diagnostic),
+            }
+        }
+    }
+
+    /// Bytes printed since the last flush, including ones dropped because of the limit.
+    pub fn printed_bytes(&self) -> usize {
+        self.printed_bytes.get()
+    }
+}
+
+impl DoveOutput for BufferedOutput {
+    fn print(&self, message: String) {
+        let printed_bytes = self.printed_bytes.get() + message.len();
+        self.printed_bytes.set(printed_bytes);
+
+        match self.max_bytes {
+            Some(max_bytes) if printed_bytes > max_bytes => {},
+            _ => self.messages.borrow_mut().push(Message::Print(message)),
+        }
+    }
+
+    fn warning(&self, message: String) {
+        self.messages.borrow_mut().push(Message::Warning(message));
+    }
+
+    fn error(&self, message: String) {
+        self.messages.borrow_mut().push(Message::Error(message));
+    }
+
+    fn diagnostic(&self, diagnostic: Diagnostic) {
+        self.messages.borrow_mut().push(Message::Diagnostic(diagnostic));
+    }
+
+    fn repl_source(&self, source: &str) {
+        self.inner.repl_source(source);
+    }
+
+    fn is_full(&self) -> bool {
+        matches!(self.max_bytes, Some(max_bytes) if self.printed_bytes.get() > max_bytes)
+    }
+}
@@ -0,0 +1,58 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::builtins::*;
+use crate::dove_callable::{Arity, BuiltinFunction};
+use crate::data_types::heap::Heap;
+
+pub fn define(definer: &mut Definer) {
+    definer.function("deque", deque());
+    definer.function("freeze", freeze());
+    definer.function("heap", heap());
+}
+
+/// A read-only view of an array or dictionary. Values that cannot change, such as numbers,
+/// strings and tuples, are returned as they are.
+fn freeze() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        match &args[0] {
+            Literals::Deque(_) | Literals::Heap(_) | Literals::Instance(_) => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Cannot freeze type '{}'.", args[0].to_string()),
+            )),
+            value => Ok(value.clone().freeze()),
+        }
+    })
+}
+
+/// Collect the items of the optional iterable argument of a constructor.
+fn initial_items(args: &[Literals]) -> Result<Vec<Literals>, RuntimeError> {
+    match args.first() {
+        Some(value) => iterable_items(value).ok_or_else(|| RuntimeError::new(
+            ErrorLocation::Unspecified,
+            format!("Expected an iterable but got '{}'.", value.to_string()),
+        )),
+        None => Ok(vec![]),
+    }
+}
+
+fn deque() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(0, 1), |_, args| {
+        let items: VecDeque<Literals> = initial_items(args)?.into_iter().collect();
+        Ok(Literals::Deque(Rc::new(RefCell::new(items))))
+    })
+}
+
+fn heap() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(0, 1), |_, args| {
+        let mut heap = Heap::default();
+        for item in initial_items(args)? {
+            if let Err(message) = heap.push(item.clone(), item) {
+                return Err(RuntimeError::new(ErrorLocation::Unspecified, message));
+            }
+        }
+
+        Ok(Literals::Heap(Rc::new(RefCell::new(heap))))
+    })
+}
@@ -0,0 +1,47 @@
+use crate::builtins::*;
+use crate::dove_callable::{Arity, BuiltinFunction};
+use crate::interpreter::stringify;
+
+pub fn define(definer: &mut Definer) {
+    definer.function("num", num());
+    definer.function("str", str());
+    definer.function("bool", bool());
+}
+
+/// `num(value)` errors if the value cannot be converted,
+/// `num(value, fallback)` returns the fallback (e.g. nil) instead.
+fn num() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(1, 2), |_, args| {
+        let number = match &args[0] {
+            Literals::Number(n) => Some(*n),
+            Literals::Int(n) => Some(*n as f64),
+            Literals::Boolean(b) => Some(if *b { 1.0 } else { 0.0 }),
+            Literals::String(s) => s.trim().parse::<f64>().ok(),
+            _ => None,
+        };
+
+        match (number, args.get(1)) {
+            (Some(n), _) => Ok(Literals::Number(n)),
+            (None, Some(fallback)) => Ok(fallback.clone()),
+            (None, None) => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Cannot convert {} to a number.", stringify(args[0].clone())),
+            )),
+        }
+    })
+}
+
+fn str() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        match &args[0] {
+            Literals::String(s) => Ok(Literals::String(s.clone())),
+            other => Ok(Literals::String(stringify(other.clone()).into())),
+        }
+    })
+}
+
+fn bool() -> impl DoveCallable {
+    BuiltinFunction::new(1, |interpreter, args| {
+        Ok(Literals::Boolean(interpreter.truthy(&args[0], ErrorLocation::Unspecified)?))
+    })
+}
@@ -0,0 +1,147 @@
+use std::rc::Rc;
+
+use crate::builtins::*;
+use crate::dove_callable::{Arity, BuiltinFunction};
+
+pub fn define(definer: &mut Definer) {
+    definer.function("bytes", bytes());
+    definer.function("encode", encode());
+    definer.function("decode", decode());
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Encoding {
+    Utf8,
+    Latin1,
+    /// Little endian, with a byte order mark when encoding.
+    Utf16,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Encoding {
+    fn from_name(name: &str) -> Option<Encoding> {
+        match name.to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Some(Encoding::Utf8),
+            "latin-1" | "latin1" | "iso-8859-1" => Some(Encoding::Latin1),
+            "utf-16" | "utf16" => Some(Encoding::Utf16),
+            "utf-16le" => Some(Encoding::Utf16Le),
+            "utf-16be" => Some(Encoding::Utf16Be),
+            _ => None,
+        }
+    }
+
+    fn encode(self, string: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Encoding::Utf8 => Ok(string.as_bytes().to_vec()),
+            Encoding::Latin1 => string.chars()
+                .map(|c| if (c as u32) < 256 { Ok(c as u8) } else { Err(format!("Cannot encode '{}' as latin-1.", c)) })
+                .collect(),
+            Encoding::Utf16 => Ok([0xFF, 0xFE].iter().copied()
+                .chain(string.encode_utf16().flat_map(u16::to_le_bytes))
+                .collect()),
+            Encoding::Utf16Le => Ok(string.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+            Encoding::Utf16Be => Ok(string.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> Result<String, String> {
+        match self {
+            Encoding::Utf8 => String::from_utf8(bytes.to_vec()).map_err(|_| "Invalid utf-8 data.".to_string()),
+            Encoding::Latin1 => Ok(bytes.iter().map(|byte| *byte as char).collect()),
+            Encoding::Utf16 => match bytes {
+                [0xFF, 0xFE, rest @ ..] => Encoding::Utf16Le.decode(rest),
+                [0xFE, 0xFF, rest @ ..] => Encoding::Utf16Be.decode(rest),
+                _ => Encoding::Utf16Le.decode(bytes),
+            },
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let pairs = bytes.chunks_exact(2);
+                if !pairs.remainder().is_empty() {
+                    return Err("Invalid utf-16 data, odd number of bytes.".to_string());
+                }
+
+                let units: Vec<u16> = pairs
+                    .map(|pair| if self == Encoding::Utf16Le {
+                        u16::from_le_bytes([pair[0], pair[1]])
+                    } else {
+                        u16::from_be_bytes([pair[0], pair[1]])
+                    })
+                    .collect();
+                String::from_utf16(&units).map_err(|_| "Invalid utf-16 data.".to_string())
+            },
+        }
+    }
+}
+
+/// The encoding named by the optional argument at `index`, utf-8 by default.
+fn encoding_arg(args: &[Literals], index: usize) -> Result<Encoding, RuntimeError> {
+    let error = |message| RuntimeError::new(ErrorLocation::Unspecified, message);
+
+    match args.get(index) {
+        Some(Literals::String(name)) => Encoding::from_name(name)
+            .ok_or_else(|| error(format!("Unknown encoding '{}'.", name))),
+        Some(other) => Err(error(format!("Expected an encoding name but got '{}'.", other.to_string()))),
+        None => Ok(Encoding::Utf8),
+    }
+}
+
+/// `bytes(array)` creates bytes from numbers between 0 and 255.
+fn bytes() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        let items = match iterable_items(&args[0]) {
+            Some(items) => items,
+            None => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected an iterable but got '{}'.", args[0].to_string()),
+            )),
+        };
+
+        let bytes = items.into_iter()
+            .map(|item| match item.unwrap_usize() {
+                Ok(byte) if byte < 256 => Ok(byte as u8),
+                _ => Err(RuntimeError::new(
+                    ErrorLocation::Unspecified,
+                    "Bytes must be integers between 0 and 255.".to_string(),
+                )),
+            })
+            .collect::<Result<Vec<u8>, RuntimeError>>()?;
+
+        Ok(Literals::Bytes(Rc::new(bytes)))
+    })
+}
+
+/// `encode(string, encoding)`, the encoding is utf-8 if not given.
+fn encode() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(1, 2), |_, args| {
+        let encoding = encoding_arg(args, 1)?;
+
+        match &args[0] {
+            Literals::String(string) => match encoding.encode(string) {
+                Ok(bytes) => Ok(Literals::Bytes(Rc::new(bytes))),
+                Err(message) => Err(RuntimeError::new(ErrorLocation::Unspecified, message)),
+            },
+            other => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a string but got '{}'.", other.to_string()),
+            )),
+        }
+    })
+}
+
+/// `decode(bytes, encoding)`, the encoding is utf-8 if not given.
+fn decode() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(1, 2), |_, args| {
+        let encoding = encoding_arg(args, 1)?;
+
+        match &args[0] {
+            Literals::Bytes(bytes) => match encoding.decode(bytes) {
+                Ok(string) => Ok(Literals::String(string.into())),
+                Err(message) => Err(RuntimeError::new(ErrorLocation::Unspecified, message)),
+            },
+            other => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected bytes but got '{}'.", other.to_string()),
+            )),
+        }
+    })
+}
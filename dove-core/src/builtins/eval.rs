@@ -0,0 +1,48 @@
+use crate::builtins::*;
+use crate::dove_callable::BuiltinFunction;
+use crate::scanner::Scanner;
+use crate::parser::Parser;
+use crate::resolver::Resolver;
+
+/// `eval` runs arbitrary code, hosts running untrusted scripts may want to deny this module.
+pub fn define(definer: &mut Definer) {
+    definer.function("eval", eval());
+}
+
+/// Run source code in the global environment and return the value of its last expression.
+fn eval() -> impl DoveCallable {
+    BuiltinFunction::new(1, |interpreter, args| {
+        let source = match &args[0] {
+            Literals::String(source) => source,
+            _ => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected source code string but got '{}'.", args[0].to_string()),
+            )),
+        };
+
+        let syntax_error = || RuntimeError::new(
+            ErrorLocation::Unspecified,
+            "Cannot evaluate source with errors.".to_string(),
+        );
+
+        let output = interpreter.output();
+        let tokens = match Scanner::new(source.chars().collect(), Rc::clone(&output)).try_scan_tokens() {
+            Some(tokens) => tokens,
+            None => return Err(syntax_error()),
+        };
+
+        let mut parser = Parser::new(tokens, Rc::clone(&output));
+        let statements = parser.program();
+        if parser.had_error() {
+            return Err(syntax_error());
+        }
+
+        let mut resolver = Resolver::new(interpreter, output);
+        resolver.resolve(&statements);
+        if resolver.had_error() {
+            return Err(syntax_error());
+        }
+
+        interpreter.execute_global(&statements)
+    })
+}
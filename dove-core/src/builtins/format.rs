@@ -0,0 +1,193 @@
+use crate::builtins::*;
+use crate::dove_callable::{Arity, BuiltinFunction};
+use crate::interpreter::stringify;
+
+pub fn define(definer: &mut Definer) {
+    definer.function("format", format());
+}
+
+fn format() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::AtLeast(1), |_, args| {
+        let template = match &args[0] {
+            Literals::String(s) => s,
+            _ => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a format string but got '{}'.", args[0].to_string()),
+            )),
+        };
+
+        match format_values(template, &args[1..]) {
+            Ok(string) => Ok(Literals::String(string.into())),
+            Err(message) => Err(RuntimeError::new(ErrorLocation::Unspecified, message)),
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// A parsed format spec, the part after ':' in `{0:>8.2}`.
+#[derive(Debug, Clone, Copy)]
+pub struct FormatSpec {
+    fill: char,
+    align: Option<Align>,
+    zero_pad: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl FormatSpec {
+    /// Parse a spec such as `*^10`, `08.3` or `.2`.
+    pub fn parse(spec: &str) -> Result<FormatSpec, String> {
+        let chars: Vec<char> = spec.chars().collect();
+        let mut format_spec = FormatSpec { fill: ' ', align: None, zero_pad: false, width: 0, precision: None };
+        let mut i = 0;
+
+        if chars.len() >= 2 && to_align(chars[1]).is_some() {
+            format_spec.fill = chars[0];
+            format_spec.align = to_align(chars[1]);
+            i = 2;
+        } else if !chars.is_empty() && to_align(chars[0]).is_some() {
+            format_spec.align = to_align(chars[0]);
+            i = 1;
+        }
+
+        if chars.get(i) == Some(&'0') {
+            format_spec.zero_pad = true;
+            i += 1;
+        }
+
+        let (width, next) = parse_digits(&chars, i);
+        format_spec.width = width.unwrap_or(0);
+        i = next;
+
+        if chars.get(i) == Some(&'.') {
+            let (precision, next) = parse_digits(&chars, i + 1);
+            if precision.is_none() {
+                return Err(format!("Missing precision in format spec '{}'.", spec));
+            }
+            format_spec.precision = precision;
+            i = next;
+        }
+
+        if i != chars.len() {
+            return Err(format!("Invalid format spec '{}'.", spec));
+        }
+
+        Ok(format_spec)
+    }
+
+    /// Format a value according to this spec.
+    pub fn apply(&self, value: &Literals) -> String {
+        let mut string = match (value, self.precision) {
+            (Literals::Number(n), Some(precision)) => format!("{:.*}", precision, n),
+            (Literals::Int(n), Some(precision)) => format!("{:.*}", precision, *n as f64),
+            (Literals::String(s), Some(precision)) => s.chars().take(precision).collect(),
+            (Literals::String(s), None) => s.to_string(),
+            _ => stringify(value.clone()),
+        };
+
+        let is_number = matches!(value, Literals::Number(_) | Literals::Int(_));
+        let len = string.chars().count();
+        if len >= self.width {
+            return string;
+        }
+        let padding = self.width - len;
+
+        if self.zero_pad && is_number && self.align.is_none() {
+            // Zeros go after the sign.
+            let sign = if string.starts_with('-') { string.remove(0).to_string() } else { String::new() };
+            return format!("{}{}{}", sign, "0".repeat(padding), string);
+        }
+
+        let fill = |n: usize| self.fill.to_string().repeat(n);
+        let default_align = if is_number { Align::Right } else { Align::Left };
+        match self.align.unwrap_or(default_align) {
+            Align::Left => format!("{}{}", string, fill(padding)),
+            Align::Right => format!("{}{}", fill(padding), string),
+            Align::Center => format!("{}{}{}", fill(padding / 2), string, fill(padding - padding / 2)),
+        }
+    }
+}
+
+/// Substitute `{}` placeholders in `template` with `values`.
+///
+/// Placeholders may name a position (`{1}`) and a spec (`{:>8.2}`); `{{` and `}}` are literal braces.
+pub fn format_values(template: &str, values: &[Literals]) -> Result<String, String> {
+    let mut res = String::new();
+    let mut chars = template.chars().peekable();
+    let mut next_index = 0;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                res.push('{');
+            },
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                res.push('}');
+            },
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err("Unterminated placeholder in format string.".to_string()),
+                    }
+                }
+
+                let (index, spec) = match placeholder.find(':') {
+                    Some(colon) => (&placeholder[..colon], &placeholder[colon + 1..]),
+                    None => (&placeholder[..], ""),
+                };
+
+                let index = if index.is_empty() {
+                    next_index += 1;
+                    next_index - 1
+                } else {
+                    match index.trim().parse::<usize>() {
+                        Ok(index) => index,
+                        Err(_) => return Err(format!("Invalid placeholder '{{{}}}'.", placeholder)),
+                    }
+                };
+
+                let value = match values.get(index) {
+                    Some(value) => value,
+                    None => return Err(format!("Missing value for placeholder {}.", index)),
+                };
+
+                res.push_str(&FormatSpec::parse(spec)?.apply(value));
+            },
+            '}' => return Err("Unmatched '}' in format string.".to_string()),
+            _ => res.push(c),
+        }
+    }
+
+    Ok(res)
+}
+
+fn to_align(c: char) -> Option<Align> {
+    match c {
+        '<' => Some(Align::Left),
+        '^' => Some(Align::Center),
+        '>' => Some(Align::Right),
+        _ => None,
+    }
+}
+
+/// Parse consecutive digits starting at `start`, returning the number (if any) and the next index.
+fn parse_digits(chars: &[char], start: usize) -> (Option<usize>, usize) {
+    let mut end = start;
+    while end < chars.len() && chars[end].is_ascii_digit() {
+        end += 1;
+    }
+
+    let digits: String = chars[start..end].iter().collect();
+    (digits.parse().ok(), end)
+}
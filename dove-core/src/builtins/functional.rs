@@ -0,0 +1,127 @@
+use crate::builtins::*;
+use crate::dove_callable::{Arity, BuiltinFunction};
+
+pub fn define(definer: &mut Definer) {
+    definer.function("partial", partial());
+    definer.function("compose", compose());
+}
+
+fn partial() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::AtLeast(1), |_, args| {
+        let function = args[0].clone();
+        let bound = args[1..].to_vec();
+
+        let arity = match callable_arity(&function) {
+            Some(arity) => arity,
+            None => return Err(not_callable("partial", &function)),
+        };
+        let arity = match remaining_arity(arity, bound.len()) {
+            Some(arity) => arity,
+            None => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Cannot bind {} arguments to a function taking {}.", bound.len(), arity),
+            )),
+        };
+
+        Ok(Literals::Function(Rc::new(Partial { function, bound, arity })))
+    })
+}
+
+fn compose() -> impl DoveCallable {
+    BuiltinFunction::new(2, |_, args| {
+        let (outer, inner) = (args[0].clone(), args[1].clone());
+        if callable_arity(&outer).is_none() {
+            return Err(not_callable("compose", &outer));
+        }
+
+        let arity = match callable_arity(&inner) {
+            Some(arity) => arity,
+            None => return Err(not_callable("compose", &inner)),
+        };
+
+        Ok(Literals::Function(Rc::new(Composition { outer, inner, arity })))
+    })
+}
+
+/// A function with its first arguments already given, created by `partial(f, ...)`.
+struct Partial {
+    function: Literals,
+    bound: Vec<Literals>,
+    arity: Arity,
+}
+
+impl DoveCallable for Partial {
+    fn arity(&self) -> Arity {
+        self.arity
+    }
+
+    fn call(&self, interpreter: &mut Interpreter, argument_vals: &Vec<Literals>) -> Result<Literals, RuntimeError> {
+        let mut arguments = self.bound.clone();
+        arguments.extend(argument_vals.iter().cloned());
+        interpreter.call_value(self.function.clone(), &arguments, ErrorLocation::Unspecified)
+    }
+
+    fn name(&self) -> String {
+        format!("partial({})", callable_name(&self.function))
+    }
+}
+
+/// `outer(inner(...))`, created by `compose(outer, inner)`.
+struct Composition {
+    outer: Literals,
+    inner: Literals,
+    arity: Arity,
+}
+
+impl DoveCallable for Composition {
+    fn arity(&self) -> Arity {
+        self.arity
+    }
+
+    fn call(&self, interpreter: &mut Interpreter, argument_vals: &Vec<Literals>) -> Result<Literals, RuntimeError> {
+        let value = interpreter.call_value(self.inner.clone(), argument_vals, ErrorLocation::Unspecified)?;
+        interpreter.call_value(self.outer.clone(), &vec![value], ErrorLocation::Unspecified)
+    }
+
+    fn name(&self) -> String {
+        format!("compose({}, {})", callable_name(&self.outer), callable_name(&self.inner))
+    }
+}
+
+/// The arity of a function or class, or `None` if the value cannot be called.
+fn callable_arity(value: &Literals) -> Option<Arity> {
+    match value {
+        Literals::Function(function) => Some(function.arity()),
+        Literals::Class(class) => match class.find_method("init") {
+            // The initializer is bound to the new instance, `self` is not an argument.
+            Some(initializer) => Some(initializer.arity()),
+            None => Some(Arity::Fixed(0)),
+        },
+        _ => None,
+    }
+}
+
+/// The arity left after binding `bound` arguments, or `None` if that is too many.
+fn remaining_arity(arity: Arity, bound: usize) -> Option<Arity> {
+    match arity {
+        Arity::Fixed(n) if bound <= n => Some(Arity::Fixed(n - bound)),
+        Arity::AtLeast(n) => Some(Arity::AtLeast(n.saturating_sub(bound))),
+        Arity::Range(min, max) if bound <= max => Some(Arity::Range(min.saturating_sub(bound), max - bound)),
+        _ => None,
+    }
+}
+
+fn callable_name(value: &Literals) -> String {
+    match value {
+        Literals::Function(function) => function.name(),
+        Literals::Class(class) => class.name.clone(),
+        _ => value.to_string(),
+    }
+}
+
+fn not_callable(name: &str, value: &Literals) -> RuntimeError {
+    RuntimeError::new(
+        ErrorLocation::Unspecified,
+        format!("'{}' expected a function but got '{}'.", name, value.to_string()),
+    )
+}
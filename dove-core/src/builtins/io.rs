@@ -0,0 +1,46 @@
+use crate::builtins::*;
+use crate::dove_callable::{Arity, BuiltinFunction};
+
+pub fn define(definer: &mut Definer) {
+    definer.function("input", input());
+    definer.function("exit", exit());
+}
+
+/// Stop the script with the given status, 0 if left out. In the REPL, this ends the session.
+fn exit() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(0, 1), |interpreter, args| {
+        let status = match args.first() {
+            None => 0,
+            Some(status) => match status.clone().unwrap_int() {
+                Some(status) if status >= i32::MIN as i64 && status <= i32::MAX as i64 => status as i32,
+                _ => return Err(RuntimeError::new(
+                    ErrorLocation::Unspecified,
+                    format!("Expected an integer exit status but got '{}'.", status.to_string()),
+                )),
+            },
+        };
+
+        Err(interpreter.exit(status))
+    })
+}
+
+/// Read a line from the input of the interpreter, after showing the optional prompt.
+/// Returns `nil` at the end of the input.
+fn input() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(0, 1), |interpreter, args| {
+        match args.first() {
+            None => {},
+            Some(Literals::String(prompt)) => interpreter.output().prompt(prompt),
+            Some(prompt) => interpreter.output().prompt(&prompt.to_string()),
+        }
+
+        match interpreter.options().input.read_line() {
+            Ok(Some(line)) => Ok(Literals::String(line.into())),
+            Ok(None) => Ok(Literals::Nil),
+            Err(error) => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Cannot read input: {}.", error),
+            )),
+        }
+    })
+}
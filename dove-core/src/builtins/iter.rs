@@ -0,0 +1,59 @@
+use crate::builtins::*;
+use crate::dove_callable::{Arity, BuiltinFunction};
+
+pub fn define(definer: &mut Definer) {
+    definer.function("zip", zip());
+    definer.function("reversed", reversed());
+    definer.function("sorted", sorted());
+}
+
+fn zip() -> impl DoveCallable {
+    BuiltinFunction::new(2, |_, args| {
+        let (a, b) = match (iterable_items(&args[0]), iterable_items(&args[1])) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Cannot zip '{}' and '{}'.", args[0].to_string(), args[1].to_string()),
+            )),
+        };
+
+        let pairs = a.into_iter()
+            .zip(b)
+            .map(|(a, b)| Literals::Tuple(Box::new(vec![a, b])))
+            .collect();
+
+        Ok(Literals::array(pairs))
+    })
+}
+
+fn reversed() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        match &args[0] {
+            Literals::Array(array) => {
+                let items = array.borrow().iter().rev().cloned().collect();
+                Ok(Literals::array(items))
+            },
+            Literals::Tuple(tuple) => Ok(Literals::Tuple(Box::new(tuple.iter().rev().cloned().collect()))),
+            Literals::String(string) => Ok(Literals::String(string.chars().rev().collect::<String>().into())),
+            _ => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Cannot reverse type '{}'.", args[0].to_string()),
+            )),
+        }
+    })
+}
+
+fn sorted() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(1, 2), |interpreter, args| {
+        let items = match iterable_items(&args[0]) {
+            Some(items) => items,
+            None => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Cannot sort type '{}'.", args[0].to_string()),
+            )),
+        };
+
+        let sorted = sort_values(interpreter, items, args.get(1))?;
+        Ok(Literals::array(sorted))
+    })
+}
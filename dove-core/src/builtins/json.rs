@@ -0,0 +1,399 @@
+use std::rc::Rc;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::builtins::*;
+use crate::dove_callable::{Arity, BuiltinFunction};
+
+/// Values nested deeper than this cannot be parsed or stringified, e.g. an array containing itself.
+const MAX_DEPTH: usize = 512;
+
+pub fn define(definer: &mut Definer) {
+    definer.value("json", make_module("json", vec![
+        ("parse", Literals::Function(Rc::new(parse()))),
+        ("stringify", Literals::Function(Rc::new(stringify()))),
+    ]));
+}
+
+/// `json.parse(string)`, objects become dictionaries, arrays become arrays and `null` becomes nil.
+fn parse() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        let source = match &args[0] {
+            Literals::String(source) => source,
+            other => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a string to parse as JSON but got '{}'.", other.to_string()),
+            )),
+        };
+
+        let mut parser = JsonParser { chars: source.chars().peekable(), line: 1, column: 1 };
+        parser.document().map_err(|message| RuntimeError::new(
+            ErrorLocation::Unspecified,
+            format!("Invalid JSON at line {}, column {}: {}", parser.line, parser.column, message),
+        ))
+    })
+}
+
+/// `json.stringify(value, indent)`, on one line, or over several lines indented by `indent` spaces.
+/// Tuples become arrays, and dictionary keys become strings.
+fn stringify() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(1, 2), |_, args| {
+        let indent = match args.get(1) {
+            None => None,
+            Some(indent) => match indent.clone().unwrap_usize() {
+                Ok(indent) => Some(indent),
+                Err(_) => return Err(RuntimeError::new(
+                    ErrorLocation::Unspecified,
+                    "The indent must be a non-negative integer.".to_string(),
+                )),
+            },
+        };
+
+        let mut json = String::new();
+        write_json(&args[0], indent, 0, &mut json)
+            .map_err(|message| RuntimeError::new(ErrorLocation::Unspecified, message))?;
+        Ok(Literals::String(json.into()))
+    })
+}
+
+fn write_json(value: &Literals, indent: Option<usize>, depth: usize, json: &mut String) -> Result<(), String> {
+    if depth > MAX_DEPTH {
+        return Err("Cannot convert to JSON, the value is nested too deeply.".to_string());
+    }
+
+    match value {
+        Literals::Nil => json.push_str("null"),
+        Literals::Boolean(boolean) => json.push_str(if *boolean { "true" } else { "false" }),
+        Literals::Number(number) if !number.is_finite() => {
+            return Err(format!("Cannot convert {} to JSON.", number));
+        },
+        Literals::Number(number) => json.push_str(&number.to_string()),
+        Literals::Int(int) => json.push_str(&int.to_string()),
+        Literals::String(string) => write_string(string, json),
+        Literals::Frozen(value) => write_json(value, indent, depth, json)?,
+        Literals::Array(array) => {
+            let items = array.borrow().clone();
+            write_array(&items, indent, depth, json)?;
+        },
+        Literals::Tuple(tuple) => write_array(tuple, indent, depth, json)?,
+        Literals::Dictionary(dict) => {
+            let entries: Vec<(DictKey, Literals)> = dict.borrow().iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+
+            if entries.is_empty() {
+                json.push_str("{}");
+                return Ok(());
+            }
+
+            json.push('{');
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                write_newline(indent, depth + 1, json);
+                let key = match key {
+                    DictKey::StringKey(key) => key.clone(),
+                    DictKey::NumberKey(key) => key.to_string(),
+                };
+                write_string(&key, json);
+                json.push_str(if indent.is_some() { ": " } else { ":" });
+                write_json(value, indent, depth + 1, json)?;
+            }
+            write_newline(indent, depth, json);
+            json.push('}');
+        },
+        other => return Err(format!("Cannot convert type '{}' to JSON.", other.to_string())),
+    }
+
+    Ok(())
+}
+
+fn write_array(items: &[Literals], indent: Option<usize>, depth: usize, json: &mut String) -> Result<(), String> {
+    if items.is_empty() {
+        json.push_str("[]");
+        return Ok(());
+    }
+
+    json.push('[');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_newline(indent, depth + 1, json);
+        write_json(item, indent, depth + 1, json)?;
+    }
+    write_newline(indent, depth, json);
+    json.push(']');
+    Ok(())
+}
+
+/// Start a new line indented for `depth`, if the output is indented.
+fn write_newline(indent: Option<usize>, depth: usize, json: &mut String) {
+    if let Some(indent) = indent {
+        json.push('\n');
+        json.push_str(&" ".repeat(indent * depth));
+    }
+}
+
+fn write_string(string: &str, json: &mut String) {
+    json.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            '\u{08}' => json.push_str("\\b"),
+            '\u{0C}' => json.push_str("\\f"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// Reads a JSON document, keeping the position of the next character for errors.
+struct JsonParser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn document(&mut self) -> Result<Literals, String> {
+        let value = self.value(0)?;
+        self.skip_whitespace();
+        match self.chars.peek() {
+            None => Ok(value),
+            Some(c) => Err(format!("Unexpected '{}' after the value.", c)),
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Literals, String> {
+        if depth > MAX_DEPTH {
+            return Err("The value is nested too deeply.".to_string());
+        }
+
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') => self.object(depth),
+            Some('[') => self.array(depth),
+            Some('"') => Ok(Literals::String(self.string()?.into())),
+            Some('-') | Some('0'..='9') => self.number(),
+            Some('t') => self.keyword("true", Literals::Boolean(true)),
+            Some('f') => self.keyword("false", Literals::Boolean(false)),
+            Some('n') => self.keyword("null", Literals::Nil),
+            Some(c) => Err(format!("Unexpected '{}', expected a value.", c)),
+            None => Err("Unexpected end, expected a value.".to_string()),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Literals, String> {
+        self.expect('{')?;
+        let mut entries = IndexMap::new();
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.next();
+        } else {
+            loop {
+                self.skip_whitespace();
+                if self.chars.peek() != Some(&'"') {
+                    return Err("Expected a string key.".to_string());
+                }
+                let key = self.string()?;
+                self.skip_whitespace();
+                self.expect(':')?;
+                let value = self.value(depth + 1)?;
+                entries.insert(DictKey::StringKey(key), value);
+
+                self.skip_whitespace();
+                match self.chars.peek() {
+                    Some(',') => self.next(),
+                    Some('}') => {
+                        self.next();
+                        break;
+                    },
+                    _ => return Err("Expected ',' or '}' after an entry.".to_string()),
+                };
+            }
+        }
+
+        Ok(Literals::dictionary(entries))
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Literals, String> {
+        self.expect('[')?;
+        let mut items = vec![];
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.next();
+        } else {
+            loop {
+                items.push(self.value(depth + 1)?);
+
+                self.skip_whitespace();
+                match self.chars.peek() {
+                    Some(',') => self.next(),
+                    Some(']') => {
+                        self.next();
+                        break;
+                    },
+                    _ => return Err("Expected ',' or ']' after an item.".to_string()),
+                };
+            }
+        }
+
+        Ok(Literals::array(items))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => match self.next() {
+                    Some('"') => string.push('"'),
+                    Some('\\') => string.push('\\'),
+                    Some('/') => string.push('/'),
+                    Some('b') => string.push('\u{08}'),
+                    Some('f') => string.push('\u{0C}'),
+                    Some('n') => string.push('\n'),
+                    Some('r') => string.push('\r'),
+                    Some('t') => string.push('\t'),
+                    Some('u') => string.push(self.unicode_escape()?),
+                    _ => return Err("Invalid escape sequence.".to_string()),
+                },
+                Some(c) if (c as u32) < 0x20 => return Err("Control characters in strings must be escaped.".to_string()),
+                Some(c) => string.push(c),
+                None => return Err("Unterminated string.".to_string()),
+            }
+        }
+    }
+
+    /// The character of a `\uXXXX` escape, after the `\u`, which may be followed by the
+    /// escape of a low surrogate for characters outside the basic multilingual plane.
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex_code()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| "Invalid unicode escape.".to_string());
+        }
+
+        if self.next() != Some('\\') || self.next() != Some('u') {
+            return Err("Expected a low surrogate after a high surrogate.".to_string());
+        }
+        let low = self.hex_code()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err("Invalid low surrogate.".to_string());
+        }
+
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or_else(|| "Invalid unicode escape.".to_string())
+    }
+
+    fn hex_code(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| "Expected four hex digits in a unicode escape.".to_string())?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<Literals, String> {
+        let mut number = String::new();
+        if self.chars.peek() == Some(&'-') {
+            number.push('-');
+            self.next();
+        }
+
+        // No leading zeros, e.g. `01`.
+        match self.chars.peek() {
+            Some('0') => number.push(self.next().unwrap_or('0')),
+            Some('1'..='9') => self.digits(&mut number),
+            _ => return Err("Expected a digit.".to_string()),
+        }
+
+        if self.chars.peek() == Some(&'.') {
+            number.push('.');
+            self.next();
+            if !matches!(self.chars.peek(), Some('0'..='9')) {
+                return Err("Expected a digit after the decimal point.".to_string());
+            }
+            self.digits(&mut number);
+        }
+
+        if let Some('e') | Some('E') = self.chars.peek() {
+            number.push('e');
+            self.next();
+            if let Some(sign @ '+') | Some(sign @ '-') = self.chars.peek().copied() {
+                number.push(sign);
+                self.next();
+            }
+            if !matches!(self.chars.peek(), Some('0'..='9')) {
+                return Err("Expected a digit in the exponent.".to_string());
+            }
+            self.digits(&mut number);
+        }
+
+        // Numbers without a fraction or exponent are integers, if they fit in one.
+        if let Ok(int) = number.parse() {
+            return Ok(Literals::Int(int));
+        }
+        number.parse()
+            .map(Literals::Number)
+            .map_err(|_| format!("Invalid number '{}'.", number))
+    }
+
+    fn digits(&mut self, number: &mut String) {
+        while let Some(digit @ '0'..='9') = self.chars.peek().copied() {
+            number.push(digit);
+            self.next();
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Literals) -> Result<Literals, String> {
+        for expected in keyword.chars() {
+            if self.next() != Some(expected) {
+                return Err(format!("Expected '{}'.", keyword));
+            }
+        }
+        Ok(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ') | Some('\t') | Some('\n') | Some('\r') = self.chars.peek() {
+            self.next();
+        }
+    }
+
+    /// Consume the expected character, the position of an error is that of the unexpected one.
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.chars.peek() {
+            Some(c) if *c == expected => {
+                self.next();
+                Ok(())
+            },
+            Some(c) => Err(format!("Unexpected '{}', expected '{}'.", c, expected)),
+            None => Err(format!("Unexpected end, expected '{}'.", expected)),
+        }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+}
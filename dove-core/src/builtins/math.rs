@@ -0,0 +1,87 @@
+use std::rc::Rc;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::builtins::*;
+use crate::builtins::numeric;
+use crate::dove_callable::{Arity, BuiltinFunction};
+
+pub fn define(definer: &mut Definer) {
+    definer.value("math", make_module("math", vec![
+        ("pi", Literals::Number(std::f64::consts::PI)),
+        ("e", Literals::Number(std::f64::consts::E)),
+        ("sqrt", unary("sqrt", f64::sqrt)),
+        ("sin", unary("sin", f64::sin)),
+        ("cos", unary("cos", f64::cos)),
+        ("tan", unary("tan", f64::tan)),
+        ("pow", Literals::Function(Rc::new(pow()))),
+        ("log", Literals::Function(Rc::new(log()))),
+        ("min", Literals::Function(Rc::new(numeric::min()))),
+        ("max", Literals::Function(Rc::new(numeric::max()))),
+        ("random", Literals::Function(Rc::new(random()))),
+    ]));
+}
+
+/// A function of one number, e.g. `math.sqrt(2)`.
+fn unary(name: &'static str, op: fn(f64) -> f64) -> Literals {
+    Literals::Function(Rc::new(BuiltinFunction::new(1, move |_, args| {
+        Ok(Literals::Number(op(number(name, &args[0])?)))
+    })))
+}
+
+/// `math.pow(base, exponent)`
+fn pow() -> impl DoveCallable {
+    BuiltinFunction::new(2, |_, args| {
+        let base = number("pow", &args[0])?;
+        let exponent = number("pow", &args[1])?;
+        Ok(Literals::Number(base.powf(exponent)))
+    })
+}
+
+/// `math.log(x)` is the natural logarithm, `math.log(x, base)` the logarithm in that base.
+fn log() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::Range(1, 2), |_, args| {
+        let x = number("log", &args[0])?;
+        match args.get(1) {
+            Some(base) => Ok(Literals::Number(x.log(number("log", base)?))),
+            None => Ok(Literals::Number(x.ln())),
+        }
+    })
+}
+
+/// The first state of the random number generator of an interpreter, different on each run
+/// unless it is `deterministic`. The state of xorshift must not be zero.
+pub(crate) fn random_seed(deterministic: bool) -> u64 {
+    if deterministic {
+        0x9E37_79B9_7F4A_7C15
+    } else {
+        RandomState::new().build_hasher().finish() | 1
+    }
+}
+
+/// `math.random()`, a number from 0 inclusive to 1 exclusive. Not suitable for cryptography.
+fn random() -> impl DoveCallable {
+    BuiltinFunction::new(0, |interpreter, _| {
+        // xorshift64*
+        let mut x = interpreter.random_state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        interpreter.random_state = x;
+        let bits = x.wrapping_mul(0x2545_F491_4F6C_DD1D);
+
+        // The top 53 bits fill the mantissa of a double.
+        Ok(Literals::Number((bits >> 11) as f64 / (1u64 << 53) as f64))
+    })
+}
+
+fn number(name: &str, value: &Literals) -> Result<f64, RuntimeError> {
+    match value {
+        Literals::Number(n) => Ok(*n),
+        Literals::Int(n) => Ok(*n as f64),
+        _ => Err(RuntimeError::new(
+            ErrorLocation::Unspecified,
+            format!("'{}' expected a number but got '{}'.", name, value.to_string()),
+        )),
+    }
+}
@@ -0,0 +1,240 @@
+use std::rc::Rc;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use indexmap::IndexMap;
+
+use crate::data_types::module::Module;
+use crate::environment::Environment;
+use crate::dove_callable::DoveCallable;
+use crate::error_handler::{RuntimeError, ErrorLocation};
+use crate::interpreter::Interpreter;
+use crate::token::{Literals, DictKey};
+
+pub mod collections;
+pub mod convert;
+pub mod encoding;
+pub mod eval;
+pub mod format;
+pub mod functional;
+pub mod io;
+pub mod iter;
+pub mod json;
+pub mod math;
+pub mod numeric;
+pub mod references;
+pub mod reflection;
+pub mod time;
+
+/// Controls which builtins scripts of an interpreter can use.
+///
+/// Rules name either a whole module (`"collections"`, `"convert"`, `"encoding"`, `"eval"`, `"format"`, `"io"`, `"iter"`, `"json"`, `"math"`, `"numeric"`, `"reflection"`, `"time"`)
+/// or a single function (`"benchmark"`). Denying takes precedence over allowing.
+#[derive(Debug, Clone, Default)]
+pub struct BuiltinPolicy {
+    /// If present, only the listed modules and functions are defined.
+    allowed: Option<HashSet<String>>,
+    denied: HashSet<String>,
+}
+
+impl BuiltinPolicy {
+    /// Define every builtin.
+    pub fn allow_all() -> BuiltinPolicy {
+        BuiltinPolicy::default()
+    }
+
+    /// Define no builtins, except for those allowed afterwards.
+    pub fn deny_all() -> BuiltinPolicy {
+        BuiltinPolicy {
+            allowed: Some(HashSet::new()),
+            denied: HashSet::new(),
+        }
+    }
+
+    pub fn allow(mut self, name: &str) -> BuiltinPolicy {
+        if let Some(allowed) = &mut self.allowed {
+            allowed.insert(name.to_string());
+        }
+        self.denied.remove(name);
+        self
+    }
+
+    pub fn deny(mut self, name: &str) -> BuiltinPolicy {
+        self.denied.insert(name.to_string());
+        self
+    }
+
+    pub fn allows(&self, module: &str, function: &str) -> bool {
+        if self.denied.contains(module) || self.denied.contains(function) {
+            return false;
+        }
+
+        match &self.allowed {
+            Some(allowed) => allowed.contains(module) || allowed.contains(function),
+            None => true,
+        }
+    }
+}
+
+/// Adds the builtins of one module to the registry.
+pub struct Definer<'a> {
+    registry: &'a mut Registry,
+    module: &'static str,
+}
+
+impl<'a> Definer<'a> {
+    fn function<C: DoveCallable + 'static>(&mut self, name: &str, function: C) {
+        self.registry.insert(name.to_string(), (self.module, Literals::Function(Rc::new(function))));
+    }
+
+    fn value(&mut self, name: &str, value: Literals) {
+        self.registry.insert(name.to_string(), (self.module, value));
+    }
+}
+
+type DefineModule = fn(&mut Definer);
+
+/// Every builtin, with the module defining it, keyed by name.
+type Registry = HashMap<String, (&'static str, Literals)>;
+
+thread_local! {
+    /// Created on first use and shared by all interpreters of the thread, which is possible
+    /// since builtins hold no state. Interpreters define a builtin in their globals only when
+    /// a script first refers to it, so starting one does not grow with the standard library.
+    static REGISTRY: Registry = registry();
+}
+
+fn registry() -> Registry {
+    let modules: [(&'static str, DefineModule); 14] = [
+        ("collections", collections::define),
+        ("convert", convert::define),
+        ("encoding", encoding::define),
+        ("eval", eval::define),
+        ("format", format::define),
+        ("functional", functional::define),
+        ("io", io::define),
+        ("iter", iter::define),
+        ("json", json::define),
+        ("math", math::define),
+        ("numeric", numeric::define),
+        ("references", references::define),
+        ("reflection", reflection::define),
+        ("time", time::define),
+    ];
+
+    let mut registry = Registry::new();
+    for (module, define) in modules.iter() {
+        define(&mut Definer { registry: &mut registry, module });
+    }
+    registry
+}
+
+/// The builtin called `name`, if there is one and `policy` allows it.
+pub fn builtin(name: &str, policy: &BuiltinPolicy) -> Option<Literals> {
+    REGISTRY.with(|registry| match registry.get(name) {
+        Some((module, value)) if policy.allows(module, name) => Some(value.clone()),
+        _ => None,
+    })
+}
+
+/// Names of the builtins allowed by `policy`.
+pub fn builtin_names(policy: &BuiltinPolicy) -> Vec<String> {
+    REGISTRY.with(|registry| {
+        registry.iter()
+            .filter(|(name, (module, _))| policy.allows(module, name))
+            .map(|(name, _)| name.clone())
+            .collect()
+    })
+}
+
+/// Define all builtins allowed by `policy` in the given environment at once,
+/// instead of on first use like interpreters do.
+pub fn define_builtins(environment: &mut Environment, policy: &BuiltinPolicy) {
+    REGISTRY.with(|registry| {
+        for (name, (module, value)) in registry.iter() {
+            if policy.allows(module, name) {
+                environment.define(name.clone(), value.clone());
+            }
+        }
+    })
+}
+
+/// Create a dictionary with string keys.
+fn make_dict(pairs: Vec<(&str, Literals)>) -> Literals {
+    let dict: IndexMap<DictKey, Literals> = pairs.into_iter()
+        .map(|(key, value)| (DictKey::StringKey(key.to_string()), value))
+        .collect();
+
+    Literals::dictionary(dict)
+}
+
+/// Create a module of builtins, whose members are used as properties, e.g. `json.parse`.
+fn make_module(name: &str, members: Vec<(&str, Literals)>) -> Literals {
+    let module = Module::new(name.to_string());
+    {
+        let mut environment = module.environment.borrow_mut();
+        for (name, value) in members {
+            environment.define(name.to_string(), value);
+        }
+    }
+
+    Literals::Module(Rc::new(module))
+}
+
+/// The error of integer arithmetic in the builtin `name` overflowing, like the one of the operators.
+pub(crate) fn integer_overflow(name: &str) -> RuntimeError {
+    RuntimeError::new(ErrorLocation::Unspecified, format!("Integer overflow in '{}'.", name))
+}
+
+/// Collect the items of an iterable value (array, tuple, string, deque or bytes), if it is one.
+pub(crate) fn iterable_items(value: &Literals) -> Option<Vec<Literals>> {
+    match value {
+        Literals::Frozen(value) => iterable_items(value),
+        Literals::Array(array) => Some(array.borrow().clone()),
+        Literals::Bytes(bytes) => Some(bytes.iter().map(|byte| Literals::Int(*byte as i64)).collect()),
+        Literals::Deque(deque) => Some(deque.borrow().iter().cloned().collect()),
+        Literals::Tuple(tuple) => Some(tuple.to_vec()),
+        Literals::String(string) => Some(string.chars().map(|c| Literals::String(c.to_string().into())).collect()),
+        _ => None,
+    }
+}
+
+/// Order two values of the same comparable type, i.e. two numbers or two strings.
+pub fn compare_values(a: &Literals, b: &Literals) -> Option<Ordering> {
+    match (a, b) {
+        (Literals::Int(a), Literals::Int(b)) => Some(a.cmp(b)),
+        (Literals::Number(_) | Literals::Int(_), Literals::Number(_) | Literals::Int(_)) => {
+            let (a, b) = (a.clone().unwrap_number().ok()?, b.clone().unwrap_number().ok()?);
+            Some(a.partial_cmp(&b).unwrap_or(Ordering::Equal))
+        },
+        (Literals::String(a), Literals::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Return the given values in ascending order, comparing the results of `key` if given.
+pub fn sort_values(interpreter: &mut Interpreter, items: Vec<Literals>, key: Option<&Literals>) -> Result<Vec<Literals>, RuntimeError> {
+    let mut keyed = Vec::with_capacity(items.len());
+    for item in items {
+        let key_val = match key {
+            Some(key) => interpreter.call_value(key.clone(), &vec![item.clone()], ErrorLocation::Unspecified)?,
+            None => item.clone(),
+        };
+        keyed.push((key_val, item));
+    }
+
+    // All keys must be comparable with each other before sorting.
+    if let Some((first, _)) = keyed.first() {
+        for (other, _) in keyed.iter() {
+            if compare_values(first, other).is_none() {
+                return Err(RuntimeError::new(
+                    ErrorLocation::Unspecified,
+                    format!("Cannot compare '{}' with '{}'.", first.to_string(), other.to_string()),
+                ));
+            }
+        }
+    }
+
+    keyed.sort_by(|(a, _), (b, _)| compare_values(a, b).unwrap_or(Ordering::Equal));
+    Ok(keyed.into_iter().map(|(_, item)| item).collect())
+}
@@ -0,0 +1,112 @@
+use crate::builtins::*;
+use crate::dove_callable::{Arity, BuiltinFunction};
+use crate::error_handler::{RuntimeError, ErrorLocation};
+use crate::interpreter::stringify;
+
+pub fn define(definer: &mut Definer) {
+    definer.function("min", min());
+    definer.function("max", max());
+    definer.function("sum", sum());
+    definer.function("abs", abs());
+    definer.function("wrapping_add", wrapping("wrapping_add", i64::wrapping_add));
+    definer.function("wrapping_sub", wrapping("wrapping_sub", i64::wrapping_sub));
+    definer.function("wrapping_mul", wrapping("wrapping_mul", i64::wrapping_mul));
+}
+
+pub(crate) fn min() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::AtLeast(1), |_, args| {
+        let numbers = variadic_numbers("min", args)?;
+        Ok(extreme(numbers, Ordering::Less))
+    })
+}
+
+pub(crate) fn max() -> impl DoveCallable {
+    BuiltinFunction::new(Arity::AtLeast(1), |_, args| {
+        let numbers = variadic_numbers("max", args)?;
+        Ok(extreme(numbers, Ordering::Greater))
+    })
+}
+
+fn sum() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        let numbers = match iterable_items(&args[0]) {
+            Some(items) => numbers(items)?,
+            None => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Cannot sum type '{}'.", args[0].to_string()),
+            )),
+        };
+
+        // Integers add up to an integer, like with `+`.
+        numbers.into_iter().try_fold(Literals::Int(0), |sum, n| match (&sum, &n) {
+            (Literals::Int(a), Literals::Int(b)) => a.checked_add(*b).map(Literals::Int).ok_or_else(|| integer_overflow("sum")),
+            _ => Ok(Literals::Number(sum.unwrap_number().unwrap_or(0.0) + n.unwrap_number().unwrap_or(0.0))),
+        })
+    })
+}
+
+/// The smallest or largest number, depending on `ordering`, as it was given, e.g. still an integer.
+fn extreme(numbers: Vec<Literals>, ordering: Ordering) -> Literals {
+    numbers.into_iter()
+        .reduce(|best, n| if compare_values(&n, &best) == Some(ordering) { n } else { best })
+        .unwrap_or(Literals::Nil)
+}
+
+fn abs() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        match args[0] {
+            Literals::Number(n) => Ok(Literals::Number(n.abs())),
+            Literals::Int(n) => n.checked_abs().map(Literals::Int).ok_or_else(|| integer_overflow("abs")),
+            _ => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a number but got '{}'.", args[0].to_string()),
+            )),
+        }
+    })
+}
+
+/// Integer arithmetic that wraps around at the bounds of 64-bit integers, instead of overflowing.
+fn wrapping(name: &'static str, op: fn(i64, i64) -> i64) -> impl DoveCallable {
+    BuiltinFunction::new(2, move |_, args| {
+        let a = integer(name, &args[0])?;
+        let b = integer(name, &args[1])?;
+        Ok(Literals::Int(op(a, b)))
+    })
+}
+
+fn integer(name: &str, value: &Literals) -> Result<i64, RuntimeError> {
+    value.clone().unwrap_int().ok_or_else(|| RuntimeError::new(
+        ErrorLocation::Unspecified,
+        format!("'{}' expected a 64-bit integer but got '{}'.", name, stringify(value.clone())),
+    ))
+}
+
+/// Numbers given either as separate arguments, or as a single iterable argument.
+fn variadic_numbers(name: &str, args: &[Literals]) -> Result<Vec<Literals>, RuntimeError> {
+    let items = match args {
+        [single] => iterable_items(single).unwrap_or_else(|| vec![single.clone()]),
+        _ => args.to_vec(),
+    };
+
+    if items.is_empty() {
+        return Err(RuntimeError::new(
+            ErrorLocation::Unspecified,
+            format!("'{}' expected at least one number.", name),
+        ));
+    }
+
+    numbers(items)
+}
+
+/// The items, if they are all numbers or integers.
+fn numbers(items: Vec<Literals>) -> Result<Vec<Literals>, RuntimeError> {
+    items.into_iter()
+        .map(|item| match item {
+            Literals::Number(_) | Literals::Int(_) => Ok(item),
+            _ => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a number but got '{}'.", item.to_string()),
+            )),
+        })
+        .collect()
+}
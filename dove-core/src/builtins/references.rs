@@ -0,0 +1,37 @@
+use std::rc::Rc;
+
+use crate::builtins::*;
+use crate::dove_callable::BuiltinFunction;
+
+pub fn define(definer: &mut Definer) {
+    definer.function("weak_ref", weak_ref());
+    definer.function("deref", deref());
+}
+
+/// A reference to an instance that does not keep it alive,
+/// e.g. to refer back to a parent or an observer without creating a cycle.
+fn weak_ref() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        match &args[0] {
+            Literals::Instance(instance) => Ok(Literals::WeakRef(Rc::downgrade(instance))),
+            Literals::WeakRef(weak) => Ok(Literals::WeakRef(weak.clone())),
+            _ => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected an instance but got '{}'.", args[0].to_string()),
+            )),
+        }
+    })
+}
+
+/// The instance of a weak reference, or nil if it no longer exists.
+fn deref() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        match &args[0] {
+            Literals::WeakRef(weak) => Ok(weak.upgrade().map(Literals::Instance).unwrap_or(Literals::Nil)),
+            _ => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a weak reference but got '{}'.", args[0].to_string()),
+            )),
+        }
+    })
+}
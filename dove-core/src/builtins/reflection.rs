@@ -0,0 +1,168 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::builtins::*;
+use crate::dove_callable::BuiltinFunction;
+use crate::dove_class::{DoveClass, DoveInstance};
+
+pub fn define(definer: &mut Definer) {
+    definer.function("fields", fields());
+    definer.function("methods", methods());
+    definer.function("class_of", class_of());
+    definer.function("has_method", has_method());
+    definer.function("to_dict", to_dict());
+    definer.function("from_dict", from_dict());
+    definer.function("runtime_stats", runtime_stats());
+    definer.function("help", help());
+}
+
+/// Print what can be done with a value, see `reflection::help`.
+fn help() -> impl DoveCallable {
+    BuiltinFunction::new(1, |interpreter, args| {
+        interpreter.output().print(crate::reflection::help(&args[0]));
+        Ok(Literals::Nil)
+    })
+}
+
+/// How many arrays, dictionaries, instances and functions created by the script since the first call are still alive.
+fn runtime_stats() -> impl DoveCallable {
+    BuiltinFunction::new(0, |interpreter, _| {
+        let stats = interpreter.runtime_stats();
+        Ok(make_dict(vec![
+            ("arrays", Literals::Int(stats.arrays as i64)),
+            ("dictionaries", Literals::Int(stats.dictionaries as i64)),
+            ("instances", Literals::Int(stats.instances as i64)),
+            ("functions", Literals::Int(stats.functions as i64)),
+        ]))
+    })
+}
+
+/// Names of the fields of an instance.
+fn fields() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        match &args[0] {
+            Literals::Instance(instance) => Ok(string_array(instance.borrow().field_names())),
+            _ => Err(expected_instance(&args[0])),
+        }
+    })
+}
+
+/// Names of the methods of a class or instance, including inherited ones.
+fn methods() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        match class_of_value(&args[0]) {
+            Some(class) => Ok(string_array(class.method_names())),
+            None => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a class or an instance but got '{}'.", args[0].to_string()),
+            )),
+        }
+    })
+}
+
+fn class_of() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        match &args[0] {
+            Literals::Instance(instance) => Ok(Literals::Class(Rc::clone(&instance.borrow().class))),
+            _ => Err(expected_instance(&args[0])),
+        }
+    })
+}
+
+/// Whether a class or instance has a method with the given name. Other values never have methods.
+fn has_method() -> impl DoveCallable {
+    BuiltinFunction::new(2, |_, args| {
+        let name = match &args[1] {
+            Literals::String(name) => name,
+            _ => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a method name but got '{}'.", args[1].to_string()),
+            )),
+        };
+
+        let has_method = class_of_value(&args[0])
+            .map(|class| class.find_method(name).is_some())
+            .unwrap_or(false);
+
+        Ok(Literals::Boolean(has_method))
+    })
+}
+
+/// A dictionary with a copy of the fields of an instance, keyed by field name.
+fn to_dict() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        let instance = match &args[0] {
+            Literals::Instance(instance) => instance.borrow(),
+            _ => return Err(expected_instance(&args[0])),
+        };
+
+        let dict = instance.field_names().into_iter()
+            .map(|name| {
+                let value = instance.get_field(&name).unwrap_or(Literals::Nil);
+                (DictKey::StringKey(name), value)
+            })
+            .collect::<IndexMap<_, _>>();
+
+        Ok(Literals::dictionary(dict))
+    })
+}
+
+/// An instance of a class with fields copied from a dictionary, also available as `ClassName.from_dict(dict)`.
+fn from_dict() -> impl DoveCallable {
+    BuiltinFunction::new(2, |_, args| {
+        match &args[0] {
+            Literals::Class(class) => instance_from_dict(class, &args[1]),
+            _ => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a class but got '{}'.", args[0].to_string()),
+            )),
+        }
+    })
+}
+
+/// Create an instance of `class` with the entries of `dict` as its fields. The initializer is not called,
+/// so the fields are exactly the ones in the dictionary.
+pub(crate) fn instance_from_dict(class: &Rc<DoveClass>, dict: &Literals) -> Result<Literals, RuntimeError> {
+    let dict = match dict {
+        Literals::Dictionary(dict) => dict.borrow(),
+        _ => return Err(RuntimeError::new(
+            ErrorLocation::Unspecified,
+            format!("Expected a dictionary but got '{}'.", dict.to_string()),
+        )),
+    };
+
+    let mut instance = DoveInstance::new(Rc::clone(class));
+    for (key, value) in dict.iter() {
+        match key {
+            DictKey::StringKey(name) => instance.set(name.clone(), value.clone()),
+            DictKey::NumberKey(_) => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Field names must be strings but got '{}'.", key.stringify()),
+            )),
+        }
+    }
+
+    let instance = Literals::Instance(Rc::new(RefCell::new(instance)));
+    crate::stats::track(&instance);
+    Ok(instance)
+}
+
+fn class_of_value(value: &Literals) -> Option<Rc<DoveClass>> {
+    match value {
+        Literals::Class(class) => Some(Rc::clone(class)),
+        Literals::Instance(instance) => Some(Rc::clone(&instance.borrow().class)),
+        _ => None,
+    }
+}
+
+fn string_array(strings: Vec<String>) -> Literals {
+    let items = strings.into_iter().map(|string| Literals::String(string.into())).collect();
+    Literals::array(items)
+}
+
+fn expected_instance(value: &Literals) -> RuntimeError {
+    RuntimeError::new(
+        ErrorLocation::Unspecified,
+        format!("Expected an instance but got '{}'.", value.to_string()),
+    )
+}
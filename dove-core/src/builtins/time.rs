@@ -0,0 +1,189 @@
+use std::time::{Duration, Instant};
+
+use crate::builtins::*;
+use crate::dove_callable::BuiltinFunction;
+
+pub fn define(definer: &mut Definer) {
+    // `Instant` is not available on wasm32-unknown-unknown.
+    if cfg!(not(target_arch = "wasm32")) {
+        definer.function("benchmark", benchmark());
+    }
+
+    definer.function("seconds", duration("seconds", 1));
+    definer.function("minutes", duration("minutes", 60));
+    definer.function("hours", duration("hours", 60 * 60));
+    definer.function("days", duration("days", 24 * 60 * 60));
+    definer.function("humanize", humanize());
+
+    #[cfg(feature = "time-format")]
+    {
+        definer.function("format_time", format::format_time());
+        definer.function("parse_time", format::parse_time());
+    }
+}
+
+/// Call a function repeatedly and return timing statistics in seconds.
+fn benchmark() -> impl DoveCallable {
+    BuiltinFunction::new(2, |interpreter, args| {
+        let iterations = match args[1].clone().unwrap_usize() {
+            Ok(n) if n > 0 => n,
+            _ => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                "Number of iterations must be a positive integer.".to_string(),
+            )),
+        };
+
+        let mut total = Duration::new(0, 0);
+        let mut min = Duration::MAX;
+        let mut max = Duration::new(0, 0);
+        for _ in 0..iterations {
+            let start = Instant::now();
+            interpreter.call_value(args[0].clone(), &vec![], ErrorLocation::Unspecified)?;
+            let time = start.elapsed();
+            total += time;
+            min = min.min(time);
+            max = max.max(time);
+        }
+
+        Ok(make_dict(vec![
+            ("iterations", Literals::Int(iterations as i64)),
+            ("total", Literals::Number(total.as_secs_f64())),
+            ("mean", Literals::Number(total.as_secs_f64() / iterations as f64)),
+            ("min", Literals::Number(min.as_secs_f64())),
+            ("max", Literals::Number(max.as_secs_f64())),
+        ]))
+    })
+}
+
+/// A duration in seconds, e.g. `minutes(5)` is 300, so durations can be added to each other and to timestamps.
+fn duration(name: &'static str, unit: i64) -> impl DoveCallable {
+    BuiltinFunction::new(1, move |_, args| {
+        match &args[0] {
+            Literals::Int(count) => count.checked_mul(unit).map(Literals::Int).ok_or_else(|| integer_overflow(name)),
+            Literals::Number(count) => Ok(Literals::Number(count * unit as f64)),
+            other => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("'{}' expected a number but got '{}'.", name, other.to_string()),
+            )),
+        }
+    })
+}
+
+/// Units of `humanize`, largest first.
+const DURATION_UNITS: [(&str, u64); 4] = [("day", 24 * 60 * 60), ("hour", 60 * 60), ("minute", 60), ("second", 1)];
+
+/// Describe a duration in seconds, e.g. `humanize(3725)` is "1 hour, 2 minutes, 5 seconds".
+/// Durations of a minute or more are rounded to whole seconds.
+fn humanize() -> impl DoveCallable {
+    BuiltinFunction::new(1, |_, args| {
+        let seconds = match args[0].clone().unwrap_number() {
+            Ok(seconds) if seconds.is_finite() => seconds,
+            _ => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Expected a duration in seconds but got '{}'.", args[0].to_string()),
+            )),
+        };
+
+        let sign = if seconds < 0.0 { "-" } else { "" };
+        // At most millisecond precision.
+        let seconds = (seconds.abs() * 1000.0).round() / 1000.0;
+        if seconds < 60.0 {
+            let plural = if seconds == 1.0 { "" } else { "s" };
+            let amount = format!("{:.3}", seconds).trim_end_matches('0').trim_end_matches('.').to_string();
+            return Ok(Literals::String(format!("{}{} second{}", sign, amount, plural).into()));
+        }
+
+        let mut remaining = seconds.round() as u64;
+        let mut parts = vec![];
+        for (unit, length) in DURATION_UNITS.iter() {
+            let count = remaining / length;
+            remaining %= length;
+            if count > 0 {
+                parts.push(format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" }));
+            }
+        }
+
+        Ok(Literals::String(format!("{}{}", sign, parts.join(", ")).into()))
+    })
+}
+
+/// Timestamps are seconds since the Unix epoch, in UTC. Formats use strftime directives, e.g. `"%Y-%m-%d %H:%M:%S"`.
+#[cfg(feature = "time-format")]
+mod format {
+    use std::fmt::Write;
+
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+    use chrono::format::{Item, StrftimeItems};
+
+    use crate::builtins::*;
+    use crate::dove_callable::BuiltinFunction;
+
+    /// `format_time(timestamp, format)`
+    pub fn format_time() -> impl DoveCallable {
+        BuiltinFunction::new(2, |_, args| {
+            let timestamp = match args[0].clone().unwrap_number() {
+                Ok(timestamp) => timestamp,
+                Err(_) => return Err(error(format!("Expected a timestamp but got '{}'.", args[0].to_string()))),
+            };
+            let format = format_string(&args[1])?;
+
+            let seconds = timestamp.floor();
+            let nanos = ((timestamp - seconds) * 1e9).round().min(999_999_999.0) as u32;
+            let time = match DateTime::<Utc>::from_timestamp(seconds as i64, nanos) {
+                Some(time) if seconds.is_finite() => time,
+                _ => return Err(error(format!("The timestamp {} is out of range.", timestamp))),
+            };
+
+            let mut formatted = String::new();
+            write!(formatted, "{}", time.format_with_items(items(format)?.into_iter()))
+                .map_err(|_| error(format!("Cannot format the time with '{}'.", format)))?;
+            Ok(Literals::String(formatted.into()))
+        })
+    }
+
+    /// `parse_time(string, format)`. Times without an offset are in UTC, and dates without a time at midnight.
+    pub fn parse_time() -> impl DoveCallable {
+        BuiltinFunction::new(2, |_, args| {
+            let string = match &args[0] {
+                Literals::String(string) => string,
+                other => return Err(error(format!("Expected a string to parse but got '{}'.", other.to_string()))),
+            };
+            let format = format_string(&args[1])?;
+            items(format)?;
+
+            let time = DateTime::parse_from_str(string, format)
+                .map(|time| time.with_timezone(&Utc))
+                .or_else(|_| NaiveDateTime::parse_from_str(string, format).map(|time| time.and_utc()))
+                .or_else(|error| match NaiveDate::parse_from_str(string, format) {
+                    Ok(date) => Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()),
+                    Err(_) => Err(error),
+                })
+                .map_err(|reason| error(format!("Cannot parse '{}' with the format '{}': {}.", string, format, reason)))?;
+
+            Ok(match time.timestamp_subsec_nanos() {
+                0 => Literals::Int(time.timestamp()),
+                nanos => Literals::Number(time.timestamp() as f64 + nanos as f64 / 1e9),
+            })
+        })
+    }
+
+    fn format_string(value: &Literals) -> Result<&str, RuntimeError> {
+        match value {
+            Literals::String(format) => Ok(format),
+            other => Err(error(format!("Expected a format string but got '{}'.", other.to_string()))),
+        }
+    }
+
+    /// The directives of a format, or an error if it has an unknown one.
+    fn items(format: &str) -> Result<Vec<Item<'_>>, RuntimeError> {
+        let items: Vec<Item> = StrftimeItems::new(format).collect();
+        if items.iter().any(|item| matches!(item, Item::Error)) {
+            return Err(error(format!("Invalid time format '{}'.", format)));
+        }
+        Ok(items)
+    }
+
+    fn error(message: String) -> RuntimeError {
+        RuntimeError::new(ErrorLocation::Unspecified, message)
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use lazy_static::lazy_static;
 
 use crate::token::TokenType;
+use crate::version::LanguageVersion;
 
 // Generate constants for keywords
 macro_rules! keywords {
@@ -25,9 +26,14 @@ macro_rules! keywords {
 
 keywords! {
     "and"       => AND,
+    "as"        => AS,
     "break"     => BREAK,
+    "by"        => BY,
+    "case"      => CASE,
+    "catch"     => CATCH,
     "class"     => CLASS,
     "continue"  => CONTINUE,
+    "del"       => DEL,
     "else"      => ELSE,
     "false"     => FALSE,
     "fun"       => FUN,
@@ -38,13 +44,44 @@ keywords! {
     "if"        => IF,
     "lambda"    => LAMBDA,
     "let"       => LET,
+    "match"     => MATCH,
     "nil"       => NIL,
     "not"       => NOT,
     "or"        => OR,
     "print"     => PRINT,
     "return"    => RETURN,
     "super"     => SUPER,
+    "switch"    => SWITCH,
     "self"      => SELF,
     "true"      => TRUE,
+    "try"       => TRY,
     "while"     => WHILE,
+    "with"      => WITH,
+}
+
+/// Keywords added after the first language version, with the version that introduced them.
+/// Scripts targeting an older version can still use them as identifiers.
+static NEW_KEYWORDS: [(&str, LanguageVersion); 9] = [
+    ("as", LanguageVersion::new(0, 2)),
+    ("by", LanguageVersion::new(0, 2)),
+    ("case", LanguageVersion::new(0, 2)),
+    ("catch", LanguageVersion::new(0, 2)),
+    ("del", LanguageVersion::new(0, 2)),
+    ("match", LanguageVersion::new(0, 2)),
+    ("switch", LanguageVersion::new(0, 2)),
+    ("try", LanguageVersion::new(0, 2)),
+    ("with", LanguageVersion::new(0, 2)),
+];
+
+/// Whether `keyword` is reserved in scripts targeting `version`.
+pub fn is_keyword_in(keyword: &str, version: LanguageVersion) -> bool {
+    NEW_KEYWORDS.iter().all(|(new_keyword, since)| *new_keyword != keyword || version >= *since)
+}
+
+/// Whether `name` can be written as an identifier: a letter or `_` followed by letters, digits or `_`,
+/// which is not a keyword.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let starts_well = matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_');
+    starts_well && chars.all(|c| c.is_alphanumeric() || c == '_') && !KEYWORD_TOKENS.contains_key(name)
 }
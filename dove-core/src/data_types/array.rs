@@ -3,7 +3,8 @@ use std::cell::RefCell;
 
 use crate::data_types::*;
 use crate::error_handler::{RuntimeError, ErrorLocation};
-use crate::dove_callable::{DoveCallable, BuiltinFunction};
+use crate::dove_callable::{DoveCallable, BuiltinFunction, Arity};
+use crate::builtins::sort_values;
 use crate::token::Literals;
 
 impl DoveObject for Rc<RefCell<Vec<Literals>>> {
@@ -14,23 +15,34 @@ impl DoveObject for Rc<RefCell<Vec<Literals>>> {
             "push" => Ok(Literals::Function(Rc::new(array_append(self)))),
             "pop" => Ok(Literals::Function(Rc::new(array_pop(self)))),
             "remove" => Ok(Literals::Function(Rc::new(array_remove(self)))),
+            "sort" => Ok(Literals::Function(Rc::new(array_sort(self)))),
+            "map" => Ok(Literals::Function(Rc::new(array_map(self)))),
+            "filter" => Ok(Literals::Function(Rc::new(array_filter(self)))),
+            "reduce" => Ok(Literals::Function(Rc::new(array_reduce(self)))),
+            "find" => Ok(Literals::Function(Rc::new(array_find(self)))),
+            "any" => Ok(Literals::Function(Rc::new(array_any(self)))),
+            "all" => Ok(Literals::Function(Rc::new(array_all(self)))),
             _ => Err(Error::CannotGetProperty),
         }
     }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["len", "is_empty", "push", "pop", "remove", "sort", "map", "filter", "reduce", "find", "any", "all"])
+    }
 }
 
 fn array_len(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
     let array = Rc::clone(array);
 
-    BuiltinFunction::new(0, move |_| {
-        Ok(Literals::Number(array.borrow().len() as f64))
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Int(array.borrow().len() as i64))
     })
 }
 
 fn array_is_empty(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
     let array = Rc::clone(array);
 
-    BuiltinFunction::new(0, move |_| {
+    BuiltinFunction::new(0, move |_, _| {
         Ok(Literals::Boolean(array.borrow().len() == 0))
     })
 }
@@ -38,8 +50,8 @@ fn array_is_empty(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
 fn array_append(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
     let array = Rc::clone(array);
 
-    BuiltinFunction::new(1, move |args| {
-        array.borrow_mut().push(args[0].clone());
+    BuiltinFunction::new(1, move |_, args| {
+        borrow_to_change(&array)?.push(args[0].clone());
         Ok(Literals::Nil)
     })
 }
@@ -47,8 +59,8 @@ fn array_append(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
 fn array_pop(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
     let array = Rc::clone(array);
 
-    BuiltinFunction::new(0, move |_| {
-        match array.borrow_mut().pop() {
+    BuiltinFunction::new(0, move |_, _| {
+        match borrow_to_change(&array)?.pop() {
             Some(v) => Ok(v),
             None => Ok(Literals::Nil),
         }
@@ -58,7 +70,7 @@ fn array_pop(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
 fn array_remove(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
     let array = Rc::clone(array);
 
-    BuiltinFunction::new(1, move |args| {
+    BuiltinFunction::new(1, move |_, args| {
         let index = match args[0].clone().unwrap_usize() {
             Ok(i) => i,
             _ => return Err(RuntimeError::new(
@@ -74,6 +86,118 @@ fn array_remove(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
             ));
         }
 
-        Ok(array.borrow_mut().remove(index))
+        Ok(borrow_to_change(&array)?.remove(index))
+    })
+}
+
+fn array_sort(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
+    let array = Rc::clone(array);
+
+    BuiltinFunction::new(Arity::Range(0, 1), move |interpreter, args| {
+        // Sort a copy, so the key function may access the array.
+        let items = array.borrow().clone();
+        let sorted = sort_values(interpreter, items, args.first())?;
+        *borrow_to_change(&array)? = sorted;
+        Ok(Literals::Nil)
+    })
+}
+
+// The methods below call back into scripts, so they iterate over a copy
+// of the items, which the callback may change without affecting the loop.
+
+fn array_map(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
+    let array = Rc::clone(array);
+
+    BuiltinFunction::new(1, move |interpreter, args| {
+        let items = array.borrow().clone();
+        let mut mapped = Vec::with_capacity(items.len());
+        for item in items {
+            mapped.push(interpreter.call_value(args[0].clone(), &vec![item], ErrorLocation::Unspecified)?);
+        }
+        Ok(Literals::array(mapped))
+    })
+}
+
+fn array_filter(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
+    let array = Rc::clone(array);
+
+    BuiltinFunction::new(1, move |interpreter, args| {
+        let items = array.borrow().clone();
+        let mut kept = vec![];
+        for item in items {
+            let keep = interpreter.call_value(args[0].clone(), &vec![item.clone()], ErrorLocation::Unspecified)?;
+            if interpreter.truthy(&keep, ErrorLocation::Unspecified)? {
+                kept.push(item);
+            }
+        }
+        Ok(Literals::array(kept))
+    })
+}
+
+/// `reduce(f, initial)` calls `f(accumulator, item)` for each item.
+/// Without an initial value, the first item is used instead.
+fn array_reduce(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
+    let array = Rc::clone(array);
+
+    BuiltinFunction::new(Arity::Range(1, 2), move |interpreter, args| {
+        let mut items = array.borrow().clone().into_iter();
+        let mut accumulator = match args.get(1).cloned().or_else(|| items.next()) {
+            Some(initial) => initial,
+            None => return Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                "Cannot reduce an empty array without an initial value.".to_string(),
+            )),
+        };
+
+        for item in items {
+            accumulator = interpreter.call_value(args[0].clone(), &vec![accumulator, item], ErrorLocation::Unspecified)?;
+        }
+        Ok(accumulator)
+    })
+}
+
+/// The first item the function returns a truthy value for, or nil.
+fn array_find(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
+    let array = Rc::clone(array);
+
+    BuiltinFunction::new(1, move |interpreter, args| {
+        let items = array.borrow().clone();
+        for item in items {
+            let found = interpreter.call_value(args[0].clone(), &vec![item.clone()], ErrorLocation::Unspecified)?;
+            if interpreter.truthy(&found, ErrorLocation::Unspecified)? {
+                return Ok(item);
+            }
+        }
+        Ok(Literals::Nil)
+    })
+}
+
+fn array_any(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
+    let array = Rc::clone(array);
+
+    BuiltinFunction::new(1, move |interpreter, args| {
+        let items = array.borrow().clone();
+        for item in items {
+            let result = interpreter.call_value(args[0].clone(), &vec![item], ErrorLocation::Unspecified)?;
+            if interpreter.truthy(&result, ErrorLocation::Unspecified)? {
+                return Ok(Literals::Boolean(true));
+            }
+        }
+        Ok(Literals::Boolean(false))
+    })
+}
+
+fn array_all(array: &Rc<RefCell<Vec<Literals>>>) -> impl DoveCallable {
+    let array = Rc::clone(array);
+
+    BuiltinFunction::new(1, move |interpreter, args| {
+        let items = array.borrow().clone();
+        for item in items {
+            let result = interpreter.call_value(args[0].clone(), &vec![item], ErrorLocation::Unspecified)?;
+            if !interpreter.truthy(&result, ErrorLocation::Unspecified)? {
+                return Ok(Literals::Boolean(false));
+            }
+        }
+        Ok(Literals::Boolean(true))
     })
 }
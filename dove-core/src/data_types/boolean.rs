@@ -0,0 +1,32 @@
+use std::rc::Rc;
+
+use crate::data_types::*;
+use crate::dove_callable::{DoveCallable, BuiltinFunction};
+use crate::token::Literals;
+
+impl DoveObject for bool {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        match name {
+            "not" => Ok(Literals::Function(Rc::new(boolean_not(*self)))),
+            "to_number" => Ok(Literals::Function(Rc::new(boolean_to_number(*self)))),
+            _ => Err(Error::CannotGetProperty),
+        }
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["not", "to_number"])
+    }
+}
+
+fn boolean_not(boolean: bool) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Boolean(!boolean))
+    })
+}
+
+/// 1 for true and 0 for false.
+fn boolean_to_number(boolean: bool) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Int(if boolean { 1 } else { 0 }))
+    })
+}
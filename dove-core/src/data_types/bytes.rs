@@ -0,0 +1,45 @@
+use std::rc::Rc;
+
+use crate::data_types::*;
+use crate::dove_callable::{DoveCallable, BuiltinFunction};
+use crate::token::Literals;
+
+impl DoveObject for Rc<Vec<u8>> {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        match name {
+            "len" => Ok(Literals::Function(Rc::new(bytes_len(self)))),
+            "is_empty" => Ok(Literals::Function(Rc::new(bytes_is_empty(self)))),
+            "to_array" => Ok(Literals::Function(Rc::new(bytes_to_array(self)))),
+            _ => Err(Error::CannotGetProperty),
+        }
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["len", "is_empty", "to_array"])
+    }
+}
+
+fn bytes_len(bytes: &Rc<Vec<u8>>) -> impl DoveCallable {
+    let bytes = Rc::clone(bytes);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Int(bytes.len() as i64))
+    })
+}
+
+fn bytes_is_empty(bytes: &Rc<Vec<u8>>) -> impl DoveCallable {
+    let bytes = Rc::clone(bytes);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Boolean(bytes.is_empty()))
+    })
+}
+
+fn bytes_to_array(bytes: &Rc<Vec<u8>>) -> impl DoveCallable {
+    let bytes = Rc::clone(bytes);
+
+    BuiltinFunction::new(0, move |_, _| {
+        let numbers = bytes.iter().map(|byte| Literals::Int(*byte as i64)).collect();
+        Ok(Literals::array(numbers))
+    })
+}
@@ -0,0 +1,29 @@
+use std::rc::Rc;
+
+use crate::data_types::*;
+use crate::builtins::reflection::instance_from_dict;
+use crate::dove_callable::BuiltinFunction;
+use crate::dove_class::DoveClass;
+
+/// Methods can be called from their class, without an instance to bind `self` to.
+/// A method named `from_dict` takes precedence over the builtin one.
+impl DoveObject for Rc<DoveClass> {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        match (self.find_method(name), name) {
+            (Some(method), _) => Ok(Literals::Function(Rc::new(method.unbound()))),
+            (None, "from_dict") => {
+                let class = Rc::clone(self);
+                Ok(Literals::Function(Rc::new(BuiltinFunction::new(1, move |_, args| {
+                    instance_from_dict(&class, &args[0])
+                }))))
+            },
+            _ => Err(Error::CannotGetProperty),
+        }
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        let mut names = self.method_names();
+        names.push("from_dict".to_string());
+        names
+    }
+}
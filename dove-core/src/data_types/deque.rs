@@ -0,0 +1,103 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::data_types::*;
+use crate::dove_callable::{DoveCallable, BuiltinFunction};
+use crate::token::Literals;
+
+impl DoveObject for Rc<RefCell<VecDeque<Literals>>> {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        match name {
+            "len" => Ok(Literals::Function(Rc::new(deque_len(self)))),
+            "is_empty" => Ok(Literals::Function(Rc::new(deque_is_empty(self)))),
+            "push_back" => Ok(Literals::Function(Rc::new(deque_push_back(self)))),
+            "push_front" => Ok(Literals::Function(Rc::new(deque_push_front(self)))),
+            "pop_back" => Ok(Literals::Function(Rc::new(deque_pop_back(self)))),
+            "pop_front" => Ok(Literals::Function(Rc::new(deque_pop_front(self)))),
+            "back" => Ok(Literals::Function(Rc::new(deque_back(self)))),
+            "front" => Ok(Literals::Function(Rc::new(deque_front(self)))),
+            "to_array" => Ok(Literals::Function(Rc::new(deque_to_array(self)))),
+            _ => Err(Error::CannotGetProperty),
+        }
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["len", "is_empty", "push_back", "push_front", "pop_back", "pop_front", "back", "front", "to_array"])
+    }
+}
+
+fn deque_len(deque: &Rc<RefCell<VecDeque<Literals>>>) -> impl DoveCallable {
+    let deque = Rc::clone(deque);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Int(deque.borrow().len() as i64))
+    })
+}
+
+fn deque_is_empty(deque: &Rc<RefCell<VecDeque<Literals>>>) -> impl DoveCallable {
+    let deque = Rc::clone(deque);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Boolean(deque.borrow().is_empty()))
+    })
+}
+
+fn deque_push_back(deque: &Rc<RefCell<VecDeque<Literals>>>) -> impl DoveCallable {
+    let deque = Rc::clone(deque);
+
+    BuiltinFunction::new(1, move |_, args| {
+        borrow_to_change(&deque)?.push_back(args[0].clone());
+        Ok(Literals::Nil)
+    })
+}
+
+fn deque_push_front(deque: &Rc<RefCell<VecDeque<Literals>>>) -> impl DoveCallable {
+    let deque = Rc::clone(deque);
+
+    BuiltinFunction::new(1, move |_, args| {
+        borrow_to_change(&deque)?.push_front(args[0].clone());
+        Ok(Literals::Nil)
+    })
+}
+
+fn deque_pop_back(deque: &Rc<RefCell<VecDeque<Literals>>>) -> impl DoveCallable {
+    let deque = Rc::clone(deque);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(borrow_to_change(&deque)?.pop_back().unwrap_or(Literals::Nil))
+    })
+}
+
+fn deque_pop_front(deque: &Rc<RefCell<VecDeque<Literals>>>) -> impl DoveCallable {
+    let deque = Rc::clone(deque);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(borrow_to_change(&deque)?.pop_front().unwrap_or(Literals::Nil))
+    })
+}
+
+fn deque_back(deque: &Rc<RefCell<VecDeque<Literals>>>) -> impl DoveCallable {
+    let deque = Rc::clone(deque);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(deque.borrow().back().cloned().unwrap_or(Literals::Nil))
+    })
+}
+
+fn deque_front(deque: &Rc<RefCell<VecDeque<Literals>>>) -> impl DoveCallable {
+    let deque = Rc::clone(deque);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(deque.borrow().front().cloned().unwrap_or(Literals::Nil))
+    })
+}
+
+fn deque_to_array(deque: &Rc<RefCell<VecDeque<Literals>>>) -> impl DoveCallable {
+    let deque = Rc::clone(deque);
+
+    BuiltinFunction::new(0, move |_, _| {
+        let items = deque.borrow().iter().cloned().collect();
+        Ok(Literals::array(items))
+    })
+}
@@ -0,0 +1,50 @@
+use std::rc::Rc;
+
+use crate::data_types::*;
+use crate::dove_callable::{DoveCallable, BuiltinFunction};
+use crate::error_handler::{RuntimeError, ErrorLocation};
+use crate::token::Literals;
+
+/// A read-only view of an array or dictionary.
+/// It has the methods of the value, which fail if they would change it.
+pub struct Frozen(pub Literals);
+
+impl DoveObject for Frozen {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        match self.0.as_object().get_property(name)? {
+            Literals::Function(method) => Ok(Literals::Function(Rc::new(frozen_method(name, &self.0, method)))),
+            value => Ok(value.freeze()),
+        }
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        self.0.as_object().property_names()
+    }
+}
+
+/// A method of a frozen value. The value is borrowed while the method runs, so a method changing
+/// it gets an error instead, and what the method returns is frozen too, e.g. an item found in it.
+fn frozen_method(name: &str, value: &Literals, method: Rc<dyn DoveCallable>) -> impl DoveCallable {
+    let message = format!("Cannot call '{}', the {} is frozen.", name, value.to_string().to_lowercase());
+    let value = value.clone();
+
+    BuiltinFunction::new(method.arity(), move |interpreter, args| {
+        let result = match &value {
+            Literals::Array(array) => {
+                let _items = array.borrow();
+                method.call(interpreter, args)
+            },
+            Literals::Dictionary(dict) => {
+                let _entries = dict.borrow();
+                method.call(interpreter, args)
+            },
+            _ => method.call(interpreter, args),
+        };
+
+        match result {
+            Ok(value) => Ok(value.freeze()),
+            Err(error) if &*error.message == CHANGED_WHILE_READ => Err(RuntimeError::new(ErrorLocation::Unspecified, message.clone())),
+            Err(error) => Err(error),
+        }
+    })
+}
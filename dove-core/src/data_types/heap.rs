@@ -0,0 +1,170 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+
+use crate::data_types::*;
+use crate::error_handler::{RuntimeError, ErrorLocation};
+use crate::dove_callable::{DoveCallable, BuiltinFunction, Arity};
+use crate::builtins::compare_values;
+use crate::token::Literals;
+
+/// A priority queue, which pops the item with the smallest priority first.
+/// Items with equal priorities are popped in the order they were pushed.
+#[derive(Clone, Default)]
+pub struct Heap {
+    /// Binary heap of (priority, push count, item).
+    entries: Vec<(Literals, usize, Literals)>,
+    pushed: usize,
+}
+
+impl Heap {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Push an item, failing if its priority cannot be compared with the others.
+    pub fn push(&mut self, item: Literals, priority: Literals) -> std::result::Result<(), String> {
+        if let Some((other, _, _)) = self.entries.first() {
+            if compare_values(&priority, other).is_none() {
+                return Err(format!("Cannot compare priority '{}' with '{}'.", priority.to_string(), other.to_string()));
+            }
+        }
+
+        self.entries.push((priority, self.pushed, item));
+        self.pushed += 1;
+        self.sift_up(self.entries.len() - 1);
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<Literals> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let (_, _, item) = self.entries.swap_remove(0);
+        self.sift_down(0);
+        Some(item)
+    }
+
+    pub fn peek(&self) -> Option<&Literals> {
+        self.entries.first().map(|(_, _, item)| item)
+    }
+
+    /// All items, in the order they would be popped.
+    pub fn items(&self) -> Vec<Literals> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|a, b| compare_entries(a, b));
+        entries.into_iter().map(|(_, _, item)| item.clone()).collect()
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if compare_entries(&self.entries[index], &self.entries[parent]) != Ordering::Less {
+                break;
+            }
+            self.entries.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let mut smallest = index;
+            for child in &[2 * index + 1, 2 * index + 2] {
+                if *child < self.entries.len()
+                    && compare_entries(&self.entries[*child], &self.entries[smallest]) == Ordering::Less {
+                    smallest = *child;
+                }
+            }
+
+            if smallest == index {
+                break;
+            }
+            self.entries.swap(index, smallest);
+            index = smallest;
+        }
+    }
+}
+
+fn compare_entries(a: &(Literals, usize, Literals), b: &(Literals, usize, Literals)) -> Ordering {
+    compare_values(&a.0, &b.0)
+        .unwrap_or(Ordering::Equal)
+        .then(a.1.cmp(&b.1))
+}
+
+impl DoveObject for Rc<RefCell<Heap>> {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        match name {
+            "len" => Ok(Literals::Function(Rc::new(heap_len(self)))),
+            "is_empty" => Ok(Literals::Function(Rc::new(heap_is_empty(self)))),
+            "push" => Ok(Literals::Function(Rc::new(heap_push(self)))),
+            "pop" => Ok(Literals::Function(Rc::new(heap_pop(self)))),
+            "peek" => Ok(Literals::Function(Rc::new(heap_peek(self)))),
+            "to_array" => Ok(Literals::Function(Rc::new(heap_to_array(self)))),
+            _ => Err(Error::CannotGetProperty),
+        }
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["len", "is_empty", "push", "pop", "peek", "to_array"])
+    }
+}
+
+fn heap_len(heap: &Rc<RefCell<Heap>>) -> impl DoveCallable {
+    let heap = Rc::clone(heap);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Int(heap.borrow().len() as i64))
+    })
+}
+
+fn heap_is_empty(heap: &Rc<RefCell<Heap>>) -> impl DoveCallable {
+    let heap = Rc::clone(heap);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Boolean(heap.borrow().is_empty()))
+    })
+}
+
+/// `push(item, priority)`, the item is its own priority if none is given.
+fn heap_push(heap: &Rc<RefCell<Heap>>) -> impl DoveCallable {
+    let heap = Rc::clone(heap);
+
+    BuiltinFunction::new(Arity::Range(1, 2), move |_, args| {
+        let priority = args.get(1).unwrap_or(&args[0]).clone();
+
+        match borrow_to_change(&heap)?.push(args[0].clone(), priority) {
+            Ok(()) => Ok(Literals::Nil),
+            Err(message) => Err(RuntimeError::new(ErrorLocation::Unspecified, message)),
+        }
+    })
+}
+
+fn heap_pop(heap: &Rc<RefCell<Heap>>) -> impl DoveCallable {
+    let heap = Rc::clone(heap);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(borrow_to_change(&heap)?.pop().unwrap_or(Literals::Nil))
+    })
+}
+
+fn heap_peek(heap: &Rc<RefCell<Heap>>) -> impl DoveCallable {
+    let heap = Rc::clone(heap);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(heap.borrow().peek().cloned().unwrap_or(Literals::Nil))
+    })
+}
+
+fn heap_to_array(heap: &Rc<RefCell<Heap>>) -> impl DoveCallable {
+    let heap = Rc::clone(heap);
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::array(heap.borrow().items()))
+    })
+}
@@ -0,0 +1,73 @@
+use std::any::Any;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::data_types::*;
+
+/// A Rust type scripts can use like an instance of a class, usually implemented with `#[derive(DoveClass)]`.
+/// Values are shared with scripts through `Rc<RefCell<Self>>`, so changes on either side are seen by both.
+pub trait HostClass: Sized + 'static {
+    /// The name printed for values of this type.
+    fn class_name() -> &'static str;
+
+    fn get_property(this: &Rc<RefCell<Self>>, name: &str) -> Result<Literals>;
+
+    fn set_property(_this: &Rc<RefCell<Self>>, _name: &str, _value: Literals) -> Result<()> {
+        Err(Error::CannotSetProperty)
+    }
+}
+
+/// Methods of a host class, usually implemented with `#[dove_methods]` on an `impl` block.
+pub trait HostMethods: Sized {
+    /// The method called `name`, bound to `this`.
+    fn method(this: &Rc<RefCell<Self>>, name: &str) -> Option<Literals>;
+}
+
+/// A value of some host class, as stored in `Literals::Object`.
+pub trait HostValue {
+    fn class_name(&self) -> &'static str;
+
+    /// The properties of the value, sharing its state.
+    fn object(self: Rc<Self>) -> Box<dyn DoveObject>;
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any>;
+}
+
+impl<T: HostClass> HostValue for RefCell<T> {
+    fn class_name(&self) -> &'static str {
+        T::class_name()
+    }
+
+    fn object(self: Rc<Self>) -> Box<dyn DoveObject> {
+        Box::new(HostObject(self))
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
+
+struct HostObject<T>(Rc<RefCell<T>>);
+
+impl<T: HostClass> DoveObject for HostObject<T> {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        T::get_property(&self.0, name)
+    }
+
+    fn set_property(&mut self, name: &str, value: Literals) -> Result<()> {
+        T::set_property(&self.0, name, value)
+    }
+}
+
+/// Pass a value of a host class to scripts.
+pub fn host_value<T: HostClass>(value: T) -> Literals {
+    Literals::Object(Rc::new(RefCell::new(value)))
+}
+
+/// The value of host class `T` a script got from `host_value`, sharing its state, or `None` for other values.
+pub fn host_ref<T: HostClass>(value: &Literals) -> Option<Rc<RefCell<T>>> {
+    match value {
+        Literals::Object(object) => Rc::clone(object).as_any().downcast().ok(),
+        _ => None,
+    }
+}
@@ -16,4 +16,11 @@ impl DoveObject for Rc<RefCell<DoveInstance>> {
         self.borrow_mut().set(name.to_string(), value);
         Ok(())
     }
+
+    fn property_names(&self) -> Vec<String> {
+        let instance = self.borrow();
+        let mut names = instance.field_names();
+        names.extend(instance.class.method_names());
+        names
+    }
 }
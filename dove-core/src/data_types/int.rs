@@ -0,0 +1,36 @@
+use std::rc::Rc;
+
+use crate::data_types::*;
+use crate::builtins::integer_overflow;
+use crate::dove_callable::{DoveCallable, BuiltinFunction};
+use crate::token::Literals;
+
+/// The same properties as numbers, giving integers where numbers give whole numbers.
+impl DoveObject for i64 {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        let int = *self;
+        let value = match name {
+            "fract" => Literals::Int(0),
+            // The absolute value of the smallest integer does not fit in an integer.
+            "abs" => return Ok(Literals::Function(Rc::new(BuiltinFunction::new(0, move |_, _| {
+                int.checked_abs().map(Literals::Int).ok_or_else(|| integer_overflow("abs"))
+            })))),
+            "floor" | "ceil" | "to_int" => Literals::Int(int),
+            "sign" => Literals::Int(int.signum()),
+            "is_int" | "is_finite" => Literals::Boolean(true),
+            "is_nan" => Literals::Boolean(false),
+            _ => return Err(Error::CannotGetProperty),
+        };
+
+        Ok(Literals::Function(Rc::new(int_method(value))))
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["fract", "abs", "floor", "ceil", "to_int", "sign", "is_int", "is_nan", "is_finite"])
+    }
+}
+
+/// A method without arguments returning `value`.
+fn int_method(value: Literals) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| Ok(value.clone()))
+}
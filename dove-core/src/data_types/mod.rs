@@ -1,10 +1,24 @@
+use std::cell::{RefCell, RefMut};
+
+use crate::error_handler::{ErrorLocation, RuntimeError};
 use crate::token::Literals;
 
 pub mod number;
+pub mod int;
+pub mod boolean;
+pub mod nil;
 pub mod string;
 pub mod array;
+pub mod tuple;
+pub mod bytes;
 pub mod dict;
+pub mod deque;
+pub mod frozen;
+pub mod heap;
+pub mod module;
+pub mod host;
 pub mod instance;
+pub mod class;
 
 // TODO: add more errors?
 // TODO: or just use Option instead?
@@ -23,4 +37,44 @@ pub trait DoveObject {
     fn set_property(&mut self, _name: &str, _value: Literals) -> Result<()> {
         Err(Error::CannotSetProperty)
     }
+
+    /// Names of the properties `get_property` knows, for suggestions when a name is misspelled.
+    fn property_names(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// Old names of builtin properties, with the names they were renamed to. Collections use `len` and
+/// `is_empty` throughout, the old names still work but are deprecated.
+static DEPRECATED_NAMES: [(&str, &str); 3] = [
+    ("length", "len"),
+    ("size", "len"),
+    ("empty", "is_empty"),
+];
+
+/// The current name of a deprecated builtin property, e.g. `len` for `length`.
+pub fn canonical_name(name: &str) -> Option<&'static str> {
+    DEPRECATED_NAMES.iter()
+        .find(|(deprecated, _)| *deprecated == name)
+        .map(|(_, canonical)| *canonical)
 }
+
+/// Borrow a collection to change it. Builtins calling back into scripts work on a copy of the items,
+/// so it can only be borrowed already if host code reading it runs a script, which gets an error instead of a panic.
+pub(crate) fn borrow_to_change<T>(collection: &RefCell<T>) -> std::result::Result<RefMut<'_, T>, RuntimeError> {
+    collection.try_borrow_mut().map_err(|_| RuntimeError::new(
+        ErrorLocation::Unspecified,
+        CHANGED_WHILE_READ.to_string(),
+    ))
+}
+
+/// The error of `borrow_to_change`, which frozen views report as changing a frozen value.
+const CHANGED_WHILE_READ: &str = "Cannot change a collection while it is being read.";
+
+/// The names as owned strings, for `property_names`.
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+/// An object without properties.
+impl DoveObject for () {}
@@ -0,0 +1,37 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::data_types::*;
+use crate::environment::Environment;
+
+/// The global scope of a file imported with `import "path" as name` or `from "path" import name`.
+/// A file runs once, later imports share the same module.
+pub struct Module {
+    pub path: String,
+    pub environment: Rc<RefCell<Environment>>,
+}
+
+impl Module {
+    pub fn new(path: String) -> Module {
+        Module {
+            path,
+            environment: Rc::new(RefCell::new(Environment::new(None))),
+        }
+    }
+
+    /// The value of a variable, function or class defined by the module.
+    pub fn get(&self, name: &str) -> Option<Literals> {
+        self.environment.borrow().get(name)
+    }
+}
+
+/// The properties of a module are its definitions, which scripts can read but not replace.
+impl DoveObject for Rc<Module> {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        self.get(name).ok_or(Error::CannotGetProperty)
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        self.environment.borrow().names()
+    }
+}
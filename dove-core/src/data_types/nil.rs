@@ -0,0 +1,28 @@
+use std::rc::Rc;
+
+use crate::data_types::*;
+use crate::dove_callable::{DoveCallable, BuiltinFunction};
+use crate::token::Literals;
+
+/// The properties of `nil`.
+pub struct Nil;
+
+impl DoveObject for Nil {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        match name {
+            "or_else" => Ok(Literals::Function(Rc::new(nil_or_else()))),
+            _ => Err(Error::CannotGetProperty),
+        }
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["or_else"])
+    }
+}
+
+/// `nil.or_else(default)` is the default.
+fn nil_or_else() -> impl DoveCallable {
+    BuiltinFunction::new(1, move |_, args| {
+        Ok(args[0].clone())
+    })
+}
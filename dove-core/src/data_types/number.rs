@@ -11,32 +11,78 @@ impl DoveObject for f64 {
             "abs" => Ok(Literals::Function(Rc::new(number_abs(*self)))),
             "floor" => Ok(Literals::Function(Rc::new(number_floor(*self)))),
             "ceil" => Ok(Literals::Function(Rc::new(number_ceil(*self)))),
+            "to_int" => Ok(Literals::Function(Rc::new(number_to_int(*self)))),
+            "sign" => Ok(Literals::Function(Rc::new(number_sign(*self)))),
+            "is_int" => Ok(Literals::Function(Rc::new(number_is_int(*self)))),
+            "is_nan" => Ok(Literals::Function(Rc::new(number_is_nan(*self)))),
+            "is_finite" => Ok(Literals::Function(Rc::new(number_is_finite(*self)))),
             _ => Err(Error::CannotGetProperty),
         }
     }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["fract", "abs", "floor", "ceil", "to_int", "sign", "is_int", "is_nan", "is_finite"])
+    }
 }
 
 fn number_fract(number: f64) -> impl DoveCallable {
-    BuiltinFunction::new(0, move |_| {
+    BuiltinFunction::new(0, move |_, _| {
         Ok(Literals::Number(number.fract()))
     })
 }
 
 fn number_abs(number: f64) -> impl DoveCallable {
-    BuiltinFunction::new(0, move |_| {
+    BuiltinFunction::new(0, move |_, _| {
         Ok(Literals::Number(number.abs()))
     })
 }
 
 fn number_floor(number: f64) -> impl DoveCallable {
-    BuiltinFunction::new(0, move |_| {
+    BuiltinFunction::new(0, move |_, _| {
         Ok(Literals::Number(number.floor()))
     })
 }
 
 fn number_ceil(number: f64) -> impl DoveCallable {
-    BuiltinFunction::new(0, move |_| {
+    BuiltinFunction::new(0, move |_, _| {
         Ok(Literals::Number(number.ceil()))
     })
 }
 
+/// The number without its fractional part, rounded towards zero, as an integer if it fits in one.
+fn number_to_int(number: f64) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(match Literals::Number(number.trunc()).unwrap_int() {
+            Some(int) => Literals::Int(int),
+            None => Literals::Number(number.trunc()),
+        })
+    })
+}
+
+/// -1 for negative numbers, 1 for positive ones and 0 for zero.
+fn number_sign(number: f64) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        let sign = if number == 0.0 || number.is_nan() { number } else { number.signum() };
+        Ok(Literals::Number(sign))
+    })
+}
+
+fn number_is_int(number: f64) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Boolean(number.is_finite() && number.fract() == 0.0))
+    })
+}
+
+/// Whether the number is NaN, e.g. the result of `0 / 0`.
+fn number_is_nan(number: f64) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Boolean(number.is_nan()))
+    })
+}
+
+/// Whether the number is neither infinite nor NaN.
+fn number_is_finite(number: f64) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Boolean(number.is_finite()))
+    })
+}
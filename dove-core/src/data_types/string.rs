@@ -1,37 +1,98 @@
 use std::rc::Rc;
-use std::cell::RefCell;
+
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::data_types::*;
-use crate::dove_callable::{DoveCallable, BuiltinFunction};
+use crate::error_handler::{RuntimeError, ErrorLocation};
+use crate::dove_callable::{DoveCallable, BuiltinFunction, Arity};
 use crate::token::Literals;
 
 impl DoveObject for String {
     fn get_property(&mut self, name: &str) -> Result<Literals> {
         match name {
             "len" => Ok(Literals::Function(Rc::new(string_len(self)))),
+            "char_len" => Ok(Literals::Function(Rc::new(string_char_len(self)))),
+            "is_empty" => Ok(Literals::Function(Rc::new(string_is_empty(self)))),
             "chars" => Ok(Literals::Function(Rc::new(string_chars(self)))),
+            "graphemes" => Ok(Literals::Function(Rc::new(string_graphemes(self)))),
+            "slice" => Ok(Literals::Function(Rc::new(string_slice(self)))),
             _ => Err(Error::CannotGetProperty),
         }
     }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["len", "char_len", "is_empty", "chars", "graphemes", "slice"])
+    }
 }
 
+/// Length in bytes of the UTF-8 encoded string, see `char_len` for the number of characters.
 fn string_len(string: &str) -> impl DoveCallable {
     let string = string.to_string();
 
-    BuiltinFunction::new(0, move |_| {
-        Ok(Literals::Number(string.len() as f64))
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Int(string.len() as i64))
+    })
+}
+
+fn string_is_empty(string: &str) -> impl DoveCallable {
+    let is_empty = string.is_empty();
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Boolean(is_empty))
     })
 }
 
 fn string_chars(string: &str) -> impl DoveCallable {
     let string = string.to_string();
 
-    BuiltinFunction::new(0, move |_| {
+    BuiltinFunction::new(0, move |_, _| {
         let char_literals = string.chars()
-            .map(|c| c.to_string())
-            .map(Literals::String)
+            .map(|c| Literals::String(c.to_string().into()))
+            .collect();
+
+        Ok(Literals::array(char_literals))
+    })
+}
+
+fn string_char_len(string: &str) -> impl DoveCallable {
+    let string = string.to_string();
+
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Int(string.chars().count() as i64))
+    })
+}
+
+/// Split into user-perceived characters, e.g. an "e" followed by a combining accent stays together.
+fn string_graphemes(string: &str) -> impl DoveCallable {
+    let string = string.to_string();
+
+    BuiltinFunction::new(0, move |_, _| {
+        let graphemes = string.graphemes(true)
+            .map(|grapheme| Literals::String(grapheme.into()))
             .collect();
 
-        Ok(Literals::Array(Rc::new(RefCell::new(char_literals))))
+        Ok(Literals::array(graphemes))
+    })
+}
+
+/// `slice(start, end)` takes the characters from `start` up to `end`, or the end of the string.
+fn string_slice(string: &str) -> impl DoveCallable {
+    let string = string.to_string();
+
+    BuiltinFunction::new(Arity::Range(1, 2), move |_, args| {
+        let char_len = string.chars().count();
+        let index = |value: &Literals| match value.clone().unwrap_usize() {
+            Ok(index) if index <= char_len => Ok(index),
+            Ok(index) => Err(RuntimeError::new(ErrorLocation::Unspecified, format!("Index '{}' out of range.", index))),
+            Err(_) => Err(RuntimeError::new(ErrorLocation::Unspecified, "Index must be an integer.".to_string())),
+        };
+
+        let start = index(&args[0])?;
+        let end = match args.get(1) {
+            Some(end) => index(end)?,
+            None => char_len,
+        };
+
+        Ok(Literals::String(string.chars().skip(start).take(end.saturating_sub(start)).collect::<String>().into()))
     })
 }
@@ -0,0 +1,62 @@
+use std::rc::Rc;
+
+use crate::data_types::*;
+use crate::dove_callable::{DoveCallable, BuiltinFunction};
+use crate::interpreter::is_equal;
+use crate::token::Literals;
+
+/// Tuples are immutable, so their methods only read the items.
+impl DoveObject for Box<Vec<Literals>> {
+    fn get_property(&mut self, name: &str) -> Result<Literals> {
+        let tuple = Rc::new(self.to_vec());
+
+        match name {
+            "len" => Ok(Literals::Function(Rc::new(tuple_len(tuple)))),
+            "is_empty" => Ok(Literals::Function(Rc::new(tuple_is_empty(tuple)))),
+            "to_array" => Ok(Literals::Function(Rc::new(tuple_to_array(tuple)))),
+            "count" => Ok(Literals::Function(Rc::new(tuple_count(tuple)))),
+            "index_of" => Ok(Literals::Function(Rc::new(tuple_index_of(tuple)))),
+            _ => Err(Error::CannotGetProperty),
+        }
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        names(&["len", "is_empty", "to_array", "count", "index_of"])
+    }
+}
+
+fn tuple_len(tuple: Rc<Vec<Literals>>) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Int(tuple.len() as i64))
+    })
+}
+
+fn tuple_is_empty(tuple: Rc<Vec<Literals>>) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::Boolean(tuple.is_empty()))
+    })
+}
+
+fn tuple_to_array(tuple: Rc<Vec<Literals>>) -> impl DoveCallable {
+    BuiltinFunction::new(0, move |_, _| {
+        Ok(Literals::array(tuple.to_vec()))
+    })
+}
+
+/// Number of items equal to the argument.
+fn tuple_count(tuple: Rc<Vec<Literals>>) -> impl DoveCallable {
+    BuiltinFunction::new(1, move |_, args| {
+        let count = tuple.iter().filter(|item| is_equal(item, &args[0])).count();
+        Ok(Literals::Int(count as i64))
+    })
+}
+
+/// Index of the first item equal to the argument, or nil if there is none.
+fn tuple_index_of(tuple: Rc<Vec<Literals>>) -> impl DoveCallable {
+    BuiltinFunction::new(1, move |_, args| {
+        match tuple.iter().position(|item| is_equal(item, &args[0])) {
+            Some(index) => Ok(Literals::Int(index as i64)),
+            None => Ok(Literals::Nil),
+        }
+    })
+}
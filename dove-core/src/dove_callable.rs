@@ -1,51 +1,145 @@
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::fmt;
 
 use crate::interpreter::{Interpreter, Interrupt};
 use crate::environment::Environment;
-use crate::token::{Token, Literals};
+use crate::token::{Literals, Token};
 use crate::ast::*;
 use crate::dove_class::DoveInstance;
 use crate::constants::keywords;
 use crate::error_handler::{RuntimeError, ErrorLocation};
 
+/// The number of arguments a callable accepts.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Arity {
+    /// Exactly the given number of arguments.
+    Fixed(usize),
+    /// The given number of arguments or more.
+    AtLeast(usize),
+    /// Between the given minimum and maximum number of arguments, inclusive.
+    Range(usize, usize),
+}
+
+impl Arity {
+    pub fn accepts(&self, count: usize) -> bool {
+        match self {
+            Arity::Fixed(n) => count == *n,
+            Arity::AtLeast(n) => count >= *n,
+            Arity::Range(min, max) => count >= *min && count <= *max,
+        }
+    }
+}
+
+impl From<usize> for Arity {
+    fn from(count: usize) -> Self {
+        Arity::Fixed(count)
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arity::Fixed(n) => write!(f, "{}", n),
+            Arity::AtLeast(n) => write!(f, "at least {}", n),
+            Arity::Range(min, max) => write!(f, "{} to {}", min, max),
+        }
+    }
+}
+
 pub trait DoveCallable {
-    fn arity(&self) -> usize;
+    fn arity(&self) -> Arity;
     fn call(&self, interpreter: &mut Interpreter, argument_vals: &Vec<Literals>) -> Result<Literals, RuntimeError>;
+
+    /// Name used when reporting calls, e.g. to `InterpreterHooks`.
+    fn name(&self) -> String {
+        "<builtin>".to_string()
+    }
+
+    /// Names of the parameters, if known.
+    fn params(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// The docstring, if the callable is a function that has one.
+    fn doc(&self) -> Option<String> {
+        None
+    }
+
+    /// What `==` compares, by default the callable itself. Accessing a method creates a new
+    /// bound method each time, which is equal to others binding the same method to the same instance.
+    fn identity(&self) -> (*const (), *const ()) {
+        (self as *const Self as *const (), std::ptr::null())
+    }
 }
 
 #[derive(Debug)]
 pub struct DoveFunction {
     // pub declaration: Stmt,
-    pub params: Vec<Token>,
+    pub name: String,
+    /// The file the function is declared in, if known, e.g. an imported module.
+    file: Option<Rc<str>>,
+    pub params: Vec<Param>,
     body: Stmt,
     // TODO: is Weak required here to prevent memory retain cycle?
     closure: Rc<RefCell<Environment>>,
+    /// Whether this is the `init` method of a class, which returns the instance when bound.
+    is_initializer: bool,
 }
 
 impl DoveFunction {
-    pub fn new(params: Vec<Token>, body: Stmt, closure: Rc<RefCell<Environment>>) -> DoveFunction {
+    /// A function named `name`, declared by the token `declaration`, e.g. its name or `lambda`.
+    pub fn new(name: String, declaration: &Token, params: Vec<Param>, body: Stmt, closure: Rc<RefCell<Environment>>) -> DoveFunction {
         DoveFunction {
+            name,
+            file: declaration.file.clone(),
             params,
             body,
             closure,
+            is_initializer: false,
         }
     }
 
-    /// Create a new function that is enclosed by a scope containing local `self` referencing `instance`.
-    pub fn bind(&self, instance: Rc<RefCell<DoveInstance>>) -> DoveFunction {
-        let mut environment = Environment::new(Some(Rc::clone(&self.closure)));
-        environment.define(keywords::SELF.to_string(), Literals::Instance(instance));
-        DoveFunction::new(self.params.clone(), self.body.clone(), Rc::new(RefCell::new(environment)))
+    /// Mark the method as the initializer of its class.
+    pub fn initializer(mut self) -> DoveFunction {
+        self.is_initializer = true;
+        self
     }
-}
 
-impl DoveCallable for DoveFunction {
-    fn call(&self, interpreter: &mut Interpreter, argument_vals: &Vec<Literals>) -> Result<Literals, RuntimeError> {
-        let mut environment = Environment::new(Some(self.closure.clone()));
+    /// Bind the method to `instance`, so `self` references it when the method is called.
+    pub fn bind(self: &Rc<Self>, instance: Rc<RefCell<DoveInstance>>) -> BoundMethod {
+        BoundMethod {
+            method: Rc::clone(self),
+            instance: Some(instance),
+        }
+    }
+
+    /// The method as called from its class, where `self` is not defined.
+    pub fn unbound(self: &Rc<Self>) -> BoundMethod {
+        BoundMethod {
+            method: Rc::clone(self),
+            instance: None,
+        }
+    }
+
+    /// Call the function in a new scope enclosed by `closure`.
+    fn call_in(&self, closure: Rc<RefCell<Environment>>, interpreter: &mut Interpreter, argument_vals: &[Literals]) -> Result<Literals, RuntimeError> {
+        let mut environment = Environment::new(Some(Rc::clone(&closure)));
 
-        for i in 0..self.params.len() {
-            environment.define(self.params[i].lexeme.clone(), argument_vals[i].clone());
+        for (i, param) in self.params.iter().enumerate() {
+            let value = match (argument_vals.get(i), &param.default) {
+                _ if param.rest => {
+                    let rest = argument_vals.get(i..).unwrap_or_default().to_vec();
+                    Literals::array(rest)
+                },
+                (Some(value), _) => value.clone(),
+                (None, Some(default)) => interpreter.evaluate_in(default, Rc::clone(&closure)).map_err(|interrupt| match interrupt {
+                    Interrupt::Error(err) => err.in_function(&self.name, self.file.as_ref()),
+                    _ => RuntimeError::new(ErrorLocation::Token(param.name.clone()), "Unexpected interrupt in a default value.".to_string()),
+                })?,
+                (None, None) => Literals::Nil,
+            };
+            environment.define(param.name.lexeme.clone(), value);
         }
 
         let statements = match &self.body {
@@ -56,31 +150,104 @@ impl DoveCallable for DoveFunction {
         match interpreter.execute_implicit_return(statements, environment) {
             Ok(implicit_return_val) => Ok(implicit_return_val),
             Err(Interrupt::Return(return_val)) => Ok(return_val),
-            Err(Interrupt::Error(err)) => Err(err),
+            Err(Interrupt::Error(err)) => Err(err.in_function(&self.name, self.file.as_ref())),
             Err(_) => Err(RuntimeError::new(ErrorLocation::Unspecified, "Unexpected break/continue statement.".to_string())),
         }
     }
+}
+
+impl DoveCallable for DoveFunction {
+    fn call(&self, interpreter: &mut Interpreter, argument_vals: &Vec<Literals>) -> Result<Literals, RuntimeError> {
+        self.call_in(Rc::clone(&self.closure), interpreter, argument_vals)
+    }
+
+    fn arity(&self) -> Arity {
+        let required = self.params.iter().filter(|param| param.default.is_none() && !param.rest).count();
+        if self.params.last().is_some_and(|param| param.rest) {
+            Arity::AtLeast(required)
+        } else if required == self.params.len() {
+            Arity::Fixed(required)
+        } else {
+            Arity::Range(required, self.params.len())
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn params(&self) -> Option<Vec<String>> {
+        Some(self.params.iter().map(|param| param.name.lexeme.clone()).collect())
+    }
+
+    fn doc(&self) -> Option<String> {
+        match &self.body {
+            Stmt::Block(statements) => docstring(statements).map(str::to_string),
+            _ => None,
+        }
+    }
+}
+
+/// A method bound to an instance, or called from its class if `instance` is `None`.
+/// The scope defining `self` is created when the method is called, so binding does not copy the method.
+#[derive(Debug)]
+pub struct BoundMethod {
+    method: Rc<DoveFunction>,
+    instance: Option<Rc<RefCell<DoveInstance>>>,
+}
+
+impl DoveCallable for BoundMethod {
+    fn call(&self, interpreter: &mut Interpreter, argument_vals: &Vec<Literals>) -> Result<Literals, RuntimeError> {
+        let mut environment = Environment::new(Some(Rc::clone(&self.method.closure)));
+        // The scope is created even without an instance, as the resolver counts it.
+        if let Some(instance) = &self.instance {
+            environment.define(keywords::SELF.to_string(), Literals::Instance(Rc::clone(instance)));
+        }
+        let value = self.method.call_in(Rc::new(RefCell::new(environment)), interpreter, argument_vals)?;
+
+        match &self.instance {
+            Some(instance) if self.method.is_initializer => Ok(Literals::Instance(Rc::clone(instance))),
+            _ => Ok(value),
+        }
+    }
+
+    fn arity(&self) -> Arity {
+        self.method.arity()
+    }
+
+    fn name(&self) -> String {
+        self.method.name()
+    }
+
+    fn params(&self) -> Option<Vec<String>> {
+        self.method.params()
+    }
+
+    fn doc(&self) -> Option<String> {
+        self.method.doc()
+    }
 
-    fn arity(&self) -> usize {
-        self.params.len()
+    fn identity(&self) -> (*const (), *const ()) {
+        let instance = self.instance.as_ref().map_or(std::ptr::null(), |instance| Rc::as_ptr(instance) as *const ());
+        (Rc::as_ptr(&self.method) as *const (), instance)
     }
 }
 
 pub struct BuiltinFunction<F>
 where
-    F: Fn(&Vec<Literals>) -> Result<Literals, RuntimeError>
+    F: Fn(&mut Interpreter, &Vec<Literals>) -> Result<Literals, RuntimeError>
 {
-    arity: usize,
+    arity: Arity,
     function: F,
 }
 
 impl<F> BuiltinFunction<F>
 where
-    F: Fn(&Vec<Literals>) -> Result<Literals, RuntimeError>
+    F: Fn(&mut Interpreter, &Vec<Literals>) -> Result<Literals, RuntimeError>
 {
-    pub fn new(arity: usize, function: F) -> BuiltinFunction<F> {
+    pub fn new<A: Into<Arity>>(arity: A, function: F) -> BuiltinFunction<F> {
         BuiltinFunction {
-            arity,
+            arity: arity.into(),
             function,
         }
     }
@@ -88,14 +255,14 @@ where
 
 impl<F> DoveCallable for BuiltinFunction<F>
 where
-    F: Fn(&Vec<Literals>) -> Result<Literals, RuntimeError>
+    F: Fn(&mut Interpreter, &Vec<Literals>) -> Result<Literals, RuntimeError>
 {
-    fn arity(&self) -> usize {
+    fn arity(&self) -> Arity {
         self.arity
     }
 
-    fn call(&self, _: &mut Interpreter, argument_vals: &Vec<Literals>) -> Result<Literals, RuntimeError> {
+    fn call(&self, interpreter: &mut Interpreter, argument_vals: &Vec<Literals>) -> Result<Literals, RuntimeError> {
         let f = &self.function;
-        f(argument_vals)
+        f(interpreter, argument_vals)
     }
 }
@@ -2,14 +2,23 @@ use std::rc::Rc;
 use std::cell::RefCell;
 use std::collections::HashMap;
 
-use crate::dove_callable::DoveFunction;
-use crate::token::Literals;
+use crate::ast::Expr;
+use crate::dove_callable::{BoundMethod, DoveCallable, DoveFunction};
+use crate::environment::Environment;
+use crate::token::{Literals, Token};
+
+/// A field declared in a class body, e.g. `let x = 0`, with its value if it has one.
+pub type FieldDeclaration = (Token, Option<Expr>);
 
 #[derive(Debug)]
 pub struct DoveClass {
     pub name: String,
     superclass: Option<Rc<DoveClass>>,
     methods: HashMap<String, Rc<DoveFunction>>,
+    /// Fields declared in the class body, e.g. `let x = 0`, with the environment their values are evaluated in.
+    fields: Vec<FieldDeclaration>,
+    closure: Option<Rc<RefCell<Environment>>>,
+    doc: Option<String>,
 }
 
 impl DoveClass {
@@ -18,7 +27,48 @@ impl DoveClass {
             name,
             superclass,
             methods,
+            fields: vec![],
+            closure: None,
+            doc: None,
+        }
+    }
+
+    /// Declare fields set on every new instance before `init` runs, with values evaluated in `closure`.
+    pub fn with_fields(mut self, fields: Vec<FieldDeclaration>, closure: Rc<RefCell<Environment>>) -> DoveClass {
+        self.fields = fields;
+        self.closure = Some(closure);
+        self
+    }
+
+    /// The fields declared by this class, without inherited ones, and the environment their values are evaluated in.
+    pub fn fields(&self) -> (&[FieldDeclaration], Option<&Rc<RefCell<Environment>>>) {
+        (&self.fields, self.closure.as_ref())
+    }
+
+    pub fn with_doc(mut self, doc: Option<String>) -> DoveClass {
+        self.doc = doc;
+        self
+    }
+
+    /// The docstring of the class, shown by `help`.
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    pub fn superclass(&self) -> Option<&Rc<DoveClass>> {
+        self.superclass.as_ref()
+    }
+
+    /// Names of all methods of this class, including inherited ones, sorted.
+    pub fn method_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.methods.keys().cloned().collect();
+        if let Some(superclass) = &self.superclass {
+            names.extend(superclass.method_names());
         }
+
+        names.sort();
+        names.dedup();
+        names
     }
 
     pub fn find_method(&self, name: &str) -> Option<Rc<DoveFunction>> {
@@ -34,8 +84,13 @@ impl DoveClass {
 
 #[derive(Debug)]
 pub struct DoveInstance {
-    class: Rc<DoveClass>,
+    pub class: Rc<DoveClass>,
     fields: HashMap<String, Literals>,
+    /// Methods bound to this instance, keyed by the method they bind, so accessing a method
+    /// again returns the same function without binding it again. A cached method references
+    /// the instance, like a field holding `self` would, so the entry is dropped when a field
+    /// of the same name is set and shadows the method.
+    bound_methods: HashMap<*const DoveFunction, Rc<BoundMethod>>,
 }
 
 impl DoveInstance {
@@ -43,29 +98,41 @@ impl DoveInstance {
         DoveInstance {
             class,
             fields: HashMap::new(),
+            bound_methods: HashMap::new(),
         }
     }
 
+    /// The field `field`, or the method with that name bound to the instance.
+    /// Fields shadow methods.
     pub fn get(instance: Rc<RefCell<DoveInstance>>, field: &str) -> Option<Literals> {
         let mut instance_ref = instance.borrow_mut();
 
-        match instance_ref.fields.get(field) {
-            Some(value) => Some(value.clone()),
-            None => {
-                instance_ref.class.find_method(field).map(|method| {
-                    let bound_method = method.bind(Rc::clone(&instance));
-                    let literal = Literals::Function(Rc::new(bound_method));
+        if let Some(value) = instance_ref.fields.get(field) {
+            return Some(value.clone());
+        }
 
-                    // Lazily bind method and save to fields
-                    instance_ref.set(field.to_string(), literal.clone());
+        let method = instance_ref.class.find_method(field)?;
+        let bound_method = instance_ref.bound_methods.entry(Rc::as_ptr(&method))
+            .or_insert_with(|| Rc::new(method.bind(Rc::clone(&instance))));
 
-                    literal
-                })
-            }
-        }
+        Some(Literals::Function(Rc::clone(bound_method) as Rc<dyn DoveCallable>))
+    }
+
+    /// Names of the fields set on this instance, sorted.
+    pub fn field_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.fields.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub(crate) fn get_field(&self, field: &str) -> Option<Literals> {
+        self.fields.get(field).cloned()
     }
 
     pub fn set(&mut self, field: String, value: Literals) {
+        if let Some(method) = self.class.find_method(&field) {
+            self.bound_methods.remove(&Rc::as_ptr(&method));
+        }
         self.fields.insert(field, value);
     }
 }
@@ -0,0 +1,35 @@
+use std::io::{self, BufRead};
+
+/// Source of the lines read by the `input` builtin. Its prompt is shown by the output of the interpreter.
+pub trait DoveInput {
+    /// Read a line, without its line ending. `None` at the end of the input,
+    /// or describe why it cannot be read.
+    fn read_line(&self) -> Result<Option<String>, String>;
+}
+
+/// Reads from stdin, for hosts with a terminal, e.g. the command line.
+pub struct StdinInput;
+
+impl DoveInput for StdinInput {
+    fn read_line(&self) -> Result<Option<String>, String> {
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line) {
+            Ok(0) => Ok(None),
+            Ok(_) => {
+                let len = line.trim_end_matches(['\n', '\r']).len();
+                line.truncate(len);
+                Ok(Some(line))
+            },
+            Err(error) => Err(error.to_string()),
+        }
+    }
+}
+
+/// Refuses to read input. The default, so an embedded interpreter does not read the stdin of its host.
+pub struct NoInput;
+
+impl DoveInput for NoInput {
+    fn read_line(&self) -> Result<Option<String>, String> {
+        Err("reading input is not supported".to_string())
+    }
+}
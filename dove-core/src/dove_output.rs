@@ -1,5 +1,31 @@
+use crate::error_handler::{Diagnostic, Severity};
+
 pub trait DoveOutput {
     fn print(&self, message: String);
     fn warning(&self, message: String);
     fn error(&self, message: String);
+
+    /// Report an error or warning diagnostic. By default it is printed as a plain message,
+    /// outputs that can show source excerpts may override this.
+    fn diagnostic(&self, diagnostic: Diagnostic) {
+        match diagnostic.severity {
+            Severity::Error => self.error(diagnostic.to_string()),
+            Severity::Warning => self.warning(diagnostic.to_string()),
+        }
+    }
+
+    /// Show the prompt of the `input` builtin before it reads a line. Printed as a line by default,
+    /// outputs writing to a terminal may leave the cursor after it instead.
+    fn prompt(&self, prompt: &str) {
+        self.print(prompt.to_string());
+    }
+
+    /// Called with each input of a REPL before it runs. Inputs have no file,
+    /// so outputs showing source excerpts may keep it to read them from.
+    fn repl_source(&self, _source: &str) {}
+
+    /// Whether the output has reached its limit, after which the running script stops with an error.
+    fn is_full(&self) -> bool {
+        false
+    }
 }
@@ -29,6 +29,11 @@ impl Environment {
         }
     }
 
+    /// The environment this one is nested in, `None` for a global scope.
+    pub fn enclosing(&self) -> Option<Rc<RefCell<Environment>>> {
+        self.enclosing.clone()
+    }
+
     pub fn get(&self, name: &str) -> Option<Literals> {
         self.values.get(name).map(Literals::clone)
     }
@@ -44,16 +49,17 @@ impl Environment {
         }
     }
 
-    pub fn assign(&mut self, name: String, value: Literals) -> bool {
-        if self.values.contains_key(&name) {
-            self.values.insert(name, value);
-            true
-        } else {
-            false
+    pub fn assign(&mut self, name: &str, value: Literals) -> bool {
+        match self.values.get_mut(name) {
+            Some(slot) => {
+                *slot = value;
+                true
+            },
+            None => false,
         }
     }
 
-    pub fn assign_at(&mut self, distance: usize, name: String, value: Literals) -> bool {
+    pub fn assign_at(&mut self, distance: usize, name: &str, value: Literals) -> bool {
         if distance <= 0 {
             self.assign(name, value)
         } else {
@@ -67,6 +73,36 @@ impl Environment {
     pub fn define(&mut self, name: String, value: Literals) {
         self.values.insert(name, value);
     }
+
+    /// Names of the variables defined in this scope, without the enclosing ones.
+    pub fn names(&self) -> Vec<String> {
+        self.values.keys().cloned().collect()
+    }
+
+    /// Names of the variables visible from this scope, including the enclosing ones.
+    pub fn visible_names(&self) -> Vec<String> {
+        let mut names = self.names();
+        if let Some(enclosing) = &self.enclosing {
+            names.extend(enclosing.borrow().visible_names());
+        }
+        names
+    }
+
+    /// Remove the variable, returning whether it was defined.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.values.remove(name).is_some()
+    }
+
+    pub fn remove_at(&mut self, distance: usize, name: &str) -> bool {
+        if distance == 0 {
+            self.remove(name)
+        } else {
+            match &self.enclosing {
+                Some(enclosing) => enclosing.borrow_mut().remove_at(distance - 1, name),
+                None => false,
+            }
+        }
+    }
 }
 
 // Scope debugging functions
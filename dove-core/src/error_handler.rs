@@ -1,3 +1,4 @@
+use std::fmt;
 use std::rc::Rc;
 
 use crate::token::*;
@@ -6,14 +7,65 @@ use crate::dove_output::DoveOutput;
 /// All ErrorHandlers should implement this trait
 /// and use its `report` method to display error messages.
 pub trait ErrorHandler {
-    fn report(&mut self, line: Option<usize>, where_: String, message: String, output: Rc<dyn DoveOutput>) {
-        let msg = if let Some(line) = line {
-            format!("[line {}] Error{}: {}", line, where_, message)
-        } else {
-            format!("Error: {}",message)
-        };
+    fn report(&mut self, diagnostic: Diagnostic, output: Rc<dyn DoveOutput>) {
+        output.diagnostic(diagnostic);
+    }
+}
+
+/// Whether a diagnostic stops the program from running.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A reported error or warning: the message, where it happened and an optional hint.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub location: Option<SourceLocation>,
+    /// Describes the offending token, e.g. ` at 'x'`.
+    pub where_: String,
+    pub message: String,
+    /// How the error might be fixed, shown as `help: ...`.
+    pub help: Option<String>,
+    /// The innermost function a runtime error happened in, shown as ` in function 'name'`.
+    pub function: Option<String>,
+}
 
-        output.error(msg);
+impl Diagnostic {
+    pub fn new(location: Option<SourceLocation>, where_: String, message: String) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            location,
+            where_,
+            message,
+            help: None,
+            function: None,
+        }
+    }
+}
+
+/// The plain, single line format, e.g. `[main.dove:3:5] Error at 'x': Undefined variable 'x'.`
+/// or `[utils.dove:42:9] Error at '[' in function 'parse_row': Index '3' out of range.`
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.severity {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+        };
+        let function = match &self.function {
+            Some(name) => format!(" in function '{}'", name),
+            None => "".to_string(),
+        };
+        match &self.location {
+            Some(location) => write!(f, "[{}] {}{}{}: {}", location, kind, self.where_, function, self.message)?,
+            None => write!(f, "{}{}: {}", kind, function, self.message)?,
+        }
+        if let Some(help) = &self.help {
+            write!(f, "\n    help: {}", help)?;
+        }
+        Ok(())
     }
 }
 
@@ -30,17 +82,32 @@ impl RuntimeErrorHandler {
         }
     }
 
+    /// Forget earlier errors, e.g. before running the next input of the REPL.
+    pub fn reset(&mut self) {
+        self.had_runtime_error = false;
+    }
+
     pub fn runtime_error(&mut self, error: RuntimeError) {
         self.had_runtime_error = true;
-        self.report(
-            error.location.line(),
+        let file = error.file().cloned();
+        let location = error.location.source_location().map(|location| SourceLocation { file, ..location });
+        let mut diagnostic = Diagnostic::new(
+            location,
             match error.location {
                 ErrorLocation::Token(token) => format!(" at '{}'", token.lexeme),
                 _ => "".to_string(),
             },
             error.message,
-            Rc::clone(&self.output),
         );
+        diagnostic.function = error.frame.map(|frame| frame.function);
+        self.report(diagnostic, Rc::clone(&self.output));
+    }
+
+    /// Report something a running program should change, which does not stop it, e.g. a deprecated name.
+    pub fn runtime_warning(&mut self, token: Token, message: String, help: String) {
+        let mut diagnostic = token_diagnostic(token, message, Some(help));
+        diagnostic.severity = Severity::Warning;
+        self.report(diagnostic, Rc::clone(&self.output));
     }
 }
 
@@ -61,25 +128,100 @@ impl CompiletimeErrorHandler {
 
     pub fn line_error(&mut self, line: usize, message: String) {
         self.had_error = true;
-        self.report(Some(line), "".to_string(), message, Rc::clone(&self.output));
+        self.report(Diagnostic::new(Some(SourceLocation::of_line(line)), "".to_string(), message), Rc::clone(&self.output));
+    }
+
+    pub fn location_error(&mut self, location: SourceLocation, message: String) {
+        self.had_error = true;
+        self.report(Diagnostic::new(Some(location), "".to_string(), message), Rc::clone(&self.output));
     }
 
     pub fn token_error(&mut self, token: Token, message: String) {
+        self.report_token(token, message, None);
+    }
+
+    /// Like `token_error`, with a hint on how to fix the error.
+    pub fn token_error_with_help(&mut self, token: Token, message: String, help: String) {
+        self.report_token(token, message, Some(help));
+    }
+
+    /// Report a likely mistake at the token, which does not stop the program from running.
+    pub fn token_warning(&mut self, token: Token, message: String, help: String) {
+        let mut diagnostic = token_diagnostic(token, message, Some(help));
+        diagnostic.severity = Severity::Warning;
+        self.report(diagnostic, Rc::clone(&self.output));
+    }
+
+    fn report_token(&mut self, token: Token, message: String, help: Option<String>) {
         self.had_error = true;
-        match token.token_type {
-            TokenType::EOF => self.report(Some(token.line), " at end".to_string(), message, Rc::clone(&self.output)),
-            _ => self.report(Some(token.line), format!(" at '{}'", token.lexeme), message, Rc::clone(&self.output)),
-        }
+        self.report(token_diagnostic(token, message, help), Rc::clone(&self.output));
     }
 }
 
 impl ErrorHandler for CompiletimeErrorHandler {}
 
+fn token_diagnostic(token: Token, message: String, help: Option<String>) -> Diagnostic {
+    let location = SourceLocation::of_token(&token);
+    let where_ = match token.token_type {
+        TokenType::EOF => " at end".to_string(),
+        _ => format!(" at '{}'", token.lexeme),
+    };
+
+    let mut diagnostic = Diagnostic::new(Some(location), where_, message);
+    diagnostic.help = help;
+    diagnostic
+}
+
+/// Where in the source a diagnostic points to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLocation {
+    pub file: Option<Rc<str>>,
+    pub line: usize,
+    pub column: Option<usize>,
+    /// Number of characters from `column` the location covers, 0 if only the line is known.
+    pub length: usize,
+}
+
+impl SourceLocation {
+    pub fn of_token(token: &Token) -> SourceLocation {
+        SourceLocation {
+            file: token.file.clone(),
+            line: token.line,
+            column: Some(token.column),
+            length: token.lexeme.chars().count().max(1),
+        }
+    }
+
+    pub fn of_line(line: usize) -> SourceLocation {
+        SourceLocation {
+            file: None,
+            line,
+            column: None,
+            length: 0,
+        }
+    }
+}
+
+/// Displays as `path:line:col` when the file is known, and `line L:col` otherwise.
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}", file, self.line)?,
+            None => write!(f, "line {}", self.line)?,
+        }
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ErrorLocation {
     Token(Token),
     Line(usize),
-    // TODO: maybe remove this after adding token to all ASTs
+    /// Errors of builtins, which get the location of the call when returned from it,
+    /// and errors raised outside a script, e.g. in a function called by the host.
     Unspecified,
 }
 
@@ -91,6 +233,14 @@ impl ErrorLocation {
             _ => None,
         }
     }
+
+    pub fn source_location(&self) -> Option<SourceLocation> {
+        match self {
+            ErrorLocation::Token(token) => Some(SourceLocation::of_token(token)),
+            ErrorLocation::Line(line) => Some(SourceLocation::of_line(*line)),
+            _ => None,
+        }
+    }
 }
 
 /// RuntimeError struct used to structure information of
@@ -99,6 +249,16 @@ impl ErrorLocation {
 pub struct RuntimeError {
     pub location: ErrorLocation,
     pub message: String,
+    /// The innermost function the error happened in, if it happened in one.
+    pub frame: Option<Box<CallFrame>>,
+}
+
+/// A function a runtime error happened in.
+#[derive(Debug, Clone)]
+pub struct CallFrame {
+    pub function: String,
+    /// The file of the function, if known, e.g. an imported module.
+    pub file: Option<Rc<str>>,
 }
 
 impl RuntimeError {
@@ -106,6 +266,33 @@ impl RuntimeError {
         RuntimeError {
             location,
             message,
+            frame: None,
         }
     }
+
+    /// The error located at `location` if it has no location yet.
+    pub fn or_at(self, location: ErrorLocation) -> Self {
+        match self.location {
+            ErrorLocation::Unspecified => RuntimeError { location, ..self },
+            _ => self,
+        }
+    }
+
+    /// The file the error happened in, if known.
+    pub fn file(&self) -> Option<&Rc<str>> {
+        match &self.location {
+            ErrorLocation::Token(token) if token.file.is_some() => token.file.as_ref(),
+            _ => self.frame.as_ref().and_then(|frame| frame.file.as_ref()),
+        }
+    }
+
+    /// The error raised in the function `name` declared in `file`, unless it was raised in a function called by it.
+    pub fn in_function(self, name: &str, file: Option<&Rc<str>>) -> Self {
+        if self.frame.is_some() {
+            return self;
+        }
+
+        let frame = CallFrame { function: name.to_string(), file: file.cloned() };
+        RuntimeError { frame: Some(Box::new(frame)), ..self }
+    }
 }
@@ -0,0 +1,37 @@
+use std::rc::Rc;
+
+/// Source of the files read by `import` statements.
+pub trait FileProvider {
+    /// Read the file at `path`, or describe why it cannot be read.
+    fn read_file(&self, path: &str) -> Result<String, String>;
+}
+
+/// Reads files from the file system, paths are relative to the working directory.
+pub struct FsFileProvider;
+
+impl FileProvider for FsFileProvider {
+    fn read_file(&self, path: &str) -> Result<String, String> {
+        std::fs::read_to_string(path).map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => "file not found".to_string(),
+            _ => error.to_string(),
+        })
+    }
+}
+
+/// Refuses to read any file, for hosts without a file system.
+pub struct NoFileProvider;
+
+impl FileProvider for NoFileProvider {
+    fn read_file(&self, _path: &str) -> Result<String, String> {
+        Err("importing files is not supported".to_string())
+    }
+}
+
+/// The provider of new interpreters: the file system, except on wasm where there is none.
+pub fn default_provider() -> Rc<dyn FileProvider> {
+    if cfg!(target_arch = "wasm32") {
+        Rc::new(NoFileProvider)
+    } else {
+        Rc::new(FsFileProvider)
+    }
+}
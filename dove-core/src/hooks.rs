@@ -0,0 +1,29 @@
+use crate::ast::Stmt;
+use crate::error_handler::RuntimeError;
+use crate::token::Literals;
+
+/// Callbacks invoked by the `Interpreter` while running, so hosts can build
+/// tracers, profilers or metering without changing the interpreter.
+///
+/// All methods do nothing by default.
+pub trait InterpreterHooks {
+    /// Called before each statement is executed. Returning an error aborts execution with that error.
+    fn on_statement(&self, _stmt: &Stmt) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+
+    /// Called before a function or class is called.
+    fn on_call(&self, _name: &str, _arguments: &[Literals]) {}
+
+    /// Called after a function or class call returns successfully.
+    fn on_return(&self, _name: &str, _value: &Literals) {}
+
+    /// Called when an error unwinds out of a function or class call instead of it returning.
+    fn on_unwind(&self, _name: &str, _error: &RuntimeError) {}
+
+    /// Called when an array, tuple or dictionary expression creates a collection.
+    fn on_collection(&self, _value: &Literals) {}
+
+    /// Called when a runtime error is reported.
+    fn on_error(&self, _error: &RuntimeError) {}
+}
@@ -1,21 +1,36 @@
 use std::rc::Rc;
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{RefCell, RefMut};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use indexmap::IndexMap;
 
 use crate::ast::*;
 use crate::token::*;
 use crate::error_handler::*;
 use crate::dove_callable::*;
 use crate::dove_class::{DoveClass, DoveInstance};
+use crate::data_types::{borrow_to_change, canonical_name};
+use crate::data_types::module::Module;
 use crate::environment::Environment;
 use crate::constants::keywords;
 use crate::dove_output::DoveOutput;
+use crate::builtins;
+use crate::iteration::iterator;
+use crate::marshal::NativeResult;
+use crate::stats::{self, RuntimeStats, ValueTracker};
+use crate::suggestions::did_you_mean;
+use crate::options::InterpreterOptions;
+use crate::scanner::Scanner;
+use crate::parser::Parser;
+use crate::resolver::Resolver;
 
 /// An enum indicating that execution was interrupted, for some reason.
 #[derive(Debug, Clone)]
 pub enum Interrupt {
-    Break,
-    Continue,
+    /// `break` of the innermost loop, or of the loop with the label.
+    Break(Option<String>),
+    Continue(Option<String>),
     Return(Literals),
     Error(RuntimeError),
 }
@@ -37,37 +52,333 @@ pub struct Interpreter {
     locals: HashMap<usize, usize>,
 
     output: Rc<dyn DoveOutput>,
+    options: InterpreterOptions,
+    /// Number of function calls currently running.
+    call_depth: usize,
+    /// When the current evaluation times out, if there is an evaluation timeout.
+    deadline: Option<Instant>,
+
+    /// Files that finished running through `import`.
+    imported: HashSet<String>,
+    /// Files imported as modules, including the ones still running.
+    modules: HashMap<String, Rc<Module>>,
+    /// Files whose imports are currently running, to detect circular imports.
+    importing: Vec<String>,
+    /// Globals deleted with `del`, so builtins of the same name are not defined again on use.
+    deleted_globals: HashSet<String>,
+    /// Arrays, dictionaries, instances and functions created by scripts, for `runtime_stats`.
+    /// Empty until it is first called, and the tracker of the thread while evaluating.
+    values: Rc<RefCell<Option<ValueTracker>>>,
+    /// Deprecated property names used so far, which are only warned about once.
+    deprecated_uses: HashSet<String>,
+    /// The status passed to `exit`, whose error stops the script and is not caught by `try`.
+    exit_status: Option<i32>,
+    /// State of the generator of `math.random`.
+    pub(crate) random_state: u64,
 }
 
 impl Interpreter {
-    pub fn new(output: Rc<dyn DoveOutput>) -> Interpreter {
+    pub fn new(output: Rc<dyn DoveOutput>, options: InterpreterOptions) -> Interpreter {
+        // Builtins are defined on first use, see `global_variable`.
         let env = Rc::new(RefCell::new(Environment::new(Option::None)));
+        let random_state = builtins::math::random_seed(options.deterministic);
+
         Interpreter{
             globals: env.clone(),
             environment: env.clone(),
             error_handler: RuntimeErrorHandler::new(Rc::clone(&output)),
             locals: HashMap::new(),
             output,
+            options,
+            call_depth: 0,
+            deadline: None,
+            imported: HashSet::new(),
+            modules: HashMap::new(),
+            importing: Vec::new(),
+            deleted_globals: HashSet::new(),
+            values: Rc::new(RefCell::new(None)),
+            deprecated_uses: HashSet::new(),
+            exit_status: None,
+            random_state,
         }
     }
 
+    pub fn options(&self) -> &InterpreterOptions {
+        &self.options
+    }
+
+    /// How `print` and the REPL show a value, see `InterpreterOptions::float_precision`.
+    pub fn display(&self, value: Literals) -> String {
+        stringify_with(value, self.options.float_precision)
+    }
+
     pub fn interpret(&mut self, stmts: Vec<Stmt>) {
-        for stmt in stmts.iter() {
-            // As this function should only be used by Dove struct,
-            // no return value should be expected.
-            self.execute(stmt).unwrap_or_else(|interrupt| match interrupt {
-                Interrupt::Error(error) => self.error_handler.runtime_error(error),
-                _ => self.output.error(format!("Unexpected interrupt: {:?}", interrupt)),
-            });
+        self.evaluation(|interpreter| {
+            for stmt in stmts.iter() {
+                // As this function should only be used by Dove struct,
+                // no return value should be expected.
+                match interpreter.execute(stmt) {
+                    Ok(()) => {},
+                    // `exit` stops the script, it is not reported as an error.
+                    Err(Interrupt::Error(_)) if interpreter.exit_status.is_some() => break,
+                    Err(Interrupt::Error(error)) => {
+                        if let Some(hooks) = &interpreter.options.hooks {
+                            hooks.on_error(&error);
+                        }
+                        interpreter.error_handler.runtime_error(error);
+
+                        if interpreter.output.is_full() {
+                            break;
+                        }
+                    },
+                    Err(interrupt) => interpreter.output.error(format!("Unexpected interrupt: {:?}", interrupt)),
+                }
+            }
+        })
+    }
+
+    /// The status passed to `exit`, if a script called it.
+    pub fn exit_status(&self) -> Option<i32> {
+        self.exit_status
+    }
+
+    /// Stop the running script with `status`. The returned error must be passed on by the caller.
+    pub(crate) fn exit(&mut self, status: i32) -> RuntimeError {
+        self.exit_status = Some(status);
+        RuntimeError::new(ErrorLocation::Unspecified, format!("Exited with status {}.", status))
+    }
+
+    /// Whether the script is stopping, because it called `exit` or filled the output.
+    fn is_stopping(&self) -> bool {
+        self.exit_status.is_some() || self.output.is_full()
+    }
+
+    /// Run one evaluation within the evaluation timeout. Nested evaluations,
+    /// such as imports and `eval`, count towards the time of the outer one.
+    fn evaluation<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let is_outermost = self.deadline.is_none();
+        if is_outermost {
+            self.deadline = self.options.evaluation_timeout.map(|timeout| Instant::now() + timeout);
+        }
+
+        let tracker = stats::replace_tracker(Some(Rc::clone(&self.values)));
+        let result = f(self);
+        stats::replace_tracker(tracker);
+
+        if is_outermost {
+            self.deadline = None;
         }
+        result
     }
 
     fn evaluate(&mut self, expr: &Expr) -> Result<Literals> {
         self.visit_expr(expr)
     }
 
+    pub fn output(&self) -> Rc<dyn DoveOutput> {
+        Rc::clone(&self.output)
+    }
+
+    /// Execute (resolved) statements in the global environment.
+    /// Returns the value of the last statement if it is an expression, otherwise nil.
+    pub fn execute_global(&mut self, statements: &[Stmt]) -> std::result::Result<Literals, RuntimeError> {
+        self.evaluation(|interpreter| interpreter.execute_global_untimed(statements))
+    }
+
+    fn execute_global_untimed(&mut self, statements: &[Stmt]) -> std::result::Result<Literals, RuntimeError> {
+        self.execute_in(Rc::clone(&self.globals), statements)
+    }
+
+    /// Execute statements with `scope` as their global scope, see `execute_global`.
+    fn execute_in(&mut self, scope: Rc<RefCell<Environment>>, statements: &[Stmt]) -> std::result::Result<Literals, RuntimeError> {
+        let previous = std::mem::replace(&mut self.environment, scope);
+
+        let mut result = Ok(Literals::Nil);
+        for (i, stmt) in statements.iter().enumerate() {
+            result = match stmt {
+                Stmt::Expression(expr) if i == statements.len() - 1 => self.evaluate(expr),
+                _ => self.execute(stmt).map(|_| Literals::Nil),
+            };
+
+            if result.is_err() {
+                break;
+            }
+        }
+
+        self.environment = previous;
+
+        match result {
+            Ok(value) => Ok(value),
+            Err(Interrupt::Error(error)) => Err(error),
+            Err(interrupt) => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Unexpected interrupt: {:?}", interrupt),
+            )),
+        }
+    }
+
+    fn import(&mut self, path: &Token, target: &ImportTarget) -> Result<()> {
+        match target {
+            ImportTarget::Globals => self.import_globals(path),
+            ImportTarget::Module(name) => {
+                let module = self.import_module(path)?;
+                self.environment.borrow_mut().define(name.lexeme.clone(), Literals::Module(module));
+                Ok(())
+            },
+            ImportTarget::Names(names) => {
+                let module = self.import_module(path)?;
+                for name in names {
+                    let value = match module.get(&name.lexeme) {
+                        Some(value) => value,
+                        None => return Err(Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(name.clone()),
+                            format!("Module '{}' has no '{}'.", module.path, name.lexeme),
+                        ))),
+                    };
+                    self.environment.borrow_mut().define(name.lexeme.clone(), value);
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Run the file named by the `path` string token in the global environment.
+    /// A file only runs the first time it is imported.
+    fn import_globals(&mut self, path: &Token) -> Result<()> {
+        let file = import_path(path);
+        if self.imported.contains(&file) && !self.importing.contains(&file) {
+            return Ok(());
+        }
+
+        let statements = self.load_import(&file, path)?;
+
+        self.importing.push(file.clone());
+        let result = self.execute_global(&statements);
+        self.importing.pop();
+
+        result?;
+        self.imported.insert(file);
+        Ok(())
+    }
+
+    /// Run the file named by the `path` string token in its own global scope.
+    /// A file only runs the first time it is imported as a module, later imports share it.
+    fn import_module(&mut self, path: &Token) -> Result<Rc<Module>> {
+        let file = import_path(path);
+        if let Some(module) = self.modules.get(&file) {
+            if !self.importing.contains(&file) {
+                return Ok(Rc::clone(module));
+            }
+        }
+
+        let statements = self.load_import(&file, path)?;
+
+        // Registered before running, so code in the module looks up its globals in the module.
+        let module = Rc::new(Module::new(file.clone()));
+        self.modules.insert(file.clone(), Rc::clone(&module));
+
+        self.importing.push(file.clone());
+        let scope = Rc::clone(&module.environment);
+        let result = self.evaluation(|interpreter| interpreter.execute_in(scope, &statements));
+        self.importing.pop();
+
+        if result.is_err() {
+            self.modules.remove(&file);
+        }
+        result?;
+        Ok(module)
+    }
+
+    /// Read, parse and resolve an imported file, failing if it is already being imported.
+    fn load_import(&mut self, file: &str, path: &Token) -> Result<Vec<Stmt>> {
+        let error = |message: String| Interrupt::Error(RuntimeError::new(ErrorLocation::Token(path.clone()), message));
+
+        if self.importing.iter().any(|importing| importing == file) {
+            return Err(error(format!("Circular import of '{}'.", file)));
+        }
+
+        let source = self.options.file_provider.read_file(file)
+            .map_err(|reason| error(format!("Cannot import '{}': {}.", file, reason)))?;
+
+        let has_errors = || error(format!("Cannot import '{}' because it has errors.", file));
+
+        let tokens = match Scanner::with_file(source.chars().collect(), file, self.output()).try_scan_tokens() {
+            Some(tokens) => tokens,
+            None => return Err(has_errors()),
+        };
+
+        let mut parser = Parser::new(tokens, self.output());
+        let statements = parser.program();
+        if parser.had_error() {
+            return Err(has_errors());
+        }
+
+        let output = self.output();
+        let mut resolver = Resolver::new(self, output);
+        resolver.resolve(&statements);
+        if resolver.had_error() {
+            return Err(has_errors());
+        }
+
+        Ok(statements)
+    }
+
+    /// Evaluate a single (resolved) expression, e.g. for tools such as the REPL.
+    pub fn evaluate_expression(&mut self, expr: &Expr) -> std::result::Result<Literals, RuntimeError> {
+        match self.evaluate(expr) {
+            Ok(value) => Ok(value),
+            Err(Interrupt::Error(error)) => Err(error),
+            Err(interrupt) => Err(RuntimeError::new(
+                ErrorLocation::Unspecified,
+                format!("Unexpected interrupt: {:?}", interrupt),
+            )),
+        }
+    }
+
+    /// The value of a global variable, e.g. a handler defined by a script.
+    pub fn global(&self, name: &str) -> Option<Literals> {
+        self.global_variable(name)
+    }
+
+    /// Define a global function implemented in Rust, e.g. to give scripts access to the host application.
+    /// It gets the arguments as Dove values and may return any `ToDove` value, or a `Result<_, String>`
+    /// whose error becomes a runtime error. Use `marshal::native` instead to convert the arguments too.
+    pub fn register_native<R: NativeResult>(&mut self, name: &str, arity: impl Into<Arity>, function: impl Fn(&[Literals]) -> R + 'static) {
+        let function = BuiltinFunction::new(arity, move |_, args| function(args).into_result());
+        self.globals.borrow_mut().define(name.to_string(), Literals::Function(Rc::new(function)));
+    }
+
+    /// Call a function or class from the host, e.g. a handler registered by a script for an event.
+    /// It may be called between evaluations or by native functions during one, in which case it
+    /// counts towards the call depth and evaluation timeout of the running script.
+    pub fn call_function(&mut self, callee: Literals, args: &[Literals]) -> std::result::Result<Literals, RuntimeError> {
+        self.evaluation(|interpreter| interpreter.call_value(callee, &args.to_vec(), ErrorLocation::Unspecified))
+    }
+
     pub fn execute(&mut self, stmt: &Stmt) -> Result<()> {
-        self.visit_stmt(stmt)
+        if let Some(hooks) = &self.options.hooks {
+            hooks.on_statement(stmt)?;
+        }
+
+        if let (Some(deadline), Some(timeout)) = (self.deadline, self.options.evaluation_timeout) {
+            if Instant::now() >= deadline {
+                return Err(Interrupt::Error(RuntimeError::new(
+                    stmt.line().map_or(ErrorLocation::Unspecified, ErrorLocation::Line),
+                    format!("Evaluation timed out after {} ms.", timeout.as_millis()),
+                )));
+            }
+        }
+
+        self.visit_stmt(stmt)?;
+
+        // Whatever wrote it, text past the limit of the output is dropped, so the script stops.
+        if self.output.is_full() {
+            return Err(Interrupt::Error(RuntimeError::new(
+                stmt.line().map_or(ErrorLocation::Unspecified, ErrorLocation::Line),
+                "Output limit reached.".to_string(),
+            )));
+        }
+        Ok(())
     }
 
     pub fn execute_block(&mut self, statements: &Vec<Stmt>, environment: Environment) -> Result<()> {
@@ -87,6 +398,23 @@ impl Interpreter {
         Ok(())
     }
 
+    /// Evaluate an expression with `environment` as the current scope, e.g. the default value of
+    /// a parameter in the closure of its function.
+    pub fn evaluate_in(&mut self, expr: &Expr, environment: Rc<RefCell<Environment>>) -> Result<Literals> {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = self.evaluate(expr);
+        self.environment = previous;
+        result
+    }
+
+    /// Run `f` with `environment` as the current scope, restoring the previous one afterwards.
+    fn in_environment<T>(&mut self, environment: Environment, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let previous = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(environment)));
+        let result = f(self);
+        self.environment = previous;
+        result
+    }
+
     /// Execute a block and return its implicit return value.
     pub fn execute_implicit_return(&mut self, statements: &Vec<Stmt>, environment: Environment) -> Result<Literals> {
         // Check if last statement is an expression
@@ -138,13 +466,561 @@ impl Interpreter {
     fn lookup_variable(&self, variable: &Token) -> Option<Literals> {
         match self.get_local(variable) {
             Some(distance) => self.environment.borrow().get_at(*distance, &variable.lexeme),
-            None => self.globals.borrow().get(&variable.lexeme),
+            // Modules can also use the builtins and globals of the interpreter.
+            None => {
+                let value = self.global_scope().borrow().get(&variable.lexeme);
+                value.or_else(|| self.global_variable(&variable.lexeme))
+            },
         }
     }
 
-    fn check_number_operand(&mut self, operator: &Token, left: &Literals, right: &Literals) -> Result<(f64, f64)> {
+    /// The interpreter global called `name`. Builtins are defined in the globals
+    /// when first looked up, unless a global of that name was deleted.
+    fn global_variable(&self, name: &str) -> Option<Literals> {
+        if let Some(value) = self.globals.borrow().get(name) {
+            return Some(value);
+        }
+        if self.deleted_globals.contains(name) {
+            return None;
+        }
+
+        let value = builtins::builtin(name, &self.options.builtins)?;
+        self.globals.borrow_mut().define(name.to_string(), value.clone());
+        Some(value)
+    }
+
+    /// Names of the variables visible from the running code: those in its scopes, the interpreter
+    /// globals and the builtins, sorted.
+    pub fn visible_names(&self) -> Vec<String> {
+        let mut names = self.environment.borrow().visible_names();
+        names.extend(self.global_scope().borrow().names());
+        names.extend(self.globals.borrow().names());
+        names.extend(builtins::builtin_names(&self.options.builtins).into_iter()
+            .filter(|name| !self.deleted_globals.contains(name)));
+
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Warn that the property `name` was renamed to `canonical`, the first time it is used.
+    fn deprecated_property(&mut self, name: &Token, canonical: &str) {
+        if self.deprecated_uses.insert(name.lexeme.clone()) {
+            self.error_handler.runtime_warning(
+                name.clone(),
+                format!("The property '{}' is deprecated, it was renamed to '{}'.", name.lexeme, canonical),
+                format!("use '{}' instead", canonical),
+            );
+        }
+    }
+
+    /// The error for a variable that is not defined, suggesting a visible one with a similar name.
+    fn variable_not_found(&self, name: &Token) -> Interrupt {
+        self.not_in_scope(name, format!("Variable '{}' not found in scope.", name.lexeme))
+    }
+
+    /// An error at `name`, which is not in scope, with the message followed by a suggestion like `variable_not_found`.
+    fn not_in_scope(&self, name: &Token, message: String) -> Interrupt {
+        let names = self.visible_names();
+        Interrupt::Error(RuntimeError::new(
+            ErrorLocation::Token(name.clone()),
+            format!("{}{}", message, did_you_mean(&name.lexeme, names.iter().map(String::as_str))),
+        ))
+    }
+
+    /// The global scope of the running code, where the global `name` is about to be changed.
+    /// Defines the builtin of that name first, so it can be assigned or deleted like other globals.
+    fn global_scope_for(&self, name: &str) -> Rc<RefCell<Environment>> {
+        let scope = self.global_scope();
+        if Rc::ptr_eq(&scope, &self.globals) {
+            self.global_variable(name);
+        }
+        scope
+    }
+
+    /// The global scope of the running code: the scope of a module, or the interpreter globals.
+    fn global_scope(&self) -> Rc<RefCell<Environment>> {
+        if self.modules.is_empty() {
+            return Rc::clone(&self.globals);
+        }
+
+        let mut scope = Rc::clone(&self.environment);
+        loop {
+            let enclosing = scope.borrow().enclosing();
+            match enclosing {
+                Some(enclosing) => scope = enclosing,
+                None => return scope,
+            }
+        }
+    }
+
+    /// Remove a variable, an element of an array or an entry of a dictionary.
+    fn delete(&mut self, keyword: &Token, target: &Expr) -> Result<()> {
+        let (expr, index) = match target {
+            Expr::IndexGet(expr, _, index) => (expr, index),
+            Expr::Variable(name) => {
+                let removed = match self.get_local(name) {
+                    Some(distance) => self.environment.borrow_mut().remove_at(*distance, &name.lexeme),
+                    None => {
+                        let scope = self.global_scope_for(&name.lexeme);
+                        let removed = scope.borrow_mut().remove(&name.lexeme);
+                        if removed && Rc::ptr_eq(&scope, &self.globals) {
+                            self.deleted_globals.insert(name.lexeme.clone());
+                        }
+                        removed
+                    },
+                };
+
+                return if removed {
+                    Ok(())
+                } else {
+                    Err(self.not_in_scope(name, format!("Cannot delete '{}', as it is not found in scope.", name.lexeme)))
+                };
+            },
+            _ => panic!("Delete statement has a target that is not a variable or an element."),
+        };
+
+        let evaluated_expr = self.evaluate(expr)?;
+        let evaluated_index = self.evaluate(index)?;
+        let message = match &evaluated_expr {
+            Literals::Array(arr) => match evaluated_index.unwrap_usize() {
+                Ok(n) if n < arr.borrow().len() => {
+                    change_at(arr, keyword)?.remove(n);
+                    return Ok(());
+                },
+                Ok(n) => format!("Index '{}' out of range.", n),
+                Err(_) => "Index must be an integer.".to_string(),
+            },
+            Literals::Dictionary(dict) => match DictKey::from_literal(&evaluated_index) {
+                Some(key) => match change_at(dict, keyword)?.shift_remove(&key) {
+                    Some(_) => return Ok(()),
+                    None => format!("Key '{}' not found.", key.stringify()),
+                },
+                None => "Index must be an integer/string.".to_string(),
+            },
+            Literals::Tuple(_) => "Cannot delete an element, tuples are immutable.".to_string(),
+            Literals::Frozen(value) => format!("Cannot delete an element, the {} is frozen.", value.to_string().to_lowercase()),
+            _ => format!("Cannot delete by index/key from '{}'.", evaluated_expr.to_string()),
+        };
+
+        Err(Interrupt::Error(RuntimeError::new(ErrorLocation::Token(keyword.clone()), message)))
+    }
+
+    /// Whether a value counts as true in a condition. Instances with a `_bool` method decide themselves,
+    /// and empty strings and collections are false if the `falsy_empty` option is set.
+    pub fn truthy(&mut self, value: &Literals, location: ErrorLocation) -> std::result::Result<bool, RuntimeError> {
+        if let Literals::Instance(_) = value {
+            if let Ok(method @ Literals::Function(_)) = value.as_object().get_property("_bool") {
+                return match self.call_value(method, &vec![], location.clone())? {
+                    Literals::Boolean(b) => Ok(b),
+                    other => Err(RuntimeError::new(
+                        location,
+                        format!("'_bool' must return a boolean but returned '{}'.", other.to_string()),
+                    )),
+                };
+            }
+        }
+
+        if self.options.falsy_empty && is_empty(value) {
+            return Ok(false);
+        }
+
+        Ok(is_truthy(value))
+    }
+
+    /// Pass a newly created collection to the `on_collection` hook.
+    fn collection(&mut self, value: Literals) -> Literals {
+        if let Some(hooks) = &self.options.hooks {
+            hooks.on_collection(&value);
+        }
+        value
+    }
+
+    /// How many of the arrays, dictionaries, instances and functions created while evaluating are still
+    /// alive, e.g. to find leaks in long running sessions. Values are only tracked from the first call on,
+    /// so evaluating costs nothing extra for hosts and scripts that never ask.
+    pub fn runtime_stats(&mut self) -> RuntimeStats {
+        self.values.borrow_mut().get_or_insert_with(ValueTracker::default).stats()
+    }
+
+    /// Call a function or class with evaluated arguments, checking the number of arguments first.
+    pub fn call_value(&mut self, callee: Literals, argument_vals: &Vec<Literals>, location: ErrorLocation) -> std::result::Result<Literals, RuntimeError> {
+        let hooks = self.options.hooks.clone();
+        let name = match &callee {
+            Literals::Class(class) => Some(class.name.clone()),
+            Literals::Function(function) => Some(function.name()),
+            _ => None,
+        };
+
+        if let Some(max_call_depth) = self.options.max_call_depth {
+            if self.call_depth >= max_call_depth {
+                return Err(RuntimeError::new(
+                    location,
+                    format!("Maximum call depth of {} exceeded.", max_call_depth),
+                ));
+            }
+        }
+
+        if let (Some(hooks), Some(name)) = (&hooks, &name) {
+            hooks.on_call(name, argument_vals);
+        }
+
+        self.call_depth += 1;
+        let value = self.call_value_unhooked(callee, argument_vals, location);
+        self.call_depth -= 1;
+
+        if let (Some(hooks), Some(name)) = (&hooks, &name) {
+            match &value {
+                Ok(value) => hooks.on_return(name, value),
+                Err(error) => hooks.on_unwind(name, error),
+            }
+        }
+
+        value
+    }
+
+    fn call_value_unhooked(&mut self, callee: Literals, argument_vals: &Vec<Literals>, location: ErrorLocation) -> std::result::Result<Literals, RuntimeError> {
+        match callee {
+            Literals::Class(class) => self.construct(class, argument_vals, location),
+            Literals::Function(function) => {
+                if !function.arity().accepts(argument_vals.len()) {
+                    return Err(RuntimeError::new(
+                        location,
+                        format!("Expected {} arguments but got {}.", function.arity(), argument_vals.len()),
+                    ));
+                }
+
+                // Builtins do not know where they were called from.
+                function.call(self, argument_vals).map_err(|error| error.or_at(location))
+            },
+            _ => Err(RuntimeError::new(
+                location,
+                format!("Type '{}' is not callable.", callee.to_string()),
+            )),
+        }
+    }
+
+    /// Create an instance of `class` and run its initializer. Always returns the new instance.
+    fn construct(&mut self, class: Rc<DoveClass>, argument_vals: &Vec<Literals>, location: ErrorLocation) -> std::result::Result<Literals, RuntimeError> {
+        let instance = Rc::new(RefCell::new(DoveInstance::new(Rc::clone(&class))));
+        let initializer = class.find_method("init").map(|init| init.bind(Rc::clone(&instance)));
+
+        // A class without an initializer takes no arguments.
+        let arity = initializer.as_ref().map_or(Arity::Fixed(0), |init| init.arity());
+        if !arity.accepts(argument_vals.len()) {
+            return Err(RuntimeError::new(
+                location,
+                format!("Class '{}' expected {} arguments but got {}.", class.name, arity, argument_vals.len()),
+            ));
+        }
+
+        self.initialize_fields(&class, &instance)?;
+        if let Some(initializer) = initializer {
+            initializer.call(self, argument_vals).map_err(|error| error.or_at(location))?;
+        }
+
+        let instance = Literals::Instance(instance);
+        stats::track(&instance);
+        Ok(instance)
+    }
+
+    /// Set the fields declared by the class on a new instance, after those of its superclasses.
+    /// Their values are evaluated for each instance, so instances do not share arrays or dictionaries.
+    fn initialize_fields(&mut self, class: &DoveClass, instance: &Rc<RefCell<DoveInstance>>) -> std::result::Result<(), RuntimeError> {
+        if let Some(superclass) = class.superclass() {
+            self.initialize_fields(superclass, instance)?;
+        }
+
+        let (fields, closure) = match class.fields() {
+            (fields, Some(closure)) => (fields, Rc::clone(closure)),
+            (_, None) => return Ok(()),
+        };
+        for (name, value) in fields {
+            let value = match value {
+                Some(value) => self.evaluate_in(value, Rc::clone(&closure)).map_err(|interrupt| match interrupt {
+                    Interrupt::Error(err) => err,
+                    _ => RuntimeError::new(ErrorLocation::Token(name.clone()), "Unexpected interrupt in a field value.".to_string()),
+                })?,
+                None => Literals::Nil,
+            };
+            instance.borrow_mut().set(name.lexeme.clone(), value);
+        }
+
+        Ok(())
+    }
+
+    /// Apply the binary operator `operator_type` to two evaluated operands.
+    /// `operator` is the token errors are reported at, e.g. `+=` for a compound assignment.
+    fn binary(&mut self, left_val: Literals, operator_type: TokenType, operator: &Token, right_val: Literals) -> Result<Literals> {
+        match operator_type {
+            TokenType::GREATER => Ok(Literals::Boolean(match self.check_number_operand(operator, &left_val, &right_val)? {
+                Operands::Ints(l, r) => l > r,
+                Operands::Numbers(l, r) => l > r,
+            })),
+            TokenType::GREATER_EQUAL => Ok(Literals::Boolean(match self.check_number_operand(operator, &left_val, &right_val)? {
+                Operands::Ints(l, r) => l >= r,
+                Operands::Numbers(l, r) => l >= r,
+            })),
+            TokenType::LESS => Ok(Literals::Boolean(match self.check_number_operand(operator, &left_val, &right_val)? {
+                Operands::Ints(l, r) => l < r,
+                Operands::Numbers(l, r) => l < r,
+            })),
+            TokenType::LESS_EQUAL => Ok(Literals::Boolean(match self.check_number_operand(operator, &left_val, &right_val)? {
+                Operands::Ints(l, r) => l <= r,
+                Operands::Numbers(l, r) => l <= r,
+            })),
+            TokenType::BANG_EQUAL => Ok(Literals::Boolean(!is_equal(&left_val, &right_val))),
+            TokenType::EQUAL_EQUAL => Ok(Literals::Boolean(is_equal(&left_val, &right_val))),
+            TokenType::MINUS => {
+                let operands = self.check_number_operand(operator, &left_val, &right_val)?;
+                int_arithmetic(operator, operands, i64::checked_sub, |l, r| l - r)
+            },
+            TokenType::PERCENT => {
+                let operands = self.check_number_operand(operator, &left_val, &right_val)?;
+                self.check_divisor(operator, &operands)?;
+                int_arithmetic(operator, operands, i64::checked_rem, |l, r| l % r)
+            }
+            TokenType::PLUS => {
+                match (left_val.unfreeze(), right_val.unfreeze()) {
+                    (l @ (Literals::Number(_) | Literals::Int(_)), r @ (Literals::Number(_) | Literals::Int(_))) => {
+                        let operands = self.check_number_operand(operator, &l, &r)?;
+                        int_arithmetic(operator, operands, i64::checked_add, |l, r| l + r)
+                    },
+                    (Literals::String(l), Literals::String(r)) => Ok(Literals::String(format!("{}{}", l, r).into())),
+                    (Literals::String(l), r @ (Literals::Number(_) | Literals::Int(_))) => Ok(Literals::String(format!("{}{}", l, stringify(r)).into())),
+                    (l @ (Literals::Number(_) | Literals::Int(_)), Literals::String(r)) => Ok(Literals::String(format!("{}{}", stringify(l), r).into())),
+                    (Literals::Array(l), Literals::Array(r)) => {
+                        let mut res = Vec::new();
+                        for val in l.borrow().iter() {
+                            res.push(val.clone());
+                        }
+                        for val in r.borrow().iter() {
+                            res.push(val.clone());
+                        }
+
+                        Ok(Literals::array(res))
+                    }
+                    (Literals::Tuple(l), Literals::Tuple(r)) => {
+                        let mut res = Vec::new();
+                        for val in *l {
+                            res.push(val);
+                        }
+                        for val in *r {
+                            res.push(val);
+                        }
+
+                        Ok(Literals::Tuple(Box::new(res)))
+                    }
+                    _ => Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(operator.clone()),
+                        format!("Operands of '{}' must be two numbers/strings/arrays/tuples.", operator.lexeme),
+                    ))),
+                }
+            },
+            // Divides integers too, e.g. `5 / 2` is 2.5, `/<` and `/>` round down and up.
+            TokenType::SLASH => {
+                let operands = self.check_number_operand(operator, &left_val, &right_val)?;
+                self.check_divisor(operator, &operands)?;
+                let (left_val, right_val) = operands.to_numbers();
+                Ok(Literals::Number(left_val / right_val))
+            },
+            TokenType::SLASH_GREATER => {
+                let operands = self.check_number_operand(operator, &left_val, &right_val)?;
+                self.check_divisor(operator, &operands)?;
+                int_arithmetic(operator, operands, ceil_div, |l, r| (l / r).ceil())
+            },
+            TokenType::SLASH_LESS => {
+                let operands = self.check_number_operand(operator, &left_val, &right_val)?;
+                self.check_divisor(operator, &operands)?;
+                int_arithmetic(operator, operands, floor_div, |l, r| (l / r).floor())
+            },
+            TokenType::STAR_STAR => {
+                match self.check_number_operand(operator, &left_val, &right_val)? {
+                    // Negative powers of integers are fractions.
+                    Operands::Ints(l, r) if r >= 0 => Some(r).filter(|r| *r <= u32::MAX as i64)
+                        .and_then(|r| l.checked_pow(r as u32))
+                        .map(Literals::Int)
+                        .ok_or_else(|| Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(operator.clone()),
+                            format!("Integer overflow in '{}'.", operator.lexeme),
+                        ))),
+                    operands => {
+                        let (l, r) = operands.to_numbers();
+                        Ok(Literals::Number(l.powf(r)))
+                    },
+                }
+            },
+            TokenType::STAR => {
+                match (left_val, right_val) {
+                    (l @ (Literals::Number(_) | Literals::Int(_)), r @ (Literals::Number(_) | Literals::Int(_))) => {
+                        let operands = self.check_number_operand(operator, &l, &r)?;
+                        int_arithmetic(operator, operands, i64::checked_mul, |l, r| l * r)
+                    },
+                    (Literals::Number(l), Literals::String(r)) => Ok(Literals::String(r.repeat(l as usize).into())),
+                    (Literals::String(l), Literals::Number(r)) => Ok(Literals::String(l.repeat(r as usize).into())),
+                    (Literals::Int(l), Literals::String(r)) => Ok(Literals::String(r.repeat(l.max(0) as usize).into())),
+                    (Literals::String(l), Literals::Int(r)) => Ok(Literals::String(l.repeat(r.max(0) as usize).into())),
+                    _ => Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(operator.clone()),
+                        format!("Operands of '{}' must be two numbers or a string and a number.", operator.lexeme),
+                    ))),
+                }
+            },
+            TokenType::AMPERSAND => {
+                let (l, r) = self.check_integer_operand(operator, &left_val, &right_val)?;
+                Ok(Literals::Int(l & r))
+            },
+            TokenType::PIPE => {
+                let (l, r) = self.check_integer_operand(operator, &left_val, &right_val)?;
+                Ok(Literals::Int(l | r))
+            },
+            TokenType::CARET => {
+                let (l, r) = self.check_integer_operand(operator, &left_val, &right_val)?;
+                Ok(Literals::Int(l ^ r))
+            },
+            // Shifting right keeps the sign, e.g. `-8 >> 1` is -4.
+            TokenType::LESS_LESS | TokenType::GREATER_GREATER => {
+                let (l, r) = self.check_integer_operand(operator, &left_val, &right_val)?;
+                let shift = if operator_type == TokenType::LESS_LESS { i64::checked_shl } else { i64::checked_shr };
+                Some(r).filter(|r| (0..64).contains(r))
+                    .and_then(|r| shift(l, r as u32))
+                    .map(Literals::Int)
+                    .ok_or_else(|| Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(operator.clone()),
+                        format!("Cannot shift by {}, expected 0 to 63.", r),
+                    )))
+            },
+            _ =>Err(Interrupt::Error(RuntimeError::new(
+                ErrorLocation::Token(operator.clone()),
+                format!("Unsupported operator: '{}'.", operator.lexeme)),
+            )),
+        }
+    }
+
+    /// Read a property of a value, or a deprecated name of a builtin one.
+    fn get_property(&mut self, value: &Literals, name: &Token) -> Result<Literals> {
+        let mut object = value.as_object();
+        if let Ok(value) = object.get_property(&name.lexeme) {
+            return Ok(value);
+        }
+
+        // Old names of builtin properties still work, with a warning.
+        if let Some(canonical) = canonical_name(&name.lexeme) {
+            if let Ok(value) = object.get_property(canonical) {
+                self.deprecated_property(name, canonical);
+                return Ok(value);
+            }
+        }
+
+        let names = object.property_names();
+        Err(Interrupt::Error(RuntimeError::new(
+            ErrorLocation::Token(name.clone()),
+            format!(
+                "Cannot get property '{}' of type '{}'.{}",
+                name.lexeme,
+                value.to_string(),
+                did_you_mean(&name.lexeme, names.iter().map(String::as_str)),
+            ),
+        )))
+    }
+
+    /// Reads an item of an array, tuple, bytes, string or dictionary by its index or key.
+    fn index_get(&self, value: Literals, index: Literals, bracket: &Token) -> Result<Literals> {
+        match value {
+            // Items read through a frozen view are frozen as well.
+            Literals::Frozen(value) => self.index_get(*value, index, bracket).map(Literals::freeze),
+            Literals::Array(arr) => {
+                match index.unwrap_usize() {
+                    Ok(n) => match arr.borrow().get(n) {
+                        Some(v) => Ok(v.clone()),
+                        None => Err(Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(bracket.clone()),
+                            format!("Index '{}' out of range.", n),
+                        ))),
+                    },
+                    Err(_) => Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(bracket.clone()),
+                        "Index must be an integer.".to_string(),
+                    ))),
+                }
+            },
+            Literals::Tuple(tup) => {
+                match index.unwrap_usize() {
+                    Ok(n) => match tup.get(n) {
+                        Some(v) => Ok(v.clone()),
+                        None => Err(Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(bracket.clone()),
+                            format!("Index '{}' out of range.", n),
+                        ))),
+                    },
+                    Err(_) => Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(bracket.clone()),
+                        "Index must be an integer.".to_string(),
+                    ))),
+                }
+            },
+            Literals::Bytes(bytes) => {
+                match index.unwrap_usize() {
+                    Ok(n) => match bytes.get(n) {
+                        Some(byte) => Ok(Literals::Int(*byte as i64)),
+                        None => Err(Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(bracket.clone()),
+                            format!("Index '{}' out of range.", n),
+                        ))),
+                    },
+                    Err(_) => Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(bracket.clone()),
+                        "Index must be an integer.".to_string(),
+                    ))),
+                }
+            },
+            // Strings are indexed by character, not by byte.
+            Literals::String(string) => {
+                match index.unwrap_usize() {
+                    Ok(n) => match string.chars().nth(n) {
+                        Some(c) => Ok(Literals::String(c.to_string().into())),
+                        None => Err(Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(bracket.clone()),
+                            format!("Index '{}' out of range.", n),
+                        ))),
+                    },
+                    Err(_) => Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(bracket.clone()),
+                        "Index must be an integer.".to_string(),
+                    ))),
+                }
+            },
+            Literals::Dictionary(dict) => {
+                let dict_key = match DictKey::from_literal(&index) {
+                    Some(key) => key,
+                    None => return Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(bracket.clone()),
+                        "Index must be an integer/string.".to_string(),
+                    ))),
+                };
+
+                match dict.borrow().get(&dict_key) {
+                    Some(v) => Ok(v.clone()),
+                    None => Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(bracket.clone()),
+                        format!("Key '{}' not found.", dict_key.stringify()),
+                    )))
+                }
+            },
+            _ => Err(Interrupt::Error(RuntimeError::new(
+                ErrorLocation::Token(bracket.clone()),
+                format!("Cannot get value by index/key from '{}'.", value.to_string()),
+            ))),
+        }
+    }
+
+    fn check_number_operand(&mut self, operator: &Token, left: &Literals, right: &Literals) -> Result<Operands> {
         match (left, right) {
-            (Literals::Number(l), Literals::Number(r)) => Ok((*l, *r)),
+            (Literals::Int(l), Literals::Int(r)) => Ok(Operands::Ints(*l, *r)),
+            (Literals::Number(_) | Literals::Int(_), Literals::Number(_) | Literals::Int(_)) => Ok(Operands::Numbers(
+                left.clone().unwrap_number().unwrap_or(f64::NAN),
+                right.clone().unwrap_number().unwrap_or(f64::NAN),
+            )),
             _ => Err(Interrupt::Error(RuntimeError::new(
                 ErrorLocation::Token(operator.clone()),
                 format!("Operands of '{}' must be two numbers.", operator.lexeme),
@@ -152,9 +1028,22 @@ impl Interpreter {
         }
     }
 
-    fn check_integer_operand(&mut self, operator: &Token, left: &Literals, right: &Literals) -> Result<(i32, i32)> {
-        match self.check_number_operand(operator, left, right) {
-            Ok((l, r)) if l.fract() == 0.0 && r.fract() == 0.0 => Ok((l as i32, r as i32)),
+    /// In strict mode, dividing by zero is an error, like it always is for integers, instead of giving infinity or NaN.
+    fn check_divisor(&self, operator: &Token, operands: &Operands) -> Result<()> {
+        let (_, divisor) = operands.to_numbers();
+        if divisor == 0.0 && self.options.strict {
+            return Err(Interrupt::Error(RuntimeError::new(
+                ErrorLocation::Token(operator.clone()),
+                "Division by zero.".to_string(),
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn check_integer_operand(&mut self, operator: &Token, left: &Literals, right: &Literals) -> Result<(i64, i64)> {
+        match (left.clone().unwrap_int(), right.clone().unwrap_int()) {
+            (Some(l), Some(r)) => Ok((l, r)),
             _ => Err(Interrupt::Error(RuntimeError::new(
                 ErrorLocation::Token(operator.clone()),
                 format!("Operands of '{}' must be two integers.", operator.lexeme)),
@@ -179,181 +1068,56 @@ impl ExprVisitor for Interpreter {
                 for expr in expressions {
                     arr_vals.push(self.evaluate(expr)?);
                 }
-                Ok(Literals::Array(Rc::new(RefCell::new(arr_vals))))
+                Ok(self.collection(Literals::array(arr_vals)))
             },
 
             Expr::Assign(name, op, value) => {
-                let line = op.line;
-                let val = match op.token_type {
-                    TokenType::EQUAL => {
-                        self.evaluate(value)?
-                    },
-                    TokenType::PLUS_EQUAL | TokenType::PLUS_PLUS => {
-                        self.evaluate(&Expr::Binary(Box::new(Expr::Variable(name.clone())),
-                                                         Token::new(0, TokenType::PLUS, "+".to_string(), None, line),
-                                                         value.clone()))?
-                    },
-                    TokenType::MINUS_EQUAL | TokenType::MINUS_MINUS => {
-                        self.evaluate(&Expr::Binary(Box::new(Expr::Variable(name.clone())),
-                                                    Token::new(0, TokenType::MINUS, "-".to_string(), None, line),
-                                                    value.clone()))?
-                    },
-                    TokenType::STAR_EQUAL => {
-                        self.evaluate(&Expr::Binary(Box::new(Expr::Variable(name.clone())),
-                                                    Token::new(0, TokenType::STAR, "*".to_string(), None, line),
-                                                    value.clone()))?
+                // Compound assignments apply the operator directly, without building a binary expression.
+                let val = match compound_operator(op) {
+                    None => self.evaluate(value)?,
+                    Some(operator_type) => {
+                        let current = match self.lookup_variable(name) {
+                            Some(current) => current,
+                            None => return Err(self.variable_not_found(name)),
+                        };
+                        let value = self.evaluate(value)?;
+                        self.binary(current, operator_type, op, value)?
                     },
-                    TokenType::SLASH_EQUAL => {
-                        self.evaluate(&Expr::Binary(Box::new(Expr::Variable(name.clone())),
-                                                    Token::new(0, TokenType::SLASH, "/".to_string(), None, line),
-                                                    value.clone()))?
-                    }
-                    _ => panic!("Magically found non assignment operator wrapped inside an Expr::Assign.")
                 };
 
                 let assigned = match self.get_local(name) {
-                    Some(distance) => self.environment.borrow_mut().assign_at(*distance, name.lexeme.clone(), val.clone()),
-                    None => self.globals.borrow_mut().assign(name.lexeme.clone(), val.clone()),
+                    Some(distance) => self.environment.borrow_mut().assign_at(*distance, &name.lexeme, val.clone()),
+                    None => self.global_scope_for(&name.lexeme).borrow_mut().assign(&name.lexeme, val.clone()),
                 };
 
                 if assigned {
                     Ok(val)
                 } else {
-                    Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Token(name.clone()),
-                        format!("Cannot assign value to '{}', as it is not found in scope.", name.lexeme),
-                    )))
+                    Err(self.not_in_scope(name, format!("Cannot assign value to '{}', as it is not found in scope.", name.lexeme)))
                 }
             },
 
             Expr::Binary(left, operator, right) => {
                 let left_val = self.evaluate(left)?;
-                let right_val = self.evaluate(right)?;
-
-                match operator.token_type {
-                    TokenType::AND => Ok(Literals::Boolean(is_truthy(&left_val) && is_truthy(&right_val))),
-                    TokenType::OR => Ok(Literals::Boolean(is_truthy(&left_val) || is_truthy(&right_val))),
-                    TokenType::GREATER => {
-                        let (left_val, right_val) = self.check_number_operand(operator, &left_val, &right_val)?;
-                        Ok(Literals::Boolean(left_val > right_val))
-                    },
-                    TokenType::GREATER_EQUAL => {
-                        let (left_val, right_val) = self.check_number_operand(operator, &left_val, &right_val)?;
-                        Ok(Literals::Boolean(left_val >= right_val))
-                    },
-                    TokenType::LESS => {
-                        let (left_val, right_val) = self.check_number_operand(operator, &left_val, &right_val)?;
-                        Ok(Literals::Boolean(left_val < right_val))
-                    },
-                    TokenType::LESS_EQUAL => {
-                        let (left_val, right_val) = self.check_number_operand(operator, &left_val, &right_val)?;
-                        Ok(Literals::Boolean(left_val <= right_val))
-                    },
-                    TokenType::BANG_EQUAL => Ok(Literals::Boolean(!is_equal(&left_val, &right_val))),
-                    TokenType::EQUAL_EQUAL => Ok(Literals::Boolean(is_equal(&left_val, &right_val))),
-                    TokenType::MINUS => {
-                        let (left_val, right_val) = self.check_number_operand(operator, &left_val, &right_val)?;
-                        Ok(Literals::Number(left_val - right_val))
-                    },
-                    TokenType::PERCENT => {
-                        let (left_val, right_val) = self.check_number_operand(operator, &left_val, &right_val)?;
-                        Ok(Literals::Number(left_val % right_val))
-                    }
-                    TokenType::PLUS => {
-                        match (left_val, right_val) {
-                            (Literals::Number(l), Literals::Number(r)) => Ok(Literals::Number(l + r)),
-                            (Literals::String(l), Literals::String(r)) => Ok(Literals::String(format!("{}{}", l, r))),
-                            (Literals::String(l), Literals::Number(r)) => Ok(Literals::String(format!("{}{}", l, r))),
-                            (Literals::Number(l), Literals::String(r)) => Ok(Literals::String(format!("{}{}", l, r))),
-                            (Literals::Array(l), Literals::Array(r)) => {
-                                let mut res = Vec::new();
-                                for val in l.borrow().iter() {
-                                    res.push(val.clone());
-                                }
-                                for val in r.borrow().iter() {
-                                    res.push(val.clone());
-                                }
-
-                                Ok(Literals::Array(Rc::new(RefCell::new(res))))
-                            }
-                            (Literals::Tuple(l), Literals::Tuple(r)) => {
-                                let mut res = Vec::new();
-                                for val in *l {
-                                    res.push(val);
-                                }
-                                for val in *r {
-                                    res.push(val);
-                                }
-
-                                Ok(Literals::Tuple(Box::new(res)))
-                            }
-                            _ => Err(Interrupt::Error(RuntimeError::new(
-                                ErrorLocation::Token(operator.clone()),
-                                format!("Operands of '{}' must be two numbers/strings/arrays/tuples.", operator.lexeme),
-                            ))),
-                        }
-                    },
-                    TokenType::SLASH => {
-                        let (left_val, right_val) = self.check_number_operand(operator, &left_val, &right_val)?;
-                        Ok(Literals::Number(left_val / right_val))
-                    },
-                    TokenType::SLASH_GREATER => {
-                        let (left_val, right_val) = self.check_number_operand(operator, &left_val, &right_val)?;
-                        Ok(Literals::Number((left_val / right_val).ceil()))
-                    },
-                    TokenType::SLASH_LESS => {
-                        let (left_val, right_val) = self.check_number_operand(operator, &left_val, &right_val)?;
-                        Ok(Literals::Number((left_val / right_val).floor()))
-                    },
-                    TokenType::STAR => {
-                        match (left_val, right_val) {
-                            (Literals::Number(l), Literals::Number(r)) => Ok(Literals::Number(l * r)),
-                            (Literals::Number(l), Literals::String(r)) => Ok(Literals::String(r.repeat(l as usize))),
-                            (Literals::String(l), Literals::Number(r)) => Ok(Literals::String(l.repeat(r as usize))),
-                            _ => Err(Interrupt::Error(RuntimeError::new(
-                                ErrorLocation::Token(operator.clone()),
-                                format!("Operands of '{}' must be two numbers or a string and a number.", operator.lexeme),
-                            ))),
-                        }
-                    },
-                    TokenType::DOT_DOT => {
-                        let (left, right) = self.check_integer_operand(operator, &left_val, &right_val)?;
 
-                        let is_right_bigger = right >= left;
-                        let diff = (right - left).abs();
-
-                        let mut res = Vec::new();
-                        for i in 0..diff {
-                            let new = left + i * if is_right_bigger { 1 } else { -1 };
-                            res.push(Literals::Number(new as f64));
-                        }
-
-                        Ok(Literals::Tuple(Box::new(res)))
-                    },
-                    TokenType::DOT_DOT_DOT => {
-                        let (left, right) = self.check_integer_operand(operator, &left_val, &right_val)?;
-
-                        let is_right_bigger = right >= left;
-                        let diff = (right - left).abs();
+                // `and`/`or` only evaluate the right operand if the left one does not decide the result,
+                // and return the deciding operand rather than a boolean.
+                if let TokenType::AND | TokenType::OR = operator.token_type {
+                    let left_truthy = self.truthy(&left_val, ErrorLocation::Token(operator.clone()))?;
+                    return if left_truthy == (operator.token_type == TokenType::OR) {
+                        Ok(left_val)
+                    } else {
+                        self.evaluate(right)
+                    };
+                }
 
-                        let mut res = Vec::new();
-                        for i in 0..(diff + 1) {
-                            let new = left + i * if is_right_bigger { 1 } else { -1 };
-                            res.push(Literals::Number(new as f64));
-                        }
+                let right_val = self.evaluate(right)?;
 
-                        Ok(Literals::Tuple(Box::new(res)))
-                    },
-                    _ =>Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Token(operator.clone()),
-                        format!("Unsupported operator: '{}'.", operator.lexeme)),
-                    )),
-                }
+                self.binary(left_val, operator.token_type, operator, right_val)
             },
 
             Expr::Call(callee, paren, arguments) => {
                 let callee_val = self.evaluate(callee)?;
-                let callee_type = (&callee_val).to_string();
 
                 // Evaluate argument literals.
                 let mut argument_vals = Vec::new();
@@ -361,87 +1125,41 @@ impl ExprVisitor for Interpreter {
                     argument_vals.push(self.evaluate(argument)?);
                 }
 
-                // TODO: simplify
-                match callee_val {
-                    Literals::Class(class) => {
-                        let instance = Rc::new(RefCell::new(DoveInstance::new(Rc::clone(&class))));
-
-                        if let Some(initializer) = class.find_method("init") {
-                            let bound_init = initializer.bind(Rc::clone(&instance));
-
-                            // TODO: move this somewhere else? inside function.call?
-                            if argument_vals.len() != bound_init.arity() {
-                                return Err(Interrupt::Error(RuntimeError::new(
-                                    ErrorLocation::Token(paren.clone()),
-                                    format!("Expected {} arguments but got {}.", bound_init.arity(), argument_vals.len()),
-                                )));
-                            }
-
-                            bound_init.call(self, &argument_vals)?;
-                        }
-
-                        Ok(Literals::Instance(instance))
-                    },
-                    Literals::Function(function) => {
-                        // Check arity.
-                        if argument_vals.len() != function.arity() {
-                            return Err(Interrupt::Error(RuntimeError::new(
-                                ErrorLocation::Token(paren.clone()),
-                                format!("Expected {} arguments but got {}.", function.arity(), argument_vals.len()),
-                            )));
-                        }
-
-                        Ok(function.call(self, &argument_vals)?)
-                    },
-                    _ => Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Token(paren.clone()),
-                        format!("Type '{}' is not callable.", callee_type),
-                    ))),
-                }
+                Ok(self.call_value(callee_val, &argument_vals, ErrorLocation::Token(paren.clone()))?)
             },
 
-            Expr::Dictionary(expressions) => {
-                let mut dict_val = HashMap::new();
+            Expr::Dictionary(brace, expressions) => {
+                let mut dict_val = IndexMap::new();
                 for (key_expr, val_expr) in expressions.iter() {
-                    let key = self.evaluate(key_expr).unwrap();
-                    let val = self.evaluate(val_expr).unwrap();
+                    let key = self.evaluate(key_expr)?;
+                    let val = self.evaluate(val_expr)?;
 
-                    // Check if key expr evaluates to String or Number.
-                    match key {
-                        Literals::String(key) => {
-                            dict_val.insert(DictKey::StringKey(key), val);
-                        },
-                        Literals::Number(key) if key.fract() != 0.0 =>{
-                            dict_val.insert(DictKey::NumberKey(key as isize), val);
+                    // Check if key expr evaluates to String or integer.
+                    match DictKey::from_literal(&key) {
+                        Some(key) => {
+                            dict_val.insert(key, val);
                         },
-
-                        _ => return Err(Interrupt::Error(RuntimeError::new(
-                            ErrorLocation::Unspecified,
+                        None => return Err(Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(brace.clone()),
                             "Only String and Integer can be used as dictionary key.".to_string(),
                         ))),
                     };
                 }
-                Ok(Literals::Dictionary(Rc::new(RefCell::new(dict_val))))
-            },
-
-            Expr::Grouping(expression) => {
-                self.evaluate(expression)
+                Ok(self.collection(Literals::dictionary(dict_val)))
             },
 
-            Expr::Get(object, name) => {
-                let expr = self.visit_expr(object)?;
-
-                match expr.as_object().get_property(&name.lexeme) {
-                    Ok(value) => Ok(value),
-                    Err(_) => Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Token(name.clone()),
-                        format!("Cannot get property '{}' of type '{}'.", name.lexeme, expr.to_string()),
-                    ))),
-                }
+            Expr::Grouping(expression) => {
+                self.evaluate(expression)
+            },
+
+            Expr::Get(object, name) => {
+                let value = self.visit_expr(object)?;
+                self.get_property(&value, name)
             }
 
             Expr::IfExpr(condition, then_branch, else_branch) => {
-                let condition_val = is_truthy(&self.evaluate(condition)?);
+                let condition_val = self.evaluate(condition)?;
+                let condition_val = self.truthy(&condition_val, condition_location(condition))?;
 
                 let branch = if condition_val {
                     then_branch
@@ -460,70 +1178,25 @@ impl ExprVisitor for Interpreter {
                 Ok(value)
             },
 
-            Expr::IndexGet(expr, index) => {
+            Expr::IndexGet(expr, bracket, index) => {
                 let evaluated_expr = self.evaluate(expr)?;
                 let evaluated_index = self.evaluate(index)?;
 
-                match evaluated_expr {
-                    Literals::Array(arr) => {
-                        match evaluated_index.unwrap_usize() {
-                            Ok(n) => match arr.borrow().get(n) {
-                                Some(v) => Ok(v.clone()),
-                                None => Err(Interrupt::Error(RuntimeError::new(
-                                    ErrorLocation::Unspecified,
-                                    format!("Index '{}' out of range.", n),
-                                ))),
-                            },
-                            Err(_) => Err(Interrupt::Error(RuntimeError::new(
-                                ErrorLocation::Unspecified,
-                                "Index must be an integer.".to_string(),
-                            ))),
-                        }
-                    },
-                    Literals::Tuple(tup) => {
-                        match evaluated_index.unwrap_usize() {
-                            Ok(n) => match tup.get(n) {
-                                Some(v) => Ok(v.clone()),
-                                None => Err(Interrupt::Error(RuntimeError::new(
-                                    ErrorLocation::Unspecified,
-                                    format!("Index '{}' out of range.", n),
-                                ))),
-                            },
-                            Err(_) => Err(Interrupt::Error(RuntimeError::new(
-                                ErrorLocation::Unspecified,
-                                "Index must be an integer.".to_string(),
-                            ))),
-                        }
-                    },
-                    Literals::Dictionary(dict) => {
-                        let dict_key = match evaluated_index {
-                            Literals::Number(i) if i.fract() != 0.0 => DictKey::NumberKey(i as isize),
-                            Literals::String(s) => DictKey::StringKey(s.clone()),
-                            _ => return Err(Interrupt::Error(RuntimeError::new(
-                                ErrorLocation::Unspecified,
-                                "Index must be an integer/string.".to_string(),
-                            ))),
-                        };
-
-                        match dict.borrow().get(&dict_key) {
-                            Some(v) => Ok(v.clone()),
-                            None => Err(Interrupt::Error(RuntimeError::new(
-                                ErrorLocation::Unspecified,
-                                format!("Key '{}' not found.", dict_key.stringify()),
-                            )))
-                        }
-                    },
-                    _ => Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Unspecified,
-                        format!("Cannot get value by index/key from '{}'.", evaluated_expr.to_string()),
-                    ))),
-                }
+                self.index_get(evaluated_expr, evaluated_index, bracket)
             }
 
-            Expr::IndexSet(expr, index, value) => {
+            Expr::IndexSet(expr, bracket, index, op, value) => {
                 let evaluated_expr = self.evaluate(expr)?;
                 let evaluated_index = self.evaluate(index)?;
-                let evaluated_value = self.evaluate(value)?;
+                // The collection and index are evaluated once, also to read the current element.
+                let evaluated_value = match compound_operator(op) {
+                    None => self.evaluate(value)?,
+                    Some(operator_type) => {
+                        let current = self.index_get(evaluated_expr.clone(), evaluated_index.clone(), bracket)?;
+                        let value = self.evaluate(value)?;
+                        self.binary(current, operator_type, op, value)?
+                    },
+                };
 
                 match evaluated_expr {
                     Literals::Array(arr) => {
@@ -532,26 +1205,25 @@ impl ExprVisitor for Interpreter {
                                 let old_val = match arr.borrow().get(n) {
                                     Some(v) => v.clone(),
                                     None => return Err(Interrupt::Error(RuntimeError::new(
-                                        ErrorLocation::Unspecified,
+                                        ErrorLocation::Token(bracket.clone()),
                                         format!("Index '{}' out of range.", n),
                                     ))),
                                 };
                                 // Index must exist, otherwise already returned Err(Interrupt::Error).
-                                arr.borrow_mut()[n] = evaluated_value;
+                                change_at(&arr, bracket)?[n] = evaluated_value;
                                 Ok(old_val)
                             },
                             Err(_) => Err(Interrupt::Error(RuntimeError::new(
-                                ErrorLocation::Unspecified,
+                                ErrorLocation::Token(bracket.clone()),
                                 "Index must be an integer.".to_string(),
                             ))),
                         }
                     },
                     Literals::Dictionary(dict) => {
-                        let dict_key = match evaluated_index {
-                            Literals::Number(i) if i.fract() != 0.0 => DictKey::NumberKey(i as isize),
-                            Literals::String(s) => DictKey::StringKey(s.clone()),
-                            _ => return Err(Interrupt::Error(RuntimeError::new(
-                                ErrorLocation::Unspecified,
+                        let dict_key = match DictKey::from_literal(&evaluated_index) {
+                            Some(key) => key,
+                            None => return Err(Interrupt::Error(RuntimeError::new(
+                                ErrorLocation::Token(bracket.clone()),
                                 "Index must be an integer/string.".to_string(),
                             ))),
                         };
@@ -560,28 +1232,74 @@ impl ExprVisitor for Interpreter {
                             Some(v) => v.clone(),
                             None => Literals::Nil,
                         };
-                        dict.borrow_mut().insert(dict_key, evaluated_value);
+                        change_at(&dict, bracket)?.insert(dict_key, evaluated_value);
                         Ok(old_val)
                     }
+                    Literals::Tuple(_) => Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(bracket.clone()),
+                        "Cannot set value by index, tuples are immutable.".to_string(),
+                    ))),
+                    Literals::Frozen(value) => Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(bracket.clone()),
+                        format!("Cannot set value by index/key, the {} is frozen.", value.to_string().to_lowercase()),
+                    ))),
                     _ => Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Unspecified,
+                        ErrorLocation::Token(bracket.clone()),
                         format!("Cannot set value by index/key from '{}'.", evaluated_expr.to_string()),
                     ))),
                 }
             }
 
-            Expr::Lambda(params, body) => {
-                let lambda = DoveFunction::new(params.clone(), *body.clone(), Rc::clone(&self.environment));
-                Ok(Literals::Function(Rc::new(lambda)))
+            Expr::Lambda(keyword, params, body) => {
+                let lambda = DoveFunction::new("<lambda>".to_string(), keyword, params.clone(), *body.clone(), Rc::clone(&self.environment));
+                let lambda = Literals::Function(Rc::new(lambda));
+                stats::track(&lambda);
+                Ok(lambda)
             }
 
             Expr::Literal(value) => {
                 Ok(value.clone())
             },
 
-            Expr::Set(object, name, value) => {
+            Expr::Range(start, operator, end, step) => {
+                let start_val = self.evaluate(start)?;
+                let end_val = self.evaluate(end)?;
+                let (left, right) = self.check_integer_operand(operator, &start_val, &end_val)?;
+
+                let step = match step {
+                    Some(step) => match self.evaluate(step)?.unwrap_usize() {
+                        Ok(step) if step > 0 => step,
+                        _ => return Err(Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(operator.clone()),
+                            "Step of a range must be a positive integer.".to_string(),
+                        ))),
+                    },
+                    None => 1,
+                };
+
+                let is_right_bigger = right >= left;
+                let diff = (right - left).abs();
+                let count = if operator.token_type == TokenType::DOT_DOT_DOT { diff + 1 } else { diff };
+
+                let mut res = Vec::new();
+                for i in (0..count).step_by(step) {
+                    let new = left + i * if is_right_bigger { 1 } else { -1 };
+                    res.push(Literals::Int(new));
+                }
+
+                Ok(Literals::Tuple(Box::new(res)))
+            },
+
+            Expr::Set(object, name, op, value) => {
                 let expr = self.visit_expr(object)?;
-                let value = self.visit_expr(value)?;
+                let value = match compound_operator(op) {
+                    None => self.visit_expr(value)?,
+                    Some(operator_type) => {
+                        let current = self.get_property(&expr, name)?;
+                        let value = self.visit_expr(value)?;
+                        self.binary(current, operator_type, op, value)?
+                    },
+                };
 
                 match expr.as_object().set_property(&name.lexeme, value.clone()) {
                     Ok(_) => Ok(value),
@@ -596,9 +1314,10 @@ impl ExprVisitor for Interpreter {
                 if let Some(instance) = self.lookup_variable(token) {
                     Ok(instance)
                 } else {
+                    // The resolver only allows `self` inside methods, so the method was called from its class.
                     Err(Interrupt::Error(RuntimeError::new(
                         ErrorLocation::Token(token.clone()),
-                        format!("Cannot find '{}' in the scope.", token.lexeme),
+                        format!("Cannot use '{}' in a method called from its class.", token.lexeme),
                     )))
                 }
             }
@@ -631,17 +1350,14 @@ impl ExprVisitor for Interpreter {
                 };
 
                 // TODO: find a more "elegant" solution. If so, remember to change visit super/self in resolver
-                // TODO: consider for static methods?
+                // The scope of `self` is right inside the scope of `super`. If the method was called from
+                // its class, `self` is not defined there, so the superclass method is not bound either.
                 let maybe_instance = self.environment.borrow().get_at(distance - 1, &keywords::SELF);
-                let instance = match maybe_instance {
-                    Some(Literals::Instance(instance)) => instance,
-                    _ => return Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Line(token.line),
-                        "Cannot find 'self' in the scope".to_string(),
-                    ))),
+                let bound_method = match maybe_instance {
+                    Some(Literals::Instance(instance)) => method.bind(instance),
+                    _ => method.unbound(),
                 };
 
-                let bound_method = method.bind(instance);
                 Ok(Literals::Function(Rc::new(bound_method)))
             }
 
@@ -650,21 +1366,32 @@ impl ExprVisitor for Interpreter {
                 for expr in expressions {
                     tup_vals.push(self.evaluate(expr)?);
                 }
-                Ok(Literals::Tuple(Box::new(tup_vals)))
+                Ok(self.collection(Literals::Tuple(Box::new(tup_vals))))
             },
 
             Expr::Unary(operator, right) => {
-                let right_val = self.evaluate(right).unwrap();
+                let right_val = self.evaluate(right)?;
 
                 match operator.token_type {
-                    TokenType::BANG | TokenType::NOT => Ok(Literals::Boolean(!is_truthy(&right_val))),
+                    TokenType::BANG | TokenType::NOT => Ok(Literals::Boolean(!self.truthy(&right_val, ErrorLocation::Token(operator.clone()))?)),
                     TokenType::MINUS => match right_val {
                         Literals::Number(n) => Ok(Literals::Number(-n)),
+                        Literals::Int(n) => n.checked_neg().map(Literals::Int).ok_or_else(|| Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(operator.clone()),
+                            format!("Integer overflow in '{}'.", operator.lexeme),
+                        ))),
                         _ => Err(Interrupt::Error(RuntimeError::new(
                             ErrorLocation::Token(operator.clone()),
                             format!("Operand of '{}' must be a number.", operator.lexeme),
                         ))),
                     },
+                    TokenType::TILDE => match right_val.clone().unwrap_int() {
+                        Some(n) => Ok(Literals::Int(!n)),
+                        None => Err(Interrupt::Error(RuntimeError::new(
+                            ErrorLocation::Token(operator.clone()),
+                            format!("Operand of '{}' must be an integer.", operator.lexeme),
+                        ))),
+                    },
                     _ => Err(Interrupt::Error(RuntimeError::new(
                         ErrorLocation::Token(operator.clone()),
                         format!("Unsupported unary operator {}.", operator.lexeme),
@@ -673,14 +1400,7 @@ impl ExprVisitor for Interpreter {
             },
 
             Expr::Variable(name) => {
-                if let Some(value) = self.lookup_variable(name) {
-                    Ok(value)
-                } else {
-                    Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Token(name.clone()),
-                        format!("Variable '{}' not found in scope.", name.lexeme)),
-                    ))
-                }
+                self.lookup_variable(name).ok_or_else(|| self.variable_not_found(name))
             },
         }
     }
@@ -696,16 +1416,17 @@ impl StmtVisitor for Interpreter {
                 self.execute_block(statements, Environment::new(Some(self.environment.clone())))
             },
 
-            Stmt::Break(_) => {
-                Err(Interrupt::Break)
+            Stmt::Break(_, label) => {
+                Err(Interrupt::Break(label.as_ref().map(|label| label.lexeme.clone())))
             },
 
-            Stmt::Continue(_) => {
-                Err(Interrupt::Continue)
+            Stmt::Continue(_, label) => {
+                Err(Interrupt::Continue(label.as_ref().map(|label| label.lexeme.clone())))
             },
 
-            Stmt::Class(name, superclass_name, methods) => {
+            Stmt::Class(name, superclass_name, members) => {
                 let mut methods_map = HashMap::new();
+                let mut fields = vec![];
 
                 let mut superclass = None;
 
@@ -720,12 +1441,18 @@ impl StmtVisitor for Interpreter {
                     }
                 }
 
-                for method in methods {
+                for member in members {
                     let mut environment = Rc::clone(&self.environment);
 
-                    let (name, params, body) = match method {
+                    let (method_name, params, body) = match member {
                         Stmt::Function(name, params, body) => (name, params, body),
-                        _ => panic!("Class contains non-method statements."),
+                        Stmt::Variable(Pattern::Binding(name), value) => {
+                            fields.push((name.clone(), value.clone()));
+                            continue;
+                        },
+                        // The docstring.
+                        Stmt::Expression(_) => continue,
+                        _ => panic!("Class contains statements other than fields and methods."),
                     };
 
                     if let Some(superclass) = &superclass {
@@ -736,11 +1463,18 @@ impl StmtVisitor for Interpreter {
                         );
                     }
 
-                    let function = Rc::new(DoveFunction::new(params.clone(), *body.clone(), environment));
-                    methods_map.insert(name.lexeme.clone(), function);
+                    let mut function = DoveFunction::new(
+                        format!("{}.{}", name.lexeme, method_name.lexeme), method_name, params.clone(), *body.clone(), environment,
+                    );
+                    if method_name.lexeme == "init" {
+                        function = function.initializer();
+                    }
+                    methods_map.insert(method_name.lexeme.clone(), Rc::new(function));
                 }
 
-                let class = Rc::new(DoveClass::new(name.lexeme.clone(), superclass, methods_map));
+                let class = Rc::new(DoveClass::new(name.lexeme.clone(), superclass, methods_map)
+                    .with_fields(fields, Rc::clone(&self.environment))
+                    .with_doc(docstring(members).map(str::to_string)));
 
                 self.environment.borrow_mut().define(name.lexeme.clone(), Literals::Class(class));
 
@@ -752,83 +1486,49 @@ impl StmtVisitor for Interpreter {
                 Ok(())
             },
 
-            Stmt::For(var_name, range_name, body) => {
-                let range_vals = self.evaluate(range_name)?;
+            Stmt::For(pattern, iterable, body, label) => {
+                let iterable = self.evaluate(iterable)?;
                 let stmts = match &**body {
                     Stmt::Block(stmts) => stmts,
                     _ => return Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Token(var_name.clone()),
+                        pattern_location(pattern, stmt.line()),
                         "Expected block statement in a 'for' loop.".to_string(),
                     ))),
                 };
 
-                match range_vals {
-                    Literals::Array(arr) => {
-
-                        // Use loop with index to avoid having a reference to arr while executing `stmts`
-                        let mut index = 0;
-
-                        loop {
-                            let item = match arr.borrow().get(index) {
-                                Some(item) => item.clone(),
-                                None => break,
-                            };
-                            // Reference to arr is dropped here
-
-                            let mut sub_env = Environment::new(Some(self.environment.clone()));
-                            sub_env.define(var_name.lexeme.clone(), item);
-
-                            if let Err(interrupt) = self.execute_block(&stmts, sub_env) {
-                                match interrupt {
-                                    Interrupt::Break => return Ok(()),
-                                    Interrupt::Continue => {},
-                                    _ => return Err(interrupt),
-                                }
-                            }
-
-                            index += 1;
-                        }
-
-                        Ok(())
-                    },
-
-                    Literals::Tuple(t) => {
-                        let tup = *t;
-
-                        for item in tup.iter() {
-                            let mut sub_env = Environment::new(Some(self.environment.clone()));
-                            sub_env.define(var_name.lexeme.clone(), item.clone());
+                let mut items = iterator(self, iterable, pattern_location(pattern, stmt.line()))?;
+                while let Some(item) = items.next(self)? {
+                    let mut sub_env = Environment::new(Some(self.environment.clone()));
+                    for (name, value) in unpack(pattern, item, stmt.line())? {
+                        sub_env.define(name, value);
+                    }
 
-                            if let Err(interrupt) = self.execute_block(&stmts, sub_env) {
-                                match interrupt {
-                                    Interrupt::Break => return Ok(()),
-                                    Interrupt::Continue => {},
-                                    _ => return Err(interrupt),
-                                }
-                            }
+                    if let Err(interrupt) = self.execute_block(stmts, sub_env) {
+                        if loop_interrupt(interrupt, label)? {
+                            return Ok(());
                         }
-
-                        Ok(())
                     }
-
-                    _ => Err(Interrupt::Error(RuntimeError::new(
-                        ErrorLocation::Token(var_name.clone()),
-                        format!("Cannot iterate over type '{}'", range_vals.to_string())
-                    ))),
                 }
+
+                Ok(())
             },
 
             Stmt::Function(name, params, body) => {
                 // Convert DoveFunction to Function Literal.
-                let function = DoveFunction::new(params.clone(), *body.clone(), Rc::clone(&self.environment));
+                let function = DoveFunction::new(name.lexeme.clone(), name, params.clone(), *body.clone(), Rc::clone(&self.environment));
                 let function_literal = Literals::Function(Rc::new(function));
+                stats::track(&function_literal);
                 self.environment.borrow_mut().define(name.lexeme.clone(), function_literal);
                 Ok(())
             },
 
+            Stmt::Import(_, path, target) => {
+                self.import(path, target)
+            },
+
             Stmt::Print(_, expression) => {
                 let literal = self.evaluate(expression)?;
-                self.output.print(stringify(literal));
+                self.output.print(self.display(literal));
                 Ok(())
             },
 
@@ -840,28 +1540,146 @@ impl StmtVisitor for Interpreter {
                 Err(Interrupt::Return(value))
             },
 
-            Stmt::Variable(name, initializer) => {
+            Stmt::Variable(pattern, initializer) => {
                 let val = match initializer {
                     Some(i) => self.evaluate(i)?,
                     None => Literals::Nil,
                 };
-                self.environment.borrow_mut().define(name.lexeme.clone(), val);
+
+                let bindings = unpack(pattern, val, stmt.line())?;
+                let mut environment = self.environment.borrow_mut();
+                for (name, value) in bindings {
+                    environment.define(name, value);
+                }
+                Ok(())
+            },
+
+            Stmt::While(condition, body, label) => {
+                loop {
+                    let condition_val = self.evaluate(condition)?;
+                    if !self.truthy(&condition_val, condition_location(condition))? {
+                        break;
+                    }
+
+                    if let Err(interrupt) = self.execute(body) {
+                        if loop_interrupt(interrupt, label)? {
+                            return Ok(());
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            Stmt::Delete(keyword, target) => self.delete(keyword, target),
+
+            Stmt::Match(_, value, cases) => {
+                let value = self.evaluate(value)?;
+
+                for case in cases {
+                    let mut bindings = vec![];
+                    if !match_pattern(&case.pattern, &value, true, &mut bindings) {
+                        continue;
+                    }
+
+                    let mut sub_env = Environment::new(Some(self.environment.clone()));
+                    for (name, value) in bindings {
+                        sub_env.define(name, value);
+                    }
+
+                    let stmts = match &case.body {
+                        Stmt::Block(stmts) => stmts,
+                        _ => panic!("Match case has a non-block body"),
+                    };
+
+                    // The guard sees the bindings, so it is evaluated in the scope of the body.
+                    let matched = self.in_environment(sub_env, |interpreter| {
+                        if let Some(guard) = &case.guard {
+                            let guard_val = interpreter.evaluate(guard)?;
+                            if !interpreter.truthy(&guard_val, condition_location(guard))? {
+                                return Ok(false);
+                            }
+                        }
+
+                        for stmt in stmts {
+                            interpreter.execute(stmt)?;
+                        }
+                        Ok(true)
+                    })?;
+
+                    if matched {
+                        break;
+                    }
+                }
+
                 Ok(())
             },
 
-            Stmt::While(condition, body) => {
-                while is_truthy(&self.evaluate(condition).unwrap()) {
-                     match self.execute(body) {
-                         Ok(_) => {},
-                         Err(interrupt) => {
-                             match interrupt {
-                                 Interrupt::Break => { return Ok(()); },
-                                 Interrupt::Continue => { continue; }
-                                 _ => { return Err(interrupt); }
-                             }
-                         }
-                     }
+            Stmt::Switch(_, value, cases, else_block) => {
+                let value = self.evaluate(value)?;
+
+                // Case values are evaluated in order, until one equals the value.
+                for (values, block) in cases {
+                    for case_value in values {
+                        if is_equal(&value, &self.evaluate(case_value)?) {
+                            return self.execute(block);
+                        }
+                    }
+                }
+
+                match else_block {
+                    Some(block) => self.execute(block),
+                    None => Ok(()),
+                }
+            },
+
+            Stmt::Try(_, body, name, handler) => {
+                let error = match self.execute(body) {
+                    Err(Interrupt::Error(error)) if !self.is_stopping() => error,
+                    // `break`, `continue`, `return`, `exit` and a full output are not caught, let them through.
+                    result => return result,
+                };
+
+                let stmts = match &**handler {
+                    Stmt::Block(stmts) => stmts,
+                    _ => panic!("Try statement has a non-block handler"),
+                };
+
+                let mut sub_env = Environment::new(Some(self.environment.clone()));
+                sub_env.define(name.lexeme.clone(), error_value(&error));
+                self.execute_block(stmts, sub_env)
+            },
+
+            Stmt::With(keyword, resource, name, body) => {
+                let resource = self.evaluate(resource)?;
+                let exit = match resource_method(&resource, "_exit").or_else(|| resource_method(&resource, "close")) {
+                    Some(exit) => exit,
+                    None => return Err(Interrupt::Error(RuntimeError::new(
+                        ErrorLocation::Token(keyword.clone()),
+                        format!("Type '{}' cannot be used in 'with', it has no '_exit' or 'close' method.", resource.to_string()),
+                    ))),
+                };
+
+                let value = match resource_method(&resource, "_enter") {
+                    Some(enter) => self.call_value(enter, &vec![], ErrorLocation::Token(keyword.clone()))?,
+                    None => resource,
+                };
+
+                let stmts = match &**body {
+                    Stmt::Block(stmts) => stmts,
+                    _ => panic!("With statement has a non-block body"),
+                };
+
+                let mut sub_env = Environment::new(Some(self.environment.clone()));
+                if let Some(name) = name {
+                    sub_env.define(name.lexeme.clone(), value);
                 }
+
+                // Always release the resource, even if the block is interrupted by an error or `return`.
+                let result = self.execute_block(stmts, sub_env);
+                let exit_result = self.call_value(exit, &vec![], ErrorLocation::Token(keyword.clone()));
+
+                result?;
+                exit_result?;
                 Ok(())
             }
         }
@@ -870,7 +1688,285 @@ impl StmtVisitor for Interpreter {
 
 
 //--- Helpers.
-fn is_truthy(literal: &Literals) -> bool {
+/// Whether `value` matches `pattern`, collecting the values bound by the pattern. If `exact`, as in `match`,
+/// a dictionary pattern without `...` does not match dictionaries with other keys, which `let` and `for` ignore.
+fn match_pattern(pattern: &Pattern, value: &Literals, exact: bool, bindings: &mut Vec<(String, Literals)>) -> bool {
+    match (pattern, value) {
+        (Pattern::Wildcard, _) => true,
+        (Pattern::Binding(name), _) => {
+            bindings.push((name.lexeme.clone(), value.clone()));
+            true
+        },
+        (Pattern::Literal(literal), _) => is_equal(literal, value),
+        (Pattern::Tuple(patterns), Literals::Tuple(items)) => match_all(patterns, items, |rest| Literals::Tuple(Box::new(rest)), exact, bindings),
+        (Pattern::Array(patterns), Literals::Array(items)) => match_all(patterns, &items.borrow(), new_array, exact, bindings),
+        // Elements of a frozen array are frozen as well.
+        (Pattern::Array(patterns), Literals::Frozen(inner)) => match &**inner {
+            Literals::Array(items) => {
+                let items: Vec<Literals> = items.borrow().iter().cloned().map(Literals::freeze).collect();
+                match_all(patterns, &items, |rest| new_array(rest).freeze(), exact, bindings)
+            },
+            _ => false,
+        },
+        (Pattern::Dictionary(entries, rest), Literals::Dictionary(dict)) => match_dict(entries, rest, &dict.borrow(), false, exact, bindings),
+        (Pattern::Dictionary(entries, rest), Literals::Frozen(inner)) => match &**inner {
+            Literals::Dictionary(dict) => match_dict(entries, rest, &dict.borrow(), true, exact, bindings),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Match the entries of a dictionary, freezing the bound values if the dictionary is frozen.
+fn match_dict(entries: &[(DictKey, Pattern)], rest: &DictRest, dict: &IndexMap<DictKey, Literals>, frozen: bool, exact: bool, bindings: &mut Vec<(String, Literals)>) -> bool {
+    if let (DictRest::None, true) = (rest, exact) {
+        if dict.len() != entries.len() {
+            return false;
+        }
+    }
+
+    for (key, pattern) in entries {
+        let value = match dict.get(key) {
+            Some(value) if frozen => value.clone().freeze(),
+            Some(value) => value.clone(),
+            None => return false,
+        };
+
+        if !match_pattern(pattern, &value, exact, bindings) {
+            return false;
+        }
+    }
+
+    if let DictRest::Bound(name) = rest {
+        let others: IndexMap<DictKey, Literals> = dict.iter()
+            .filter(|(key, _)| entries.iter().all(|(named, _)| named != *key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let others = Literals::dictionary(others);
+        bindings.push((name.lexeme.clone(), if frozen { others.freeze() } else { others }));
+    }
+
+    true
+}
+
+/// Match the elements of a tuple or array. A rest pattern binds the remaining ones to a collection made by `rest`.
+fn match_all(patterns: &[Pattern], items: &[Literals], rest: fn(Vec<Literals>) -> Literals, exact: bool, bindings: &mut Vec<(String, Literals)>) -> bool {
+    let (patterns, rest_pattern) = split_rest(patterns);
+    let count_matches = match rest_pattern {
+        Some(_) => items.len() >= patterns.len(),
+        None => items.len() == patterns.len(),
+    };
+    if !count_matches || !patterns.iter().zip(items).all(|(pattern, item)| match_pattern(pattern, item, exact, bindings)) {
+        return false;
+    }
+
+    if let Some(Some(name)) = rest_pattern {
+        bindings.push((name.lexeme.clone(), rest(items[patterns.len()..].to_vec())));
+    }
+    true
+}
+
+/// The element patterns of a tuple or array pattern, and the name of its rest pattern, if it has one.
+fn split_rest(patterns: &[Pattern]) -> (&[Pattern], Option<&Option<Token>>) {
+    match patterns.split_last() {
+        Some((Pattern::Rest(name), elements)) => (elements, Some(name)),
+        _ => (patterns, None),
+    }
+}
+
+fn new_array(items: Vec<Literals>) -> Literals {
+    Literals::array(items)
+}
+
+/// The variables bound by the pattern of a `let` statement or `for` loop unpacking `value`.
+fn unpack(pattern: &Pattern, value: Literals, line: Option<usize>) -> Result<Vec<(String, Literals)>> {
+    if let Pattern::Binding(name) = pattern {
+        return Ok(vec![(name.lexeme.clone(), value)]);
+    }
+
+    let mut bindings = vec![];
+    if !match_pattern(pattern, &value, false, &mut bindings) {
+        return Err(Interrupt::Error(RuntimeError::new(pattern_location(pattern, line), mismatch(pattern, &value))));
+    }
+    Ok(bindings)
+}
+
+/// Where errors of a `let` statement or `for` loop are reported: the first variable of its pattern, or else its line.
+fn pattern_location(pattern: &Pattern, line: Option<usize>) -> ErrorLocation {
+    match pattern.bindings().first() {
+        Some(name) => ErrorLocation::Token((*name).clone()),
+        None => line.map_or(ErrorLocation::Unspecified, ErrorLocation::Line),
+    }
+}
+
+/// Why `value` does not match `pattern`, for errors of `let` statements and `for` loops unpacking it.
+fn mismatch(pattern: &Pattern, value: &Literals) -> String {
+    let (patterns, items) = match (pattern, value.clone().unfreeze()) {
+        (Pattern::Tuple(patterns), Literals::Tuple(items)) => (patterns, *items),
+        (Pattern::Array(patterns), Literals::Array(items)) => (patterns, items.borrow().clone()),
+        (Pattern::Literal(literal), _) => return format!("Expected {} but got {}.", stringify(literal.clone()), stringify(value.clone())),
+        (Pattern::Dictionary(entries, _), Literals::Dictionary(dict)) => return dict_mismatch(entries, &dict.borrow(), value),
+        (Pattern::Tuple(_), _) => return format!("Cannot unpack '{}' as a tuple.", value.to_string()),
+        (Pattern::Array(_), _) => return format!("Cannot unpack '{}' as an array.", value.to_string()),
+        (Pattern::Dictionary(_, _), _) => return format!("Cannot unpack '{}' as a dictionary.", value.to_string()),
+        _ => return format!("Cannot unpack '{}'.", value.to_string()),
+    };
+
+    match split_rest(patterns) {
+        (elements, Some(_)) if items.len() < elements.len() => {
+            format!("Expected at least {} values to unpack but got {}.", elements.len(), items.len())
+        },
+        (elements, None) if items.len() != elements.len() => {
+            format!("Expected {} values to unpack but got {}.", elements.len(), items.len())
+        },
+        // An element does not match its pattern.
+        (elements, _) => elements.iter().zip(items.iter())
+            .find(|(pattern, item)| !match_pattern(pattern, item, false, &mut vec![]))
+            .map(|(pattern, item)| mismatch(pattern, item))
+            .unwrap_or_else(|| format!("Cannot unpack '{}'.", value.to_string())),
+    }
+}
+
+/// Why a dictionary does not match a dictionary pattern: a missing key or a value not matching its pattern.
+fn dict_mismatch(entries: &[(DictKey, Pattern)], dict: &IndexMap<DictKey, Literals>, value: &Literals) -> String {
+    for (key, pattern) in entries {
+        match dict.get(key) {
+            Some(item) if !match_pattern(pattern, item, false, &mut vec![]) => return mismatch(pattern, item),
+            Some(_) => {},
+            None => return format!("Key {} not found.", key.stringify()),
+        }
+    }
+
+    format!("Cannot unpack '{}'.", value.to_string())
+}
+
+/// The file named by the string token of an import statement.
+fn import_path(path: &Token) -> String {
+    match &path.literal {
+        Some(Literals::String(file)) => file.to_string(),
+        _ => panic!("Import path is not a string."),
+    }
+}
+
+/// The operator a compound assignment such as `+=` or `++` applies, or `None` for `=`.
+fn compound_operator(operator: &Token) -> Option<TokenType> {
+    match operator.token_type {
+        TokenType::EQUAL => None,
+        TokenType::PLUS_EQUAL | TokenType::PLUS_PLUS => Some(TokenType::PLUS),
+        TokenType::MINUS_EQUAL | TokenType::MINUS_MINUS => Some(TokenType::MINUS),
+        TokenType::STAR_EQUAL => Some(TokenType::STAR),
+        TokenType::SLASH_EQUAL => Some(TokenType::SLASH),
+        _ => unreachable!("The parser only wraps assignment operators in assignments."),
+    }
+}
+
+/// Borrow a collection to change an element, with errors located at `token`, see `borrow_to_change`.
+fn change_at<'a, T>(collection: &'a RefCell<T>, token: &Token) -> Result<RefMut<'a, T>> {
+    borrow_to_change(collection).map_err(|error| Interrupt::Error(error.or_at(ErrorLocation::Token(token.clone()))))
+}
+
+/// The value a `catch` binds: a dictionary with the error's message and line, or nil if the line is unknown.
+fn error_value(error: &RuntimeError) -> Literals {
+    let line = match error.location.line() {
+        Some(line) => Literals::Int(line as i64),
+        None => Literals::Nil,
+    };
+
+    let mut dict = IndexMap::new();
+    dict.insert(DictKey::StringKey("message".to_string()), Literals::String(error.message.as_str().into()));
+    dict.insert(DictKey::StringKey("line".to_string()), line);
+    Literals::dictionary(dict)
+}
+
+/// The method `name` of a resource used in a `with` statement, if it has one.
+fn resource_method(resource: &Literals, name: &str) -> Option<Literals> {
+    match resource.as_object().get_property(name) {
+        Ok(method @ Literals::Function(_)) => Some(method),
+        _ => None,
+    }
+}
+
+/// Whether a string or collection is empty, other values never are.
+fn is_empty(literal: &Literals) -> bool {
+    match literal {
+        Literals::String(s) => s.is_empty(),
+        Literals::Array(array) => array.borrow().is_empty(),
+        Literals::Bytes(bytes) => bytes.is_empty(),
+        Literals::Dictionary(dict) => dict.borrow().is_empty(),
+        Literals::Deque(deque) => deque.borrow().is_empty(),
+        Literals::Frozen(value) => is_empty(value),
+        Literals::Heap(heap) => heap.borrow().len() == 0,
+        Literals::Tuple(tuple) => tuple.is_empty(),
+        _ => false,
+    }
+}
+
+/// Where to report errors deciding whether a condition is true.
+fn condition_location(condition: &Expr) -> ErrorLocation {
+    condition.line().map_or(ErrorLocation::Unspecified, ErrorLocation::Line)
+}
+
+/// Whether a loop with the label stops after its body was interrupted: `Ok(true)` for a `break` of
+/// this loop, `Ok(false)` for a `continue`, or the interrupt for an enclosing loop or function.
+fn loop_interrupt(interrupt: Interrupt, label: &Option<Token>) -> Result<bool> {
+    let is_this_loop = |target: &Option<String>| match target {
+        None => true,
+        Some(target) => label.as_ref().is_some_and(|label| label.lexeme == *target),
+    };
+
+    match interrupt {
+        Interrupt::Break(target) if is_this_loop(&target) => Ok(true),
+        Interrupt::Continue(target) if is_this_loop(&target) => Ok(false),
+        interrupt => Err(interrupt),
+    }
+}
+
+/// Whether a value is truthy by default, where only `nil` and `false` are false.
+/// Use `Interpreter::truthy` to respect `_bool` methods and the interpreter options.
+/// The operands of an arithmetic operator, integers if both are, otherwise numbers.
+enum Operands {
+    Ints(i64, i64),
+    Numbers(f64, f64),
+}
+
+impl Operands {
+    fn to_numbers(&self) -> (f64, f64) {
+        match *self {
+            Operands::Ints(l, r) => (l as f64, r as f64),
+            Operands::Numbers(l, r) => (l, r),
+        }
+    }
+}
+
+/// Apply `int_op` to integers, which fails on overflow or division by zero, or `op` to numbers.
+fn int_arithmetic(operator: &Token, operands: Operands, int_op: fn(i64, i64) -> Option<i64>, op: fn(f64, f64) -> f64) -> Result<Literals> {
+    match operands {
+        Operands::Ints(l, r) => int_op(l, r).map(Literals::Int).ok_or_else(|| {
+            // Only dividing by zero fails for a zero right operand.
+            let message = if r == 0 {
+                "Division by zero.".to_string()
+            } else {
+                format!("Integer overflow in '{}'.", operator.lexeme)
+            };
+            Interrupt::Error(RuntimeError::new(ErrorLocation::Token(operator.clone()), message))
+        }),
+        Operands::Numbers(l, r) => Ok(Literals::Number(op(l, r))),
+    }
+}
+
+/// Integer division rounding down, e.g. `-7 /< 2` is -4.
+fn floor_div(l: i64, r: i64) -> Option<i64> {
+    let quotient = l.checked_div(r)?;
+    Some(if l % r != 0 && (l < 0) != (r < 0) { quotient - 1 } else { quotient })
+}
+
+/// Integer division rounding up, e.g. `7 /> 2` is 4.
+fn ceil_div(l: i64, r: i64) -> Option<i64> {
+    let quotient = l.checked_div(r)?;
+    Some(if l % r != 0 && (l < 0) == (r < 0) { quotient + 1 } else { quotient })
+}
+
+pub fn is_truthy(literal: &Literals) -> bool {
     match literal {
         Literals::Nil => false,
         Literals::Boolean(b) => *b,
@@ -878,8 +1974,14 @@ fn is_truthy(literal: &Literals) -> bool {
     }
 }
 
-fn is_equal(literal_a: &Literals, literal_b: &Literals) -> bool {
+pub fn is_equal(literal_a: &Literals, literal_b: &Literals) -> bool {
+    // A frozen view equals the value it reads from.
+    if let Literals::Frozen(b) = literal_b {
+        return is_equal(literal_a, b);
+    }
+
     match literal_a {
+        Literals::Frozen(a) => is_equal(a, literal_b),
         Literals::Array(a) => { match literal_b {
             Literals::Array(other) => {
                 return if a.borrow().len() != other.borrow().len() {
@@ -911,6 +2013,17 @@ fn is_equal(literal_a: &Literals, literal_b: &Literals) -> bool {
             },
             _ => false,
         }},
+        Literals::Bytes(b) => { match literal_b {
+            Literals::Bytes(other) => b == other,
+            _ => false,
+        }},
+        Literals::Deque(d) => { match literal_b {
+            Literals::Deque(other) => {
+                let (d, other) = (d.borrow(), other.borrow());
+                d.len() == other.len() && d.iter().zip(other.iter()).all(|(a, b)| is_equal(a, b))
+            },
+            _ => false,
+        }},
         Literals::String(s) => { match literal_b {
             Literals::String(other) => s == other,
             _ => false,
@@ -930,6 +2043,12 @@ fn is_equal(literal_a: &Literals, literal_b: &Literals) -> bool {
         }},
         Literals::Number(n) => { match literal_b {
             Literals::Number(other) => n == other,
+            Literals::Int(other) => *n == *other as f64,
+            _ => false,
+        }},
+        Literals::Int(n) => { match literal_b {
+            Literals::Int(other) => n == other,
+            Literals::Number(other) => *n as f64 == *other,
             _ => false,
         }},
         Literals::Boolean(b) => { match literal_b {
@@ -940,17 +2059,58 @@ fn is_equal(literal_a: &Literals, literal_b: &Literals) -> bool {
             Literals::Nil => true,
             _ => false,
         }},
-        _ => panic!("Comparison not supported.")
+        // Heaps, functions, classes and instances are only equal to themselves.
+        Literals::Heap(h) => { match literal_b {
+            Literals::Heap(other) => Rc::ptr_eq(h, other),
+            _ => false,
+        }},
+        Literals::Function(f) => { match literal_b {
+            Literals::Function(other) => f.identity() == other.identity(),
+            _ => false,
+        }},
+        Literals::Class(c) => { match literal_b {
+            Literals::Class(other) => Rc::ptr_eq(c, other),
+            _ => false,
+        }},
+        Literals::Instance(i) => { match literal_b {
+            Literals::Instance(other) => Rc::ptr_eq(i, other),
+            _ => false,
+        }},
+        Literals::Module(m) => { match literal_b {
+            Literals::Module(other) => Rc::ptr_eq(m, other),
+            _ => false,
+        }},
+        Literals::Object(o) => { match literal_b {
+            Literals::Object(other) => Rc::as_ptr(o) as *const () == Rc::as_ptr(other) as *const (),
+            _ => false,
+        }},
+        Literals::Native(n) => { match literal_b {
+            Literals::Native(other) => Rc::as_ptr(n) as *const () == Rc::as_ptr(other) as *const (),
+            _ => false,
+        }},
+        Literals::WeakRef(w) => { match literal_b {
+            Literals::WeakRef(other) => w.ptr_eq(other),
+            _ => false,
+        }},
     }
 }
 
-fn stringify(literal: Literals) -> String {
+fn stringify_items<'a>(items: impl Iterator<Item = &'a Literals>, float_precision: Option<usize>) -> String {
+    items.map(|item| stringify_with(item.clone(), float_precision)).collect::<Vec<_>>().join(", ")
+}
+
+pub fn stringify(literal: Literals) -> String {
+    stringify_with(literal, None)
+}
+
+/// Like `stringify`, with numbers shown with `float_precision` digits after the decimal point if it is set.
+pub fn stringify_with(literal: Literals, float_precision: Option<usize>) -> String {
     match literal {
         Literals::Array(a) => {
             let mut res = String::from("[");
             let arr = a.borrow();
             for item in arr.iter() {
-                res.push_str(&format!("{}, ", stringify(item.clone())));
+                res.push_str(&format!("{}, ", stringify_with(item.clone(), float_precision)));
             }
             if res.len() > 1 {
                 res.truncate(res.len() - 2);
@@ -961,7 +2121,7 @@ fn stringify(literal: Literals) -> String {
         Literals::Dictionary(h) => {
             let mut res = String::from("{");
             for (key, val) in h.borrow().iter() {
-                res.push_str(&format!("{}: {}, ", key.stringify(), stringify(val.clone())));
+                res.push_str(&format!("{}: {}, ", key.stringify(), stringify_with(val.clone(), float_precision)));
             }
             if res.len() > 1 {
                 res.truncate(res.len() - 2);
@@ -969,12 +2129,16 @@ fn stringify(literal: Literals) -> String {
             res.push('}');
             res
         }
+        Literals::Bytes(b) => format!("bytes([{}])", b.iter().map(u8::to_string).collect::<Vec<_>>().join(", ")),
+        Literals::Deque(d) => format!("deque([{}])", stringify_items(d.borrow().iter(), float_precision)),
+        Literals::Frozen(value) => stringify_with(*value, float_precision),
+        Literals::Heap(h) => format!("heap([{}])", stringify_items(h.borrow().items().iter(), float_precision)),
         Literals::String(s) => format!("\"{}\"", s),
         Literals::Tuple(a) => {
             let mut res = String::from("(");
             let arr = *a;
             for item in arr.iter() {
-                res.push_str(&format!("{}, ", stringify(item.clone())));
+                res.push_str(&format!("{}, ", stringify_with(item.clone(), float_precision)));
             }
             if res.len() > 1 {
                 res.truncate(res.len() - 2);
@@ -982,21 +2146,27 @@ fn stringify(literal: Literals) -> String {
             res.push(')');
             res
         },
-        Literals::Number(n) => n.to_string(),
+        Literals::Number(n) => match float_precision {
+            Some(precision) => format!("{:.*}", precision, n),
+            None => n.to_string(),
+        },
+        Literals::Int(n) => n.to_string(),
         Literals::Boolean(b) => b.to_string(),
         Literals::Nil => "nil".to_string(),
-        Literals::Function(_function) => {
-            let mut res = String::from("<fun (");
-            // TODO: hmm
-            // for param in function.params.iter() {
-            //     res.push_str(&param.lexeme);
-            //     res.push_str(", ");
-            // }
-            if res.len() > 9 { res.truncate(res.len() - 2); }
-            res.push_str(")>");
-
-            res
+        Literals::Function(function) => {
+            match function.params() {
+                Some(params) => format!("<fun ({})>", params.join(", ")),
+                None => "<fun (...)>".to_string(),
+            }
+        },
+        Literals::Class(class) => format!("<class {}>", class.name),
+        Literals::Instance(instance) => format!("<{} instance>", instance.borrow().class.name),
+        Literals::Module(module) => format!("<module \"{}\">", module.path),
+        Literals::Object(object) => format!("<{} object>", object.class_name()),
+        Literals::Native(_) => "<native>".to_string(),
+        Literals::WeakRef(weak) => match weak.upgrade() {
+            Some(instance) => format!("<weak_ref {} instance>", instance.borrow().class.name),
+            None => "<weak_ref dropped>".to_string(),
         },
-        _ => panic!("Not implemented.")
     }
 }
@@ -0,0 +1,135 @@
+use std::rc::Rc;
+
+use crate::dove_callable::Arity;
+use crate::dove_output::DoveOutput;
+use crate::error_handler::{ErrorLocation, RuntimeError};
+use crate::interpreter::Interpreter;
+use crate::marshal::NativeResult;
+use crate::options::InterpreterOptions;
+use crate::parser::Parser;
+use crate::resolver::Resolver;
+use crate::scanner::Scanner;
+use crate::token::Literals;
+
+/// A Dove program with its own globals, imports, options and output.
+///
+/// All interpreter state lives in the isolate, so a host can run many of them in one process,
+/// e.g. one per tenant script, without any of them seeing another's variables or sharing
+/// its limits. Isolates are not `Send`, create each one on the thread that runs it.
+///
+/// Only the registry of builtins is per thread rather than per isolate, which is possible since
+/// builtins hold no state.
+pub struct Isolate {
+    interpreter: Interpreter,
+    output: Rc<dyn DoveOutput>,
+}
+
+impl Isolate {
+    pub fn new(output: Rc<dyn DoveOutput>, options: InterpreterOptions) -> Isolate {
+        Isolate {
+            interpreter: Interpreter::new(Rc::clone(&output), options),
+            output,
+        }
+    }
+
+    /// Run `source` in the global environment of the isolate and return the value of its last expression.
+    /// Syntax errors are reported to the output of the isolate, runtime errors are returned.
+    pub fn run(&mut self, source: &str) -> Result<Literals, RuntimeError> {
+        let syntax_error = || RuntimeError::new(
+            ErrorLocation::Unspecified,
+            "Cannot run source with errors.".to_string(),
+        );
+
+        let tokens = match Scanner::new(source.chars().collect(), Rc::clone(&self.output)).try_scan_tokens() {
+            Some(tokens) => tokens,
+            None => return Err(syntax_error()),
+        };
+
+        let mut parser = Parser::new(tokens, Rc::clone(&self.output));
+        let statements = parser.program();
+        if parser.had_error() {
+            return Err(syntax_error());
+        }
+
+        let mut resolver = Resolver::new(&mut self.interpreter, Rc::clone(&self.output));
+        resolver.resolve(&statements);
+        if resolver.had_error() {
+            return Err(syntax_error());
+        }
+
+        self.interpreter.error_handler.reset();
+        self.interpreter.execute_global(&statements)
+    }
+
+    /// The value of a global variable, if it is defined.
+    pub fn global(&self, name: &str) -> Option<Literals> {
+        self.interpreter.global(name)
+    }
+
+    /// Call a function or class of the isolate, e.g. a handler its script registered for an event.
+    pub fn call_function(&mut self, callee: Literals, args: &[Literals]) -> Result<Literals, RuntimeError> {
+        self.interpreter.call_function(callee, args)
+    }
+
+    /// Define a global variable, or replace an existing one, e.g. to pass input to a script.
+    pub fn set_global(&mut self, name: &str, value: Literals) {
+        self.interpreter.globals.borrow_mut().define(name.to_string(), value);
+    }
+
+    /// Define a global function implemented in Rust, see `Interpreter::register_native`.
+    pub fn register_native<R: NativeResult>(&mut self, name: &str, arity: impl Into<Arity>, function: impl Fn(&[Literals]) -> R + 'static) {
+        self.interpreter.register_native(name, arity, function);
+    }
+
+    pub fn interpreter(&mut self) -> &mut Interpreter {
+        &mut self.interpreter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::builtins::BuiltinPolicy;
+
+    struct NoOutput;
+
+    impl DoveOutput for NoOutput {
+        fn print(&self, _message: String) {}
+        fn warning(&self, _message: String) {}
+        fn error(&self, _message: String) {}
+    }
+
+    fn isolate(options: InterpreterOptions) -> Isolate {
+        Isolate::new(Rc::new(NoOutput), options)
+    }
+
+    #[test]
+    fn globals_are_not_shared() {
+        let mut a = isolate(InterpreterOptions::default());
+        let b = isolate(InterpreterOptions::default());
+
+        a.run("let secret = 42").unwrap();
+        assert!(matches!(a.global("secret"), Some(Literals::Int(42))));
+        assert!(b.global("secret").is_none());
+    }
+
+    #[test]
+    fn timeouts_are_not_shared() {
+        let mut a = isolate(InterpreterOptions::default().evaluation_timeout(Some(Duration::from_millis(0))));
+        let mut b = isolate(InterpreterOptions::default());
+
+        assert!(a.run("while true {}").unwrap_err().message.contains("timed out"));
+        assert!(matches!(b.run("let i = 0\nwhile i < 1000 { i += 1 }\ni"), Ok(Literals::Int(1000))));
+    }
+
+    #[test]
+    fn builtin_policies_are_not_shared() {
+        let mut a = isolate(InterpreterOptions::default().builtins(BuiltinPolicy::deny_all()));
+        let mut b = isolate(InterpreterOptions::default());
+
+        assert!(a.run("math.sqrt(4)").is_err());
+        assert!(matches!(b.run("math.sqrt(4)"), Ok(Literals::Number(n)) if n == 2.0));
+    }
+}
@@ -0,0 +1,119 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use indexmap::IndexMap;
+
+use crate::builtins::iterable_items;
+use crate::error_handler::{ErrorLocation, RuntimeError};
+use crate::interpreter::Interpreter;
+use crate::token::{DictKey, Literals};
+
+/// The items a `for` loop goes through, one at a time.
+///
+/// Arrays, tuples, ranges, strings (their characters), dictionaries (tuples of key and value),
+/// deques and bytes can be iterated over, as can instances of classes that follow the protocol:
+/// either a `next()` method returning the next item, or `nil` after the last one, or an `iter()`
+/// method returning such an instance or any other iterable value.
+pub trait DoveIterator {
+    /// The next item, or `None` after the last one.
+    fn next(&mut self, interpreter: &mut Interpreter) -> Result<Option<Literals>, RuntimeError>;
+}
+
+/// An iterator over the value, or an error at `location` if it cannot be iterated over.
+pub fn iterator(interpreter: &mut Interpreter, value: Literals, location: ErrorLocation) -> Result<Box<dyn DoveIterator>, RuntimeError> {
+    let iterator: Box<dyn DoveIterator> = match value {
+        Literals::Array(array) => Box::new(ArrayIterator { array, index: 0, frozen: false }),
+        // Items of a frozen array or dictionary are frozen as well.
+        Literals::Frozen(value) => match *value {
+            Literals::Array(array) => Box::new(ArrayIterator { array, index: 0, frozen: true }),
+            Literals::Dictionary(dict) => Box::new(dict_entries(&dict, true)),
+            value => return iterator(interpreter, value, location),
+        },
+        Literals::Dictionary(dict) => Box::new(dict_entries(&dict, false)),
+        Literals::Instance(_) => instance_iterator(interpreter, value, location)?,
+        value => match iterable_items(&value) {
+            Some(items) => Box::new(ItemsIterator(items.into_iter())),
+            None => return Err(RuntimeError::new(
+                location,
+                format!("Cannot iterate over type '{}'.", value.to_string()),
+            )),
+        },
+    };
+
+    Ok(iterator)
+}
+
+/// Follow the iteration protocol of an instance, see `DoveIterator`.
+fn instance_iterator(interpreter: &mut Interpreter, instance: Literals, location: ErrorLocation) -> Result<Box<dyn DoveIterator>, RuntimeError> {
+    // `iter` may return the instance itself.
+    let items = match instance.as_object().get_property("iter") {
+        Ok(iter) => interpreter.call_value(iter, &vec![], location.clone())?,
+        Err(_) => instance,
+    };
+
+    match &items {
+        Literals::Instance(_) => match items.as_object().get_property("next") {
+            Ok(next) => Ok(Box::new(InstanceIterator { next, location })),
+            Err(_) => Err(RuntimeError::new(
+                location,
+                "Cannot iterate over an instance without an 'iter' or 'next' method.".to_string(),
+            )),
+        },
+        _ => iterator(interpreter, items, location),
+    }
+}
+
+/// Tuples of the key and value of each entry, read when the loop starts, so the loop may change the dictionary.
+fn dict_entries(dict: &RefCell<IndexMap<DictKey, Literals>>, frozen: bool) -> ItemsIterator {
+    let entries: Vec<Literals> = dict.borrow().iter()
+        .map(|(key, value)| {
+            let value = if frozen { value.clone().freeze() } else { value.clone() };
+            Literals::Tuple(Box::new(vec![key.to_literal(), value]))
+        })
+        .collect();
+
+    ItemsIterator(entries.into_iter())
+}
+
+/// Reads the items by index, so items pushed by the loop are visited as well.
+struct ArrayIterator {
+    array: Rc<RefCell<Vec<Literals>>>,
+    index: usize,
+    frozen: bool,
+}
+
+impl DoveIterator for ArrayIterator {
+    fn next(&mut self, _interpreter: &mut Interpreter) -> Result<Option<Literals>, RuntimeError> {
+        let item = self.array.borrow().get(self.index).cloned();
+        self.index += 1;
+
+        Ok(match item {
+            Some(item) if self.frozen => Some(item.freeze()),
+            item => item,
+        })
+    }
+}
+
+/// Items collected when the loop starts.
+struct ItemsIterator(std::vec::IntoIter<Literals>);
+
+impl DoveIterator for ItemsIterator {
+    fn next(&mut self, _interpreter: &mut Interpreter) -> Result<Option<Literals>, RuntimeError> {
+        Ok(self.0.next())
+    }
+}
+
+/// Calls the `next` method of an instance until it returns `nil`.
+struct InstanceIterator {
+    next: Literals,
+    location: ErrorLocation,
+}
+
+impl DoveIterator for InstanceIterator {
+    fn next(&mut self, interpreter: &mut Interpreter) -> Result<Option<Literals>, RuntimeError> {
+        match interpreter.call_value(self.next.clone(), &vec![], self.location.clone())? {
+            Literals::Nil => Ok(None),
+            item => Ok(Some(item)),
+        }
+    }
+}
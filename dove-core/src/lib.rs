@@ -1,21 +1,62 @@
+//! The Dove language: scanner, parser, resolver and tree-walking interpreter.
+//!
+//! To embed Dove in a Rust application, create an `Isolate` with a `DoveOutput` receiving what
+//! scripts print and report, then `run` scripts in it. Pass values in with `set_global`, read them
+//! with `global`, call script functions with `call_function`, and give scripts Rust functions with
+//! `register_native`, or `marshal::native` for functions with typed arguments. Values are
+//! `Literals`, converted from and to Rust types with `ToDove` and `FromDove`.
+//! The items re-exported here are the stable embedding API, the modules hold the internals.
+
+// Runtime errors hold the token they happened at, which makes results holding them large.
+// They are rare, and boxing them would change the public `RuntimeError`, so results are left as they are.
+#![allow(clippy::result_large_err)]
+
 pub mod dove_output;
+pub mod dove_input;
+pub mod buffered_output;
 pub mod constants;
 pub mod scanner;
 pub mod token;
 pub mod ast;
 pub mod dove_callable;
-pub mod importer;
 pub mod interpreter;
+pub mod isolate;
+pub mod iteration;
 pub mod environment;
 pub mod parser;
 pub mod error_handler;
 pub mod resolver;
 pub mod dove_class;
 pub mod data_types;
+pub mod builtins;
+pub mod hooks;
+pub mod reflection;
+pub mod file_provider;
+pub mod version;
+pub mod options;
+pub mod repl;
+pub mod symbols;
+pub mod marshal;
+pub mod stats;
+pub mod suggestions;
 
 pub use scanner::Scanner;
-pub use importer::Importer;
 pub use interpreter::Interpreter;
+pub use isolate::Isolate;
 pub use parser::Parser;
 pub use resolver::Resolver;
 pub use dove_output::DoveOutput;
+pub use dove_input::DoveInput;
+pub use buffered_output::BufferedOutput;
+pub use hooks::InterpreterHooks;
+pub use builtins::BuiltinPolicy;
+pub use file_provider::FileProvider;
+pub use options::InterpreterOptions;
+pub use repl::{ReplSession, ReplStatus};
+pub use marshal::{ToDove, FromDove};
+pub use token::Literals;
+pub use dove_callable::{Arity, BuiltinFunction, DoveCallable};
+pub use error_handler::RuntimeError;
+
+#[cfg(feature = "derive")]
+pub use dove_derive::{DoveClass, dove_methods};
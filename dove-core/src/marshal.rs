@@ -0,0 +1,330 @@
+use std::any::{self, Any};
+use std::rc::Rc;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use indexmap::IndexMap;
+
+use crate::dove_callable::{BuiltinFunction, DoveCallable};
+use crate::error_handler::{ErrorLocation, RuntimeError};
+use crate::token::{DictKey, Literals};
+
+/// Rust values that can be passed to Dove scripts.
+pub trait ToDove {
+    fn to_dove(self) -> Literals;
+}
+
+/// Rust values that can be read from Dove values, e.g. the arguments of a native function.
+pub trait FromDove: Sized {
+    /// The value as `Self`, or `None` if it has a different type.
+    fn from_dove(value: Literals) -> Option<Self>;
+
+    /// The Dove type expected by `from_dove`, used in error messages, e.g. `Array of Number`.
+    fn expected() -> String;
+}
+
+impl ToDove for Literals {
+    fn to_dove(self) -> Literals {
+        self
+    }
+}
+
+impl FromDove for Literals {
+    fn from_dove(value: Literals) -> Option<Self> {
+        Some(value)
+    }
+
+    fn expected() -> String {
+        "any value".to_string()
+    }
+}
+
+impl ToDove for () {
+    fn to_dove(self) -> Literals {
+        Literals::Nil
+    }
+}
+
+impl ToDove for bool {
+    fn to_dove(self) -> Literals {
+        Literals::Boolean(self)
+    }
+}
+
+impl FromDove for bool {
+    fn from_dove(value: Literals) -> Option<Self> {
+        value.unwrap_boolean().ok()
+    }
+
+    fn expected() -> String {
+        "Boolean".to_string()
+    }
+}
+
+impl ToDove for String {
+    fn to_dove(self) -> Literals {
+        Literals::String(self.into())
+    }
+}
+
+impl ToDove for &str {
+    fn to_dove(self) -> Literals {
+        Literals::String(self.into())
+    }
+}
+
+impl FromDove for String {
+    fn from_dove(value: Literals) -> Option<Self> {
+        value.unwrap_string().ok()
+    }
+
+    fn expected() -> String {
+        "String".to_string()
+    }
+}
+
+macro_rules! number_conversions {
+    ( $variant:ident as $target:ty: $( $number:ty ),* ) => {
+        $(
+            impl ToDove for $number {
+                fn to_dove(self) -> Literals {
+                    Literals::$variant(self as $target)
+                }
+            }
+        )*
+    }
+}
+
+number_conversions!(Number as f64: f64, f32);
+number_conversions!(Int as i64: i64, i32, usize, u32);
+
+impl FromDove for f64 {
+    fn from_dove(value: Literals) -> Option<Self> {
+        value.unwrap_number().ok()
+    }
+
+    fn expected() -> String {
+        "Number".to_string()
+    }
+}
+
+impl FromDove for i64 {
+    fn from_dove(value: Literals) -> Option<Self> {
+        value.unwrap_int()
+    }
+
+    fn expected() -> String {
+        "integer".to_string()
+    }
+}
+
+impl FromDove for usize {
+    fn from_dove(value: Literals) -> Option<Self> {
+        value.unwrap_usize().ok()
+    }
+
+    fn expected() -> String {
+        "non-negative integer".to_string()
+    }
+}
+
+/// Integers out of the range of 32-bit integers are rejected instead of truncated.
+impl FromDove for i32 {
+    fn from_dove(value: Literals) -> Option<Self> {
+        value.unwrap_int().and_then(|n| i32::try_from(n).ok())
+    }
+
+    fn expected() -> String {
+        "32-bit integer".to_string()
+    }
+}
+
+impl FromDove for u32 {
+    fn from_dove(value: Literals) -> Option<Self> {
+        value.unwrap_int().and_then(|n| u32::try_from(n).ok())
+    }
+
+    fn expected() -> String {
+        "non-negative 32-bit integer".to_string()
+    }
+}
+
+/// Finite numbers too large for an `f32` are rejected instead of becoming infinite.
+impl FromDove for f32 {
+    fn from_dove(value: Literals) -> Option<Self> {
+        match value.unwrap_number() {
+            Ok(n) if n.is_finite() && n.abs() > f32::MAX as f64 => None,
+            Ok(n) => Some(n as f32),
+            Err(_) => None,
+        }
+    }
+
+    fn expected() -> String {
+        "32-bit number".to_string()
+    }
+}
+
+/// `None` is passed as `nil`.
+impl<T: ToDove> ToDove for Option<T> {
+    fn to_dove(self) -> Literals {
+        match self {
+            Some(value) => value.to_dove(),
+            None => Literals::Nil,
+        }
+    }
+}
+
+impl<T: FromDove> FromDove for Option<T> {
+    fn from_dove(value: Literals) -> Option<Self> {
+        match value {
+            Literals::Nil => Some(None),
+            value => T::from_dove(value).map(Some),
+        }
+    }
+
+    fn expected() -> String {
+        format!("{} or Nil", T::expected())
+    }
+}
+
+impl<T: ToDove> ToDove for Vec<T> {
+    fn to_dove(self) -> Literals {
+        let items = self.into_iter().map(ToDove::to_dove).collect();
+        Literals::array(items)
+    }
+}
+
+/// Read from arrays and tuples, frozen or not.
+impl<T: FromDove> FromDove for Vec<T> {
+    fn from_dove(value: Literals) -> Option<Self> {
+        let items = match value.unfreeze() {
+            Literals::Array(array) => array.borrow().clone(),
+            Literals::Tuple(tuple) => *tuple,
+            _ => return None,
+        };
+        items.into_iter().map(T::from_dove).collect()
+    }
+
+    fn expected() -> String {
+        format!("Array of {}", T::expected())
+    }
+}
+
+impl<T: ToDove> ToDove for HashMap<String, T> {
+    fn to_dove(self) -> Literals {
+        let entries = self.into_iter()
+            .map(|(key, value)| (DictKey::StringKey(key), value.to_dove()))
+            .collect::<IndexMap<_, _>>();
+        Literals::dictionary(entries)
+    }
+}
+
+/// Read from dictionaries with only string keys, frozen or not.
+impl<T: FromDove> FromDove for HashMap<String, T> {
+    fn from_dove(value: Literals) -> Option<Self> {
+        let dict = match value.unfreeze() {
+            Literals::Dictionary(dict) => dict,
+            _ => return None,
+        };
+
+        let entries = dict.borrow();
+        entries.iter()
+            .map(|(key, value)| match key {
+                DictKey::StringKey(key) => T::from_dove(value.clone()).map(|value| (key.clone(), value)),
+                DictKey::NumberKey(_) => None,
+            })
+            .collect()
+    }
+
+    fn expected() -> String {
+        format!("Dictionary of {}", T::expected())
+    }
+}
+
+/// Opaque handles, see `Literals::Native`.
+impl<T: Any> ToDove for Rc<T> {
+    fn to_dove(self) -> Literals {
+        Literals::Native(self)
+    }
+}
+
+impl<T: Any> FromDove for Rc<T> {
+    fn from_dove(value: Literals) -> Option<Self> {
+        value.downcast_native()
+    }
+
+    fn expected() -> String {
+        format!("Native {}", any::type_name::<T>())
+    }
+}
+
+/// Values returned by native functions, either a value or a runtime error.
+pub trait NativeResult {
+    fn into_result(self) -> Result<Literals, RuntimeError>;
+}
+
+impl<T: ToDove> NativeResult for T {
+    fn into_result(self) -> Result<Literals, RuntimeError> {
+        Ok(self.to_dove())
+    }
+}
+
+/// An `Err` becomes a runtime error with its message.
+impl<T: ToDove> NativeResult for Result<T, String> {
+    fn into_result(self) -> Result<Literals, RuntimeError> {
+        self.map(ToDove::to_dove)
+            .map_err(|message| RuntimeError::new(ErrorLocation::Unspecified, message))
+    }
+}
+
+/// Rust functions whose arguments and return value can be converted, see `native`.
+pub trait NativeFunction<Args> {
+    fn arity(&self) -> usize;
+    fn call_native(&self, args: &[Literals]) -> Result<Literals, RuntimeError>;
+}
+
+/// Convert the argument at `index`, with a runtime error naming it if it has the wrong type.
+fn argument<T: FromDove>(args: &[Literals], index: usize) -> Result<T, RuntimeError> {
+    T::from_dove(args[index].clone()).ok_or_else(|| RuntimeError::new(
+        ErrorLocation::Unspecified,
+        format!("Expected {} for argument {} but got '{}'.", T::expected(), index + 1, args[index].to_string()),
+    ))
+}
+
+macro_rules! native_functions {
+    ( $( ( $arity:expr; $( $arg:ident $index:tt ),* ) )* ) => {
+        $(
+            impl<F, R, $( $arg ),*> NativeFunction<( $( $arg, )* )> for F
+            where
+                F: Fn( $( $arg ),* ) -> R,
+                R: NativeResult,
+                $( $arg: FromDove, )*
+            {
+                fn arity(&self) -> usize {
+                    $arity
+                }
+
+                #[allow(unused_variables)]
+                fn call_native(&self, args: &[Literals]) -> Result<Literals, RuntimeError> {
+                    self( $( argument::<$arg>(args, $index)? ),* ).into_result()
+                }
+            }
+        )*
+    }
+}
+
+native_functions! {
+    (0;)
+    (1; A 0)
+    (2; A 0, B 1)
+    (3; A 0, B 1, C 2)
+    (4; A 0, B 1, C 2, D 3)
+    (5; A 0, B 1, C 2, D 3, E 4)
+    (6; A 0, B 1, C 2, D 3, E 4, G 5)
+}
+
+/// Wrap a Rust function, e.g. `fn(f64, String) -> Vec<f64>`, as a Dove function.
+/// The arity comes from the signature, and arguments of the wrong type raise a runtime error.
+pub fn native<Args, F: NativeFunction<Args>>(function: F) -> impl DoveCallable {
+    BuiltinFunction::new(function.arity(), move |_, args| function.call_native(args))
+}
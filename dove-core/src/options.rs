@@ -0,0 +1,133 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::builtins::BuiltinPolicy;
+use crate::dove_input::{DoveInput, NoInput};
+use crate::file_provider::{self, FileProvider};
+use crate::hooks::InterpreterHooks;
+
+/// Settings of an `Interpreter`. Start from `InterpreterOptions::default()`
+/// and override fields with the builder methods, e.g.
+/// `InterpreterOptions::default().max_call_depth(Some(200)).repl_echo(false)`.
+#[derive(Clone)]
+pub struct InterpreterOptions {
+    /// Which builtins are defined.
+    pub builtins: BuiltinPolicy,
+    /// How deep function calls may nest before a runtime error, `None` for no limit.
+    pub max_call_depth: Option<usize>,
+    /// How long a single evaluation, such as a script or an input of the REPL, may run before a
+    /// runtime error stops it, `None` for no limit. Checked before each statement.
+    /// The clock is not available on wasm, so leave it unset there.
+    pub evaluation_timeout: Option<Duration>,
+    /// Whether a REPL prints the value of an expression entered on its own.
+    pub repl_echo: bool,
+    /// What a REPL shows before each input, `>>>` by default. Lines continuing an unfinished input
+    /// show as many dots instead, so they line up.
+    pub repl_prompt: String,
+    /// Whether a REPL shows its version and where to learn more when it starts.
+    pub repl_banner: bool,
+    /// Whether likely mistakes, such as assigning in a condition, are errors rather than warnings,
+    /// and dividing by zero is an error rather than giving infinity or NaN.
+    pub strict: bool,
+    /// Whether empty strings and collections are falsy, as in Python. By default only `nil` and `false` are.
+    pub falsy_empty: bool,
+    /// Digits after the decimal point when `print` and the REPL show a number, `None` for as many
+    /// as needed to read back the same number. Conversions such as `str` are not affected.
+    pub float_precision: Option<usize>,
+    /// Whether `math.random` gives the same numbers on every run, e.g. for tests.
+    pub deterministic: bool,
+    pub hooks: Option<Rc<dyn InterpreterHooks>>,
+    /// Where `import` statements read files from.
+    pub file_provider: Rc<dyn FileProvider>,
+    /// Where the `input` builtin reads lines from, nowhere by default.
+    pub input: Rc<dyn DoveInput>,
+}
+
+impl InterpreterOptions {
+    pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
+
+    pub fn builtins(mut self, builtins: BuiltinPolicy) -> InterpreterOptions {
+        self.builtins = builtins;
+        self
+    }
+
+    pub fn max_call_depth(mut self, max_call_depth: Option<usize>) -> InterpreterOptions {
+        self.max_call_depth = max_call_depth;
+        self
+    }
+
+    pub fn evaluation_timeout(mut self, evaluation_timeout: Option<Duration>) -> InterpreterOptions {
+        self.evaluation_timeout = evaluation_timeout;
+        self
+    }
+
+    pub fn repl_echo(mut self, repl_echo: bool) -> InterpreterOptions {
+        self.repl_echo = repl_echo;
+        self
+    }
+
+    pub fn repl_prompt(mut self, repl_prompt: &str) -> InterpreterOptions {
+        self.repl_prompt = repl_prompt.to_string();
+        self
+    }
+
+    pub fn repl_banner(mut self, repl_banner: bool) -> InterpreterOptions {
+        self.repl_banner = repl_banner;
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> InterpreterOptions {
+        self.strict = strict;
+        self
+    }
+
+    pub fn falsy_empty(mut self, falsy_empty: bool) -> InterpreterOptions {
+        self.falsy_empty = falsy_empty;
+        self
+    }
+
+    pub fn float_precision(mut self, float_precision: Option<usize>) -> InterpreterOptions {
+        self.float_precision = float_precision;
+        self
+    }
+
+    pub fn deterministic(mut self, deterministic: bool) -> InterpreterOptions {
+        self.deterministic = deterministic;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: Rc<dyn InterpreterHooks>) -> InterpreterOptions {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    pub fn file_provider(mut self, file_provider: Rc<dyn FileProvider>) -> InterpreterOptions {
+        self.file_provider = file_provider;
+        self
+    }
+
+    pub fn input(mut self, input: Rc<dyn DoveInput>) -> InterpreterOptions {
+        self.input = input;
+        self
+    }
+}
+
+impl Default for InterpreterOptions {
+    fn default() -> InterpreterOptions {
+        InterpreterOptions {
+            builtins: BuiltinPolicy::allow_all(),
+            max_call_depth: Some(InterpreterOptions::DEFAULT_MAX_CALL_DEPTH),
+            evaluation_timeout: None,
+            repl_echo: true,
+            repl_prompt: ">>>".to_string(),
+            repl_banner: true,
+            strict: false,
+            falsy_empty: false,
+            float_precision: None,
+            deterministic: false,
+            hooks: None,
+            file_provider: file_provider::default_provider(),
+            input: Rc::new(NoInput),
+        }
+    }
+}
@@ -1,14 +1,17 @@
 use std::rc::Rc;
 
-use crate::ast::{Expr, Stmt};
-use crate::token::{Token, TokenType, Literals};
+use crate::ast::{DictRest, Expr, ImportTarget, MatchCase, Param, Pattern, Stmt};
+use crate::token::{Token, TokenType, Literals, DictKey};
+use crate::constants::keywords::KEYWORD_TOKENS;
 use crate::error_handler::CompiletimeErrorHandler;
 use crate::dove_output::DoveOutput;
+use crate::version::LanguageVersion;
 
 #[derive(Debug)]
 enum ParseError {
     Token(Token, String),
-    Line(usize, String),
+    /// An error at the token, with a hint on how to fix it. Boxed, since it is rare.
+    Help(Box<(Token, String, String)>),
 }
 
 type Result<T> = std::result::Result<T, ParseError>;
@@ -20,12 +23,6 @@ pub struct Parser {
     /// If this is true, automatically skips newline after advance.
     ignore_newline: bool,
 
-    /// If `is_in_repl` is true, do not consider unterminated block as error,
-    /// set `unfinished_blk` to true,
-    /// when given source is parsed correctly, set `unfinished_blk` back to false.
-    is_in_repl: bool,
-    pub is_in_unfinished_blk: bool,
-
     error_handler: CompiletimeErrorHandler,
 
     /// Indicates how "deep" the parser currently is nested in (), [], and {}.
@@ -37,13 +34,12 @@ pub struct Parser {
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>, is_in_repl: bool, output: Rc<dyn DoveOutput>) -> Parser {
+    pub fn new(tokens: Vec<Token>, output: Rc<dyn DoveOutput>) -> Parser {
         Parser {
             current: 0,
-            tokens,
+            // Comments kept by the scanner are not part of the syntax.
+            tokens: tokens.into_iter().filter(|token| !token.token_type.is_comment()).collect(),
             ignore_newline: false,
-            is_in_repl,
-            is_in_unfinished_blk: false,
             error_handler: CompiletimeErrorHandler {
                 had_error: false,
                 output,
@@ -58,6 +54,14 @@ impl Parser {
 
         self.skip_newlines();
 
+        if self.check(TokenType::PRAGMA) {
+            let pragma = self.advance();
+            if let Err(message) = LanguageVersion::from_pragma(&pragma.lexeme[1..]) {
+                self.error_handler.token_error(pragma, message);
+            }
+            self.skip_newlines();
+        }
+
         while !self.is_at_end() {
             if let Some(statement) = self.declaration() {
                 if self.consume_newline().is_ok() {
@@ -73,19 +77,19 @@ impl Parser {
         statements
     }
 
+    pub fn had_error(&self) -> bool {
+        self.error_handler.had_error
+    }
+
     fn handle_error(&mut self, error: ParseError) {
         self.synchronize();
 
         match error {
-            ParseError::Token(token, message) => {
-                if self.is_in_repl && message.contains("RIGHT_BRACE") {
-                    self.is_in_unfinished_blk = true;
-                    return;
-                }
-
-                self.error_handler.token_error(token, message)
+            ParseError::Token(token, message) => self.error_handler.token_error(token, message),
+            ParseError::Help(error) => {
+                let (token, message, help) = *error;
+                self.error_handler.token_error_with_help(token, message, help)
             },
-            ParseError::Line(line, message) => self.error_handler.line_error(line, message),
         }
     }
 
@@ -116,7 +120,14 @@ impl Parser {
         let declaration = match self.peek().token_type {
             TokenType::CLASS => self.class_decl(),
             TokenType::FUN => self.fun_decl(),
+            TokenType::IMPORT => self.import_decl(),
+            TokenType::FROM => self.import_from_decl(),
             TokenType::LET => self.var_decl(),
+            TokenType::PRAGMA => Err(ParseError::Help(Box::new((
+                self.peek().clone(),
+                "A pragma must come before any code.".to_string(),
+                "move it to the first line of the file".to_string(),
+            )))),
             _ => self.statement(),
         };
 
@@ -142,15 +153,27 @@ impl Parser {
         self.consume(TokenType::LEFT_BRACE)?;
         self.skip_newlines();
 
-        let mut functions = vec![];
+        let mut members = vec![];
+        if let Ok(doc) = self.consume(TokenType::STRING) {
+            members.push(Stmt::Expression(Expr::Literal(doc.literal.unwrap_or(Literals::Nil))));
+            self.skip_newlines();
+        }
         while !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() {
-            functions.push(self.fun_decl()?);
+            if self.check(TokenType::LET) {
+                let keyword = self.peek().clone();
+                match self.var_decl()? {
+                    field @ Stmt::Variable(Pattern::Binding(_), _) => members.push(field),
+                    _ => return Err(ParseError::Token(keyword, "Fields are declared one at a time, patterns cannot be used.".to_string())),
+                }
+            } else {
+                members.push(self.fun_decl()?);
+            }
             self.skip_newlines();
         }
 
         self.consume(TokenType::RIGHT_BRACE)?;
 
-        Ok(Stmt::Class(identifier, superclass, functions))
+        Ok(Stmt::Class(identifier, superclass, members))
     }
 
     fn fun_decl(&mut self) -> Result<Stmt> {
@@ -169,16 +192,44 @@ impl Parser {
         Ok(Stmt::Function(identifier, parameters, Box::new(block)))
     }
 
+    fn import_decl(&mut self) -> Result<Stmt> {
+        let keyword = self.consume(TokenType::IMPORT)?;
+        let path = self.consume(TokenType::STRING)?;
+        let target = if self.consume(TokenType::AS).is_ok() {
+            ImportTarget::Module(self.consume(TokenType::IDENTIFIER)?)
+        } else {
+            ImportTarget::Globals
+        };
+
+        Ok(Stmt::Import(keyword, path, target))
+    }
+
+    fn import_from_decl(&mut self) -> Result<Stmt> {
+        let keyword = self.consume(TokenType::FROM)?;
+        let path = self.consume(TokenType::STRING)?;
+        self.consume(TokenType::IMPORT)?;
+
+        let mut names = vec![self.consume(TokenType::IDENTIFIER)?];
+        while self.consume(TokenType::COMMA).is_ok() {
+            names.push(self.consume(TokenType::IDENTIFIER)?);
+        }
+
+        Ok(Stmt::Import(keyword, path, ImportTarget::Names(names)))
+    }
+
     fn var_decl(&mut self) -> Result<Stmt> {
         self.consume(TokenType::LET)?;
-        let variable = self.consume(TokenType::IDENTIFIER)?;
+        let pattern = self.variable_pattern()?;
+
         let expr = if self.consume(TokenType::EQUAL).is_ok() {
             Some(self.expression()?)
-        } else {
+        } else if let Pattern::Binding(_) = pattern {
             None
+        } else {
+            return Err(ParseError::Token(self.peek().clone(), "Expected '=' after a pattern, it needs a value to unpack.".to_string()));
         };
 
-        Ok(Stmt::Variable(variable, expr))
+        Ok(Stmt::Variable(pattern, expr))
     }
 
     fn statement(&mut self) -> Result<Stmt> {
@@ -187,7 +238,7 @@ impl Parser {
                 // Try to parse a dictionary. If it doesn't work, then parse block
                 let current = self.current;
                 let nested_level = self.nested_level;
-                self.consume(TokenType::LEFT_BRACE)?;
+                let brace = self.consume(TokenType::LEFT_BRACE)?;
 
                 let prev = self.set_ignore_newline(true);
                 let exprs = self.key_value_pairs();
@@ -196,7 +247,7 @@ impl Parser {
                 // Check if can parse key value pairs
                 if let Ok(exprs) = exprs {
                     if self.consume(TokenType::RIGHT_BRACE).is_ok() {
-                        return Ok(Stmt::Expression(Expr::Dictionary(exprs)));
+                        return Ok(Stmt::Expression(Expr::Dictionary(brace, exprs)));
                     }
                 }
 
@@ -205,12 +256,18 @@ impl Parser {
                 self.nested_level = nested_level;
                 self.block()
             },
-            TokenType::FOR => self.for_stmt(),
+            TokenType::FOR => self.for_stmt(None),
+            TokenType::MATCH => self.match_stmt(),
             TokenType::PRINT => self.print_stmt(),
             TokenType::RETURN => self.return_stmt(),
-            TokenType::WHILE => self.while_stmt(),
+            TokenType::SWITCH => self.switch_stmt(),
+            TokenType::TRY => self.try_stmt(),
+            TokenType::WHILE => self.while_stmt(None),
+            TokenType::WITH => self.with_stmt(),
             TokenType::BREAK => self.break_stmt(),
             TokenType::CONTINUE => self.continue_stmt(),
+            TokenType::DEL => self.del_stmt(),
+            TokenType::IDENTIFIER if self.check_next(TokenType::COLON) => self.labeled_stmt(),
             _ => self.expr_stmt(),
         }
     }
@@ -244,13 +301,60 @@ impl Parser {
         Ok(Stmt::Block(statements))
     }
 
-    fn for_stmt(&mut self) -> Result<Stmt> {
+    fn for_stmt(&mut self, label: Option<Token>) -> Result<Stmt> {
         self.consume(TokenType::FOR)?;
-        let variable = self.consume(TokenType::IDENTIFIER)?;
+        let pattern = self.variable_pattern()?;
         self.consume(TokenType::IN)?;
         let expr = self.logic_or()?;
         let block = self.block()?;
-        Ok(Stmt::For(variable, expr, Box::new(block)))
+        Ok(Stmt::For(pattern, expr, Box::new(block), label))
+    }
+
+    /// A loop with a label, e.g. `outer: for x in xs {...}`, which `break outer` and `continue outer` refer to.
+    fn labeled_stmt(&mut self) -> Result<Stmt> {
+        let label = self.consume(TokenType::IDENTIFIER)?;
+        self.consume(TokenType::COLON)?;
+
+        match self.peek().token_type {
+            TokenType::FOR => self.for_stmt(Some(label)),
+            TokenType::WHILE => self.while_stmt(Some(label)),
+            _ => Err(ParseError::Token(self.peek().clone(), "Only 'for' and 'while' loops can have a label.".to_string())),
+        }
+    }
+
+    fn match_stmt(&mut self) -> Result<Stmt> {
+        let keyword = self.consume(TokenType::MATCH)?;
+        let value = self.logic_or()?;
+
+        self.consume(TokenType::LEFT_BRACE)?;
+        self.skip_newlines();
+        let prev = self.set_ignore_newline(false);
+
+        let mut cases = vec![];
+        while !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            self.consume(TokenType::CASE)?;
+            let pattern = self.pattern()?;
+            let guard = if self.consume(TokenType::IF).is_ok() {
+                Some(self.logic_or()?)
+            } else {
+                None
+            };
+            self.consume(TokenType::MINUS_GREATER)?;
+
+            // Like lambdas, support both a block and a single statement
+            let body = if self.check(TokenType::LEFT_BRACE) {
+                self.block()?
+            } else {
+                Stmt::Block(vec![self.statement()?])
+            };
+
+            cases.push(MatchCase { pattern, guard, body });
+            self.skip_newlines();
+        }
+
+        self.set_ignore_newline(prev);
+        self.consume(TokenType::RIGHT_BRACE)?;
+        Ok(Stmt::Match(keyword, value, cases))
     }
 
     fn print_stmt(&mut self) -> Result<Stmt> {
@@ -270,21 +374,84 @@ impl Parser {
         }
     }
 
-    fn while_stmt(&mut self) -> Result<Stmt> {
+    fn switch_stmt(&mut self) -> Result<Stmt> {
+        let keyword = self.consume(TokenType::SWITCH)?;
+        let value = self.logic_or()?;
+
+        self.consume(TokenType::LEFT_BRACE)?;
+        self.skip_newlines();
+
+        let mut cases = vec![];
+        while self.consume(TokenType::CASE).is_ok() {
+            let mut values = vec![self.logic_or()?];
+            while self.consume(TokenType::COMMA).is_ok() {
+                values.push(self.logic_or()?);
+            }
+
+            cases.push((values, self.block()?));
+            self.skip_newlines();
+        }
+
+        let else_block = if self.consume(TokenType::ELSE).is_ok() {
+            let block = self.block()?;
+            self.skip_newlines();
+            Some(Box::new(block))
+        } else {
+            None
+        };
+
+        self.consume(TokenType::RIGHT_BRACE)?;
+        Ok(Stmt::Switch(keyword, value, cases, else_block))
+    }
+
+    fn try_stmt(&mut self) -> Result<Stmt> {
+        let keyword = self.consume(TokenType::TRY)?;
+        let body = self.block()?;
+        self.skip_newlines();
+        self.consume(TokenType::CATCH)?;
+        let name = self.consume(TokenType::IDENTIFIER)?;
+        let handler = self.block()?;
+        Ok(Stmt::Try(keyword, Box::new(body), name, Box::new(handler)))
+    }
+
+    fn while_stmt(&mut self, label: Option<Token>) -> Result<Stmt> {
         self.consume(TokenType::WHILE)?;
         let condition = self.expression()?;
         let block = self.block()?;
-        Ok(Stmt::While(condition, Box::new(block)))
+        Ok(Stmt::While(condition, Box::new(block), label))
+    }
+
+    fn with_stmt(&mut self) -> Result<Stmt> {
+        let keyword = self.consume(TokenType::WITH)?;
+        let resource = self.logic_or()?;
+        let name = if self.check(TokenType::AS) {
+            self.consume(TokenType::AS)?;
+            Some(self.consume(TokenType::IDENTIFIER)?)
+        } else {
+            None
+        };
+        let block = self.block()?;
+        Ok(Stmt::With(keyword, resource, name, Box::new(block)))
     }
 
     fn break_stmt(&mut self) -> Result<Stmt> {
         let token = self.consume(TokenType::BREAK)?;
-        Ok(Stmt::Break(token))
+        let label = self.consume(TokenType::IDENTIFIER).ok();
+        Ok(Stmt::Break(token, label))
     }
 
     fn continue_stmt(&mut self) -> Result<Stmt> {
         let token = self.consume(TokenType::CONTINUE)?;
-        Ok(Stmt::Continue(token))
+        let label = self.consume(TokenType::IDENTIFIER).ok();
+        Ok(Stmt::Continue(token, label))
+    }
+
+    fn del_stmt(&mut self) -> Result<Stmt> {
+        let token = self.consume(TokenType::DEL)?;
+        match self.call()? {
+            target @ Expr::Variable(_) | target @ Expr::IndexGet(_, _, _) => Ok(Stmt::Delete(token, target)),
+            _ => Err(ParseError::Token(token, "Can only delete a variable or an element.".to_string())),
+        }
     }
 
     fn expr_stmt(&mut self) -> Result<Stmt> {
@@ -307,9 +474,9 @@ impl Parser {
             TokenType::PLUS_PLUS | TokenType::MINUS_MINUS => {
                 let sign = self.advance();
 
-                // If ++ or --, make value Number(1.0).
+                // If ++ or --, make value Int(1).
                 let value = match (&sign).token_type {
-                    TokenType::PLUS_PLUS | TokenType::MINUS_MINUS => Expr::Literal(Literals::Number(1.0)),
+                    TokenType::PLUS_PLUS | TokenType::MINUS_MINUS => Expr::Literal(Literals::Int(1)),
                     // If there is equal sign, parse assignment
                     // Parse expression here to allow assigning an assign expression
                     _ => self.expression()?,
@@ -317,10 +484,10 @@ impl Parser {
 
                 // Check whether assign to variable or set object property
                 return match expr {
-                    Expr::Get(obj, name) => Ok(Expr::Set(obj, name, Box::new(value))),
-                    Expr::IndexGet(expr, index) => Ok(Expr::IndexSet(expr, index, Box::new(value))),
+                    Expr::Get(obj, name) => Ok(Expr::Set(obj, name, sign, Box::new(value))),
+                    Expr::IndexGet(expr, bracket, index) => Ok(Expr::IndexSet(expr, bracket, index, sign, Box::new(value))),
                     Expr::Variable(variable) => Ok(Expr::Assign(variable, sign, Box::new(value))),
-                    _ => Err(ParseError::Line(self.peek().line, "Cannot use assignment.".to_string())),
+                    _ => Err(ParseError::Token(sign, "Cannot use assignment.".to_string())),
                 };
             },
             _ => {
@@ -330,7 +497,7 @@ impl Parser {
     }
 
     fn lambda(&mut self) -> Result<Expr> {
-        if self.consume(TokenType::LAMBDA).is_ok() {
+        if let Ok(keyword) = self.consume(TokenType::LAMBDA) {
             let parameters = self.parameters()?;
             self.consume(TokenType::MINUS_GREATER)?;
 
@@ -344,7 +511,7 @@ impl Parser {
                 stmt = Stmt::Block(vec![temp]);
             }
 
-            let res = Expr::Lambda(parameters, Box::new(stmt));
+            let res = Expr::Lambda(keyword, parameters, Box::new(stmt));
             // println!("{:?}", &res);
             Ok(res)
         } else {
@@ -376,10 +543,28 @@ impl Parser {
             Ok(Expr::IfExpr(Box::new(condition), Box::new(then_stmt), Box::new(else_stmt)))
 
         } else {
-            self.logic_or()
+            self.conditional()
         }
     }
 
+    /// `condition ? a : b`, short for `if condition { a } else { b }`.
+    fn conditional(&mut self) -> Result<Expr> {
+        let condition = self.logic_or()?;
+        if self.consume(TokenType::QUESTION).is_err() {
+            return Ok(condition);
+        }
+
+        let then_expr = self.if_expr()?;
+        self.consume(TokenType::COLON)?;
+        let else_expr = self.if_expr()?;
+
+        Ok(Expr::IfExpr(
+            Box::new(condition),
+            Box::new(Stmt::Block(vec![Stmt::Expression(then_expr)])),
+            Box::new(Stmt::Block(vec![Stmt::Expression(else_expr)])),
+        ))
+    }
+
     fn logic_or(&mut self) -> Result<Expr> {
         let mut left = self.logic_and()?;
 
@@ -430,16 +615,70 @@ impl Parser {
     }
 
     fn range(&mut self) -> Result<Expr> {
-        let left = self.addition()?;
+        let left = self.bitwise_or()?;
 
         if let Some(token) = self.match_token(&[TokenType::DOT_DOT, TokenType::DOT_DOT_DOT]) {
-            let right = self.addition()?;
-            Ok(Expr::Binary(Box::new(left), token, Box::new(right)))
+            let right = self.bitwise_or()?;
+
+            // Optional step, e.g. `0..10 by 2`
+            let step = if self.consume(TokenType::BY).is_ok() {
+                Some(Box::new(self.bitwise_or()?))
+            } else {
+                None
+            };
+
+            Ok(Expr::Range(Box::new(left), token, Box::new(right), step))
         } else {
             Ok(left)
         }
     }
 
+    /// Bitwise operators bind looser than arithmetic, from `|` through `^` and `&` to the shifts,
+    /// e.g. `flags & 1 << 2` is `flags & (1 << 2)`.
+    fn bitwise_or(&mut self) -> Result<Expr> {
+        let mut left = self.bitwise_xor()?;
+
+        while let Some(op) = self.match_token(&[TokenType::PIPE]) {
+            let right = self.bitwise_xor()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn bitwise_xor(&mut self) -> Result<Expr> {
+        let mut left = self.bitwise_and()?;
+
+        while let Some(op) = self.match_token(&[TokenType::CARET]) {
+            let right = self.bitwise_and()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn bitwise_and(&mut self) -> Result<Expr> {
+        let mut left = self.shift()?;
+
+        while let Some(op) = self.match_token(&[TokenType::AMPERSAND]) {
+            let right = self.shift()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn shift(&mut self) -> Result<Expr> {
+        let mut left = self.addition()?;
+
+        while let Some(op) = self.match_token(&[TokenType::LESS_LESS, TokenType::GREATER_GREATER]) {
+            let right = self.addition()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
     fn addition(&mut self) -> Result<Expr> {
         let mut left = self.multiplication()?;
 
@@ -467,11 +706,11 @@ impl Parser {
     fn unary(&mut self) -> Result<Expr> {
         let mut unary_ops = vec![];
 
-        while let Some(op) = self.match_token(&[TokenType::BANG, TokenType::MINUS, TokenType::NOT]) {
+        while let Some(op) = self.match_token(&[TokenType::BANG, TokenType::MINUS, TokenType::NOT, TokenType::TILDE]) {
             unary_ops.push(op);
         }
 
-        let mut expr = self.call()?;
+        let mut expr = self.power()?;
 
         for op in unary_ops.into_iter().rev() {
             expr = Expr::Unary(op, Box::new(expr));
@@ -480,6 +719,19 @@ impl Parser {
         Ok(expr)
     }
 
+    /// `**` binds tighter than a unary operator on its left, e.g. `-2 ** 2` is -4,
+    /// and is right-associative, e.g. `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+    fn power(&mut self) -> Result<Expr> {
+        let left = self.call()?;
+
+        if let Some(op) = self.match_token(&[TokenType::STAR_STAR]) {
+            let right = self.unary()?;
+            return Ok(Expr::Binary(Box::new(left), op, Box::new(right)));
+        }
+
+        Ok(left)
+    }
+
     fn call(&mut self) -> Result<Expr> {
         let mut expr = self.primary()?;
 
@@ -491,22 +743,22 @@ impl Parser {
                 self.consume(TokenType::RIGHT_PAREN)?;
                 expr = Expr::Call(Box::new(expr), paren, args);
 
-            } else if self.consume(TokenType::LEFT_BRACKET).is_ok() {
+            } else if let Ok(bracket) = self.consume(TokenType::LEFT_BRACKET) {
                 let prev = self.set_ignore_newline(true);
                 let index = self.expression()?;
                 self.set_ignore_newline(prev);
                 self.consume(TokenType::RIGHT_BRACKET)?;
-                expr = Expr::IndexGet(Box::new(expr), Box::new(index));
+                expr = Expr::IndexGet(Box::new(expr), bracket, Box::new(index));
 
             } else if self.consume(TokenType::DOT).is_ok() {
-                let name = self.consume(TokenType::IDENTIFIER)?;
+                let name = self.property_name()?;
                 expr = Expr::Get(Box::new(expr), name);
 
             } else if self.check(TokenType::NEWLINE) && self.peek_next_non_newline().token_type == TokenType::DOT {
                 // Allows leading dot chain method call
                 self.skip_newlines();
                 self.consume(TokenType::DOT)?;
-                let name = self.consume(TokenType::IDENTIFIER)?;
+                let name = self.property_name()?;
                 expr = Expr::Get(Box::new(expr), name);
 
             } else {
@@ -517,6 +769,16 @@ impl Parser {
         Ok(expr)
     }
 
+    /// The name after a dot, which may also be a keyword, e.g. `flag.not()`.
+    fn property_name(&mut self) -> Result<Token> {
+        if KEYWORD_TOKENS.contains_key(&self.peek().lexeme) {
+            let mut name = self.advance();
+            name.token_type = TokenType::IDENTIFIER;
+            return Ok(name);
+        }
+        self.consume(TokenType::IDENTIFIER)
+    }
+
     fn primary(&mut self) -> Result<Expr> {
         if let Some(token) = self.match_token(&[
             TokenType::STRING,
@@ -580,34 +842,182 @@ impl Parser {
             self.consume(TokenType::RIGHT_BRACKET)?;
             Ok(Expr::Array(exprs))
 
-        } else if self.consume(TokenType::LEFT_BRACE).is_ok() {
+        } else if let Ok(brace) = self.consume(TokenType::LEFT_BRACE) {
             // Parse dictionary
             let prev = self.set_ignore_newline(true);
             let exprs = self.key_value_pairs()?;
             self.set_ignore_newline(prev);
             self.consume(TokenType::RIGHT_BRACE)?;
-            Ok(Expr::Dictionary(exprs))
+            Ok(Expr::Dictionary(brace, exprs))
 
         } else {
-            Err(ParseError::Token(self.peek().clone(), "Unexpected token.".to_string()))
+            Err(self.unexpected_token("Unexpected token.".to_string()))
+        }
+    }
+}
+
+// Patterns
+impl Parser {
+    /// The variables of `let` and `for`. A plain name, even `_`, is a variable, anything else a pattern unpacking the value.
+    fn variable_pattern(&mut self) -> Result<Pattern> {
+        match self.consume(TokenType::IDENTIFIER) {
+            Ok(variable) => Ok(Pattern::Binding(variable)),
+            Err(_) => self.pattern(),
+        }
+    }
+
+    fn pattern(&mut self) -> Result<Pattern> {
+        // `true`, `false` and `nil` are patterns, other keywords are likely meant as names.
+        if self.at_keyword() && !matches!(self.peek().token_type, TokenType::TRUE | TokenType::FALSE | TokenType::NIL) {
+            return Err(self.unexpected_token("Expected a pattern.".to_string()));
+        }
+
+        let token = self.advance();
+        match token.token_type {
+            TokenType::IDENTIFIER if token.lexeme == "_" => Ok(Pattern::Wildcard),
+            TokenType::IDENTIFIER => Ok(Pattern::Binding(token)),
+            TokenType::NUMBER | TokenType::STRING => Ok(Pattern::Literal(token.literal.unwrap())),
+            TokenType::TRUE => Ok(Pattern::Literal(Literals::Boolean(true))),
+            TokenType::FALSE => Ok(Pattern::Literal(Literals::Boolean(false))),
+            TokenType::NIL => Ok(Pattern::Literal(Literals::Nil)),
+            TokenType::MINUS => match self.consume(TokenType::NUMBER)?.literal {
+                Some(Literals::Number(n)) => Ok(Pattern::Literal(Literals::Number(-n))),
+                Some(Literals::Int(n)) => Ok(Pattern::Literal(Literals::Int(-n))),
+                _ => Err(ParseError::Token(token, "Expected a number.".to_string())),
+            },
+            TokenType::LEFT_PAREN => {
+                let (patterns, trailing_comma) = self.patterns(TokenType::RIGHT_PAREN)?;
+
+                // `(a)` is a grouped pattern, while `(a,)` is a tuple with one element
+                if patterns.len() == 1 && !trailing_comma && !matches!(patterns[0], Pattern::Rest(_)) {
+                    Ok(patterns.into_iter().next().unwrap())
+                } else {
+                    Ok(Pattern::Tuple(patterns))
+                }
+            },
+            TokenType::LEFT_BRACKET => Ok(Pattern::Array(self.patterns(TokenType::RIGHT_BRACKET)?.0)),
+            TokenType::LEFT_BRACE => self.dict_pattern(),
+            _ => Err(ParseError::Token(token, "Expected a pattern.".to_string())),
+        }
+    }
+
+    /// The entries of a dictionary pattern after `{`. Keys are strings, integers or names, which stand for strings.
+    /// A name without a pattern, as in `{name, age}`, binds the value of its key to a variable of the same name.
+    fn dict_pattern(&mut self) -> Result<Pattern> {
+        let prev = self.set_ignore_newline(true);
+
+        let mut entries = vec![];
+        let mut rest = DictRest::None;
+        while !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            if self.consume(TokenType::DOT_DOT_DOT).is_ok() {
+                // The rest pattern must be the last one
+                rest = match self.consume(TokenType::IDENTIFIER) {
+                    Ok(name) if name.lexeme != "_" => DictRest::Bound(name),
+                    _ => DictRest::Ignored,
+                };
+                self.consume(TokenType::COMMA).ok();
+                break;
+            }
+
+            let token = self.advance();
+            let key = match (&token.token_type, &token.literal) {
+                (TokenType::IDENTIFIER, _) => DictKey::StringKey(token.lexeme.clone()),
+                (TokenType::STRING, Some(literal)) | (TokenType::NUMBER, Some(literal)) => match DictKey::from_literal(literal) {
+                    Some(key) => key,
+                    None => return Err(ParseError::Token(token, "Dictionary keys must be strings or integers.".to_string())),
+                },
+                _ => return Err(ParseError::Token(token, "Expected a dictionary key.".to_string())),
+            };
+            if entries.iter().any(|(other, _)| *other == key) {
+                return Err(ParseError::Token(token, "Duplicate key in dictionary pattern.".to_string()));
+            }
+            let pattern = match token.token_type {
+                TokenType::IDENTIFIER if !self.check(TokenType::COLON) => Pattern::Binding(token),
+                _ => {
+                    self.consume(TokenType::COLON)?;
+                    self.pattern()?
+                },
+            };
+            entries.push((key, pattern));
+
+            if self.consume(TokenType::COMMA).is_err() {
+                break;
+            }
+        }
+
+        self.set_ignore_newline(prev);
+        self.consume(TokenType::RIGHT_BRACE)?;
+        Ok(Pattern::Dictionary(entries, rest))
+    }
+
+    /// Comma separated patterns up to and including `end`, and whether there is a trailing comma.
+    /// The last one can be a rest pattern, `...name` or `name...`.
+    fn patterns(&mut self, end: TokenType) -> Result<(Vec<Pattern>, bool)> {
+        let prev = self.set_ignore_newline(true);
+
+        let mut patterns: Vec<Pattern> = vec![];
+        let mut trailing_comma = false;
+        while !self.check(end) && !self.is_at_end() {
+            if let Some(Pattern::Rest(_)) = patterns.last() {
+                return Err(ParseError::Token(self.peek().clone(), "A rest pattern must be the last one.".to_string()));
+            }
+
+            let pattern = if self.consume(TokenType::DOT_DOT_DOT).is_ok() {
+                Pattern::Rest(self.consume(TokenType::IDENTIFIER).ok().filter(|name| name.lexeme != "_"))
+            } else if self.check(TokenType::IDENTIFIER) && self.check_next(TokenType::DOT_DOT_DOT) {
+                let name = self.advance();
+                self.advance();
+                Pattern::Rest(Some(name).filter(|name| name.lexeme != "_"))
+            } else {
+                self.pattern()?
+            };
+            patterns.push(pattern);
+            trailing_comma = self.consume(TokenType::COMMA).is_ok();
+            if !trailing_comma {
+                break;
+            }
         }
+
+        self.set_ignore_newline(prev);
+        self.consume(end)?;
+        Ok((patterns, trailing_comma))
     }
 }
 
 // Other parsing methods
 impl Parser {
-    fn parameters(&mut self) -> Result<Vec<Token>> {
-        let mut parameters = vec![];
+    /// Parameters with default values, e.g. `b = 2`, must come after the others,
+    /// and a rest parameter, e.g. `...args`, must be the last one.
+    fn parameters(&mut self) -> Result<Vec<Param>> {
+        let mut parameters: Vec<Param> = vec![];
 
         loop {
-            if let Ok(token) = self.consume(TokenType::IDENTIFIER) {
-                parameters.push(token);
-
-                if self.consume(TokenType::COMMA).is_ok() {
-                    continue;
+            if self.consume(TokenType::DOT_DOT_DOT).is_ok() {
+                let name = self.consume(TokenType::IDENTIFIER)?;
+                if self.check(TokenType::COMMA) || self.check(TokenType::EQUAL) {
+                    return Err(ParseError::Token(self.peek().clone(), "A rest parameter must be the last parameter, without a default value.".to_string()));
                 }
+                parameters.push(Param { name, default: None, rest: true });
+                break;
+            }
+
+            let name = match self.consume(TokenType::IDENTIFIER) {
+                Ok(name) => name,
+                Err(error) if self.at_keyword() => return Err(error),
+                Err(_) => break,
+            };
+            let default = match self.consume(TokenType::EQUAL) {
+                Ok(_) => Some(self.if_expr()?),
+                Err(_) => None,
+            };
+            if default.is_none() && parameters.iter().any(|param| param.default.is_some()) {
+                return Err(ParseError::Token(name, "Parameters without default values must come before those with them.".to_string()));
+            }
+            parameters.push(Param { name, default, rest: false });
+
+            if self.consume(TokenType::COMMA).is_err() {
+                break;
             }
-            break;
         }
 
         Ok(parameters)
@@ -664,6 +1074,11 @@ impl Parser {
         &self.tokens[self.current]
     }
 
+    /// Whether the token after the current one has the type.
+    fn check_next(&self, token_type: TokenType) -> bool {
+        self.tokens.get(self.current + 1).is_some_and(|token| token.token_type == token_type)
+    }
+
     fn peek_next_non_newline(&self) -> &Token {
         let mut index = self.current + 1;
         while self.tokens[index].token_type == TokenType::NEWLINE && index < self.tokens.len() {
@@ -686,7 +1101,10 @@ impl Parser {
 
         match token.token_type {
             TokenType::LEFT_PAREN | TokenType::LEFT_BRACKET | TokenType::LEFT_BRACE => self.nested_level += 1,
-            TokenType::RIGHT_PAREN | TokenType::RIGHT_BRACKET | TokenType::RIGHT_BRACE => self.nested_level -= 1,
+            TokenType::RIGHT_PAREN | TokenType::RIGHT_BRACKET | TokenType::RIGHT_BRACE => {
+                // Unbalanced closers are reported by whatever expected something else.
+                self.nested_level = self.nested_level.saturating_sub(1)
+            },
             _ => (),
         }
 
@@ -707,11 +1125,30 @@ impl Parser {
     fn consume(&mut self, token_type: TokenType) -> Result<Token> {
         if self.check(token_type) {
             Ok(self.advance())
+        } else if token_type == TokenType::IDENTIFIER {
+            Err(self.unexpected_token(format!("Unexpected token, expected type {:?}.", token_type)))
         } else {
             Err(ParseError::Token(self.peek().clone(), format!("Unexpected token, expected type {:?}.", token_type)))
         }
     }
 
+    /// An error at the current token where an identifier could be, which is explained instead
+    /// if the token is a keyword, e.g. `let class = 1`.
+    fn unexpected_token(&self, message: String) -> ParseError {
+        let token = self.peek().clone();
+        if self.at_keyword() {
+            let message = format!("'{}' is a reserved keyword and cannot be used as an identifier.", token.lexeme);
+            return ParseError::Token(token, message);
+        }
+        ParseError::Token(token, message)
+    }
+
+    /// Whether the current token is a keyword rather than an identifier.
+    fn at_keyword(&self) -> bool {
+        let token = self.peek();
+        token.token_type != TokenType::IDENTIFIER && KEYWORD_TOKENS.contains_key(&token.lexeme)
+    }
+
     /// Consume at least one newline and skip the rest.
     /// Does not need to skip if at the end of file.
     fn consume_newline(&mut self) -> Result<()> {
@@ -738,3 +1175,41 @@ impl Parser {
         prev
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::dove_output::DoveOutput;
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+
+    /// Keeps the reported errors.
+    struct Errors(RefCell<Vec<String>>);
+
+    impl DoveOutput for Errors {
+        fn print(&self, _message: String) {}
+        fn warning(&self, _message: String) {}
+        fn error(&self, message: String) {
+            self.0.borrow_mut().push(message);
+        }
+    }
+
+    fn first_error(source: &str) -> String {
+        let errors = Rc::new(Errors(RefCell::new(vec![])));
+        let tokens = Scanner::new(source.chars().collect(), errors.clone()).try_scan_tokens().unwrap();
+        Parser::new(tokens, errors.clone()).program();
+
+        let first = errors.0.borrow().first().cloned();
+        first.expect("Expected an error.")
+    }
+
+    #[test]
+    fn reserved_keywords_as_names() {
+        let reserved = "'class' is a reserved keyword and cannot be used as an identifier.";
+        assert!(first_error("let class = 1").contains(reserved));
+        assert!(first_error("for class in [1] {}").contains(reserved));
+        assert!(first_error("fun f(class) {}").contains(reserved));
+    }
+}
@@ -0,0 +1,112 @@
+use std::rc::Rc;
+
+use crate::dove_callable::DoveCallable;
+use crate::dove_class::DoveClass;
+use crate::interpreter::stringify;
+use crate::token::Literals;
+
+/// Describe the type of a value, including the class hierarchy of classes and instances,
+/// e.g. `Instance of Dog < Animal`.
+pub fn type_description(value: &Literals) -> String {
+    match value {
+        Literals::Class(class) => format!("Class {}", class_hierarchy(class)),
+        Literals::Instance(instance) => format!("Instance of {}", class_hierarchy(&instance.borrow().class)),
+        _ => value.to_string(),
+    }
+}
+
+/// The name of a class followed by the names of its superclasses, e.g. `Dog < Animal`.
+pub fn class_hierarchy(class: &Rc<DoveClass>) -> String {
+    let mut names = vec![class.name.clone()];
+    let mut current = class.superclass();
+    while let Some(superclass) = current {
+        names.push(superclass.name.clone());
+        current = superclass.superclass();
+    }
+
+    names.join(" < ")
+}
+
+/// Describe the contents of a value over multiple lines: fields of instances,
+/// methods of classes and parameters of functions.
+pub fn inspect(value: &Literals) -> String {
+    let mut lines = vec![type_description(value)];
+
+    match value {
+        Literals::Instance(instance) => {
+            let instance = instance.borrow();
+            for name in instance.field_names() {
+                if let Some(field) = instance.get_field(&name) {
+                    lines.push(format!("    {}: {}", name, stringify(field)));
+                }
+            }
+            for name in instance.class.method_names() {
+                lines.push(format!("    fun {}", name));
+            }
+        },
+        Literals::Class(class) => {
+            for name in class.method_names() {
+                lines.push(format!("    fun {}", name));
+            }
+        },
+        Literals::Function(function) => lines.push(signature(function.as_ref())),
+        _ => lines.push(format!("    {}", stringify(value.clone()))),
+    }
+
+    lines.join("\n")
+}
+
+/// Describe what can be done with a value, for the `help` builtin: the signature and docstring of functions,
+/// the docstring, fields and methods of classes and instances, and the properties of other values.
+pub fn help(value: &Literals) -> String {
+    let mut lines = vec![type_description(value)];
+
+    match value {
+        Literals::Function(function) => {
+            lines.push(signature(function.as_ref()));
+            lines.extend(doc_lines(function.doc().as_deref()));
+        },
+        Literals::Class(class) => {
+            lines.extend(doc_lines(class.doc()));
+            let (fields, _) = class.fields();
+            lines.push(names_line("Fields", fields.iter().map(|(name, _)| name.lexeme.clone()).collect()));
+            lines.push(names_line("Methods", class.method_names()));
+        },
+        Literals::Instance(instance) => {
+            let instance = instance.borrow();
+            lines.extend(doc_lines(instance.class.doc()));
+            lines.push(names_line("Fields", instance.field_names()));
+            lines.push(names_line("Methods", instance.class.method_names()));
+        },
+        _ => lines.push(names_line("Properties", value.as_object().property_names())),
+    }
+
+    lines.join("\n")
+}
+
+/// The name and parameters of a function, or the number of arguments if they are unknown.
+fn signature(function: &dyn DoveCallable) -> String {
+    let params = match function.params() {
+        Some(params) => params.join(", "),
+        None => format!("{} argument(s)", function.arity()),
+    };
+    format!("    {}({})", function.name(), params)
+}
+
+/// The lines of a docstring, indented and without surrounding blank lines.
+fn doc_lines(doc: Option<&str>) -> Vec<String> {
+    doc.map(str::trim).unwrap_or("").lines()
+        .map(|line| format!("    {}", line.trim()))
+        .collect()
+}
+
+/// Sorted names after a label, e.g. `    Methods: area, init`.
+fn names_line(label: &str, mut names: Vec<String>) -> String {
+    names.sort();
+    names.dedup();
+    if names.is_empty() {
+        format!("    {}: none", label)
+    } else {
+        format!("    {}: {}", label, names.join(", "))
+    }
+}
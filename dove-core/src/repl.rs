@@ -0,0 +1,166 @@
+use std::rc::Rc;
+
+use crate::ast::Stmt;
+use crate::dove_output::DoveOutput;
+use crate::interpreter::Interpreter;
+use crate::options::InterpreterOptions;
+use crate::parser::Parser;
+use crate::resolver::Resolver;
+use crate::scanner::{ScannedInput, Scanner};
+use crate::token::Literals;
+
+/// The version shown in the banner.
+const VERSION: &str = "0.1.1";
+
+/// Whether an input of the REPL ran, or started or continued a construct that is not finished yet,
+/// e.g. a block, brackets or a string.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplStatus {
+    Complete,
+    Unfinished,
+    /// The input called `exit` with the status, the session should end.
+    Exited(i32),
+}
+
+/// An interactive session, so every REPL frontend treats input the same way: lines of an
+/// unfinished block are kept until it is complete, one mistake does not end the session,
+/// and the values of expressions are printed if the `repl_echo` option is set.
+pub struct ReplSession {
+    interpreter: Interpreter,
+    output: Rc<dyn DoveOutput>,
+    /// Lines of an unfinished input.
+    buffer: String,
+    /// Complete inputs, oldest first.
+    history: Vec<String>,
+    history_hook: Option<HistoryHook>,
+    prompt: String,
+    /// The prompt of lines continuing an unfinished input, dots as wide as `prompt`.
+    continuation: String,
+}
+
+type HistoryHook = Box<dyn FnMut(&str)>;
+
+impl ReplSession {
+    pub fn new(output: Rc<dyn DoveOutput>, options: InterpreterOptions) -> ReplSession {
+        let prompt = options.repl_prompt.clone();
+        let continuation = ".".repeat(prompt.trim_end().chars().count().max(1));
+        ReplSession {
+            interpreter: Interpreter::new(Rc::clone(&output), options),
+            output,
+            buffer: String::new(),
+            history: vec![],
+            history_hook: None,
+            prompt,
+            continuation,
+        }
+    }
+
+    /// Call `hook` with every complete input added to the history, e.g. to save it to a file.
+    pub fn on_history(mut self, hook: impl FnMut(&str) + 'static) -> ReplSession {
+        self.history_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// The prompt to show before the next line, dots while the input is unfinished.
+    pub fn prompt(&self) -> &str {
+        if self.is_unfinished() { &self.continuation } else { &self.prompt }
+    }
+
+    /// The lines to show when the session starts, none if the `repl_banner` option is off.
+    /// Frontends decide how to style them.
+    pub fn banner(&self) -> Vec<String> {
+        if !self.interpreter.options().repl_banner {
+            return vec![];
+        }
+
+        vec![
+            format!("Dove {}", VERSION),
+            "Visit https://github.com/dove-lang for more information.".to_string(),
+        ]
+    }
+
+    pub fn is_unfinished(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Forget the lines of an unfinished block, e.g. when the user interrupts it.
+    pub fn cancel(&mut self) {
+        self.buffer.clear();
+    }
+
+    pub fn interpreter(&mut self) -> &mut Interpreter {
+        &mut self.interpreter
+    }
+
+    /// Run a line of input, or keep it if it starts or continues an unfinished construct.
+    pub fn input(&mut self, line: &str) -> ReplStatus {
+        let mut source = std::mem::take(&mut self.buffer);
+        source.push_str(line);
+        if !source.ends_with('\n') {
+            source.push('\n');
+        }
+
+        self.output.repl_source(&source);
+        let tokens = match Scanner::new(source.chars().collect(), Rc::clone(&self.output)).scan_input() {
+            ScannedInput::Complete(tokens) => tokens,
+            ScannedInput::Unfinished => {
+                self.buffer = source;
+                return ReplStatus::Unfinished;
+            },
+            ScannedInput::Invalid => {
+                self.add_history(&source);
+                return ReplStatus::Complete;
+            },
+        };
+
+        self.add_history(&source);
+        let mut parser = Parser::new(tokens, Rc::clone(&self.output));
+        let statements = parser.program();
+
+        // Every input starts afresh, one mistake should not end the session.
+        self.interpreter.error_handler.reset();
+        if parser.had_error() {
+            return ReplStatus::Complete;
+        }
+
+        let mut resolver = Resolver::new(&mut self.interpreter, Rc::clone(&self.output));
+        resolver.resolve(&statements);
+        if resolver.had_error() {
+            return ReplStatus::Complete;
+        }
+
+        let echo = self.interpreter.options().repl_echo;
+        if echo && matches!(statements.last(), Some(Stmt::Expression(_))) {
+            // Show the value of an expression, e.g. `1 + 2`.
+            match self.interpreter.execute_global(&statements) {
+                Ok(Literals::Nil) => {},
+                Ok(value) => self.output.print(self.interpreter.display(value)),
+                Err(_) if self.interpreter.exit_status().is_some() => {},
+                Err(error) => self.interpreter.error_handler.runtime_error(error),
+            }
+        } else {
+            self.interpreter.interpret(statements);
+        }
+
+        match self.interpreter.exit_status() {
+            Some(status) => ReplStatus::Exited(status),
+            None => ReplStatus::Complete,
+        }
+    }
+
+    fn add_history(&mut self, source: &str) {
+        let entry = source.trim_end();
+        if entry.is_empty() {
+            return;
+        }
+
+        self.history.push(entry.to_string());
+        if let Some(hook) = &mut self.history_hook {
+            hook(entry);
+        }
+    }
+}
@@ -1,12 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
-use crate::ast::{Expr, Stmt};
-use crate::token::Token;
+use crate::ast::{Expr, ImportTarget, Param, Pattern, Stmt};
+use crate::token::{Token, TokenType};
 use crate::interpreter::Interpreter;
 use crate::error_handler::CompiletimeErrorHandler;
 use crate::constants::keywords;
 use crate::dove_output::DoveOutput;
+use crate::symbols::{Symbol, SymbolTable};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 enum FunctionType {
@@ -23,24 +24,45 @@ enum ClassType {
     Subclass,
 }
 
+/// The private members of a class being resolved, see `check_private`.
+struct PrivateMembers {
+    /// Names starting with an underscore that the class or its superclasses declare,
+    /// or that its methods use on `self`.
+    declared: HashSet<String>,
+    /// Private members used on objects other than `self`, checked once the whole class is resolved.
+    used: Vec<Token>,
+}
+
 pub struct Resolver<'a> {
     scopes: Vec<HashMap<String, bool>>,
+    /// Ids of the tokens declaring the names in `scopes`, kept when collecting symbols.
+    declarations: Vec<HashMap<String, usize>>,
+    symbols: Option<SymbolTable>,
     interpreter: &'a mut Interpreter,
     error_handler: CompiletimeErrorHandler,
     current_function: FunctionType,
     current_class: ClassType,
-    in_loop: bool,
+    /// The private members of the enclosing classes, innermost last.
+    private_members: Vec<PrivateMembers>,
+    /// The private members of each class resolved so far, keyed by name, for its subclasses.
+    class_private_members: HashMap<String, HashSet<String>>,
+    /// The labels of the loops enclosing the current statement in its function, innermost last.
+    loop_labels: Vec<Option<String>>,
 }
 
 impl<'a> Resolver<'a> {
     pub fn new(interpreter: &'a mut Interpreter, output: Rc<dyn DoveOutput>) -> Resolver<'a> {
         Resolver {
             scopes: vec![],
+            declarations: vec![],
+            symbols: None,
             interpreter,
             error_handler: CompiletimeErrorHandler::new(output),
             current_function: FunctionType::None,
             current_class: ClassType::None,
-            in_loop: false,
+            private_members: vec![],
+            class_private_members: HashMap::new(),
+            loop_labels: vec![],
         }
     }
 
@@ -49,6 +71,30 @@ impl<'a> Resolver<'a> {
             self.visit_stmt(statement);
         }
     }
+
+    pub fn had_error(&self) -> bool {
+        self.error_handler.had_error
+    }
+
+    /// Record where each variable is declared and used while resolving, see `symbols`.
+    pub fn collect_symbols(mut self) -> Resolver<'a> {
+        self.symbols = Some(SymbolTable::default());
+        self
+    }
+
+    /// The symbols recorded so far, if `collect_symbols` was called.
+    pub fn symbols(&mut self) -> Option<SymbolTable> {
+        self.symbols.take()
+    }
+
+    fn record_symbol(&mut self, token: &Token, symbol: Symbol) {
+        if let Some(symbols) = &mut self.symbols {
+            // `self` and `super` cannot be renamed
+            if token.token_type == TokenType::IDENTIFIER {
+                symbols.add(token, symbol);
+            }
+        }
+    }
 }
 
 impl<'a> Resolver<'a> {
@@ -59,18 +105,27 @@ impl<'a> Resolver<'a> {
                 self.resolve(statements);
                 self.end_scope();
             },
-            Stmt::Break(token) => {
-                if !self.in_loop {
+            Stmt::Break(token, label) => {
+                if self.loop_labels.is_empty() {
                     self.error_handler.token_error(
                         token.clone(),
                         "Break statements can only be used inside loops.".to_string(),
                     );
+                } else if let Some(label) = label {
+                    self.check_label(label);
                 }
             },
-            Stmt::Class(name, superclass, methods) => {
+            Stmt::Class(name, superclass, members) => {
                 self.declare(name);
                 self.define(name);
 
+                // Field values are evaluated where the class is declared, without `self`.
+                for member in members {
+                    if let Stmt::Variable(_, Some(value)) = member {
+                        self.visit_expr(value);
+                    }
+                }
+
                 if let Some(superclass) = superclass {
                     self.resolve_local(superclass, &superclass.lexeme);
 
@@ -89,6 +144,18 @@ impl<'a> Resolver<'a> {
                 self.begin_scope();
                 self.scopes.last_mut().unwrap().insert(keywords::SELF.to_string(), true);
 
+                let mut declared: HashSet<String> = members.iter()
+                    .filter_map(|member| match member {
+                        Stmt::Variable(Pattern::Binding(name), _) | Stmt::Function(name, _, _) => Some(name.lexeme.clone()),
+                        _ => None,
+                    })
+                    .filter(|name| name.starts_with('_'))
+                    .collect();
+                if let Some(inherited) = superclass.as_ref().and_then(|superclass| self.class_private_members.get(&superclass.lexeme)) {
+                    declared.extend(inherited.iter().cloned());
+                }
+                self.private_members.push(PrivateMembers { declared, used: vec![] });
+
                 // Set class type
                 let prev_class = self.current_class;
                 self.current_class = if superclass.is_some() {
@@ -97,8 +164,9 @@ impl<'a> Resolver<'a> {
                     ClassType::Class
                 };
 
-                for method in methods {
-                    match method {
+                for member in members {
+                    match member {
+                        Stmt::Variable(_, _) | Stmt::Expression(_) => (),
                         Stmt::Function(name, params, body) => self.visit_function(
                             params,
                             body,
@@ -107,7 +175,7 @@ impl<'a> Resolver<'a> {
                             } else {
                                 FunctionType::Method
                             }),
-                        _ => panic!("Class methods contain non-function statements."),
+                        _ => panic!("Class contains statements other than fields and methods."),
                     }
                 }
 
@@ -118,41 +186,85 @@ impl<'a> Resolver<'a> {
 
                 self.current_class = prev_class;
 
+                let private_members = self.private_members.pop().unwrap();
+                for name in private_members.used {
+                    if !private_members.declared.contains(&name.lexeme) {
+                        self.private_member_error(name);
+                    }
+                }
+                self.class_private_members.insert(name.lexeme.clone(), private_members.declared);
+
                 self.end_scope();
             },
-            Stmt::Continue(token) => {
-                if !self.in_loop {
+            Stmt::Continue(token, label) => {
+                if self.loop_labels.is_empty() {
                     self.error_handler.token_error(
                         token.clone(),
                         "Continue statements can only be used inside loops.".to_string(),
                     );
+                } else if let Some(label) = label {
+                    self.check_label(label);
                 }
             },
             Stmt::Expression(expr) => {
                 self.visit_expr(expr);
             },
-            Stmt::For(variable, expr, block) => {
+            Stmt::For(pattern, expr, block, label) => {
                 self.visit_expr(expr);
 
-                let prev_in_loop = self.in_loop;
-                self.in_loop = true;
+                self.loop_labels.push(label.as_ref().map(|label| label.lexeme.clone()));
 
                 self.begin_scope();
-                self.declare(variable);
-                self.define(variable);
+                for name in pattern.bindings() {
+                    self.declare(name);
+                    self.define(name);
+                }
 
                 self.resolve(unwrap_block(block));
 
                 self.end_scope();
 
-                self.in_loop = prev_in_loop;
+                self.loop_labels.pop();
             },
+            Stmt::Delete(_, target) => self.visit_expr(target),
             Stmt::Function(name, params, body) => {
                 self.declare(name);
                 self.define(name);
 
                 self.visit_function(params, body, FunctionType::Function)
             },
+            // The imported file is resolved when it runs.
+            Stmt::Import(_, _, target) => {
+                let names = match target {
+                    ImportTarget::Globals => vec![],
+                    ImportTarget::Module(name) => vec![name],
+                    ImportTarget::Names(names) => names.iter().collect(),
+                };
+
+                for name in names {
+                    self.declare(name);
+                    self.define(name);
+                }
+            },
+            Stmt::Match(_, value, cases) => {
+                self.visit_expr(value);
+
+                for case in cases {
+                    // Bindings of the pattern are in scope in the guard and the body
+                    self.begin_scope();
+                    for name in case.pattern.bindings() {
+                        self.declare(name);
+                        self.define(name);
+                    }
+
+                    if let Some(guard) = &case.guard {
+                        self.visit_expr(guard);
+                    }
+                    self.resolve(unwrap_block(&case.body));
+
+                    self.end_scope();
+                }
+            },
             Stmt::Print(_, expr) => {
                 self.visit_expr(expr);
             },
@@ -166,33 +278,73 @@ impl<'a> Resolver<'a> {
 
                 if let Some(expr) = expr {
                     if self.current_function == FunctionType::Initializer {
-                        self.error_handler.token_error(
+                        self.error_handler.token_error_with_help(
                             token.clone(),
                             "Cannot return a value from an initializer.".to_string(),
+                            "calling a class always returns the new instance".to_string(),
                         );
                     }
 
                     self.visit_expr(expr);
                 }
             },
-            Stmt::Variable(variable, initializer) => {
-                self.declare(variable);
+            Stmt::Variable(pattern, initializer) => {
+                let names = pattern.bindings();
+                for name in names.iter() {
+                    self.declare(name);
+                }
 
                 if let Some(expr) = initializer {
                     self.visit_expr(expr);
                 }
 
-                self.define(variable);
+                for name in names {
+                    self.define(name);
+                }
             },
-            Stmt::While(condition, block) => {
-                self.visit_expr(condition);
+            Stmt::Switch(_, value, cases, else_block) => {
+                self.visit_expr(value);
+
+                for (values, block) in cases {
+                    for value in values {
+                        self.visit_expr(value);
+                    }
+                    self.visit_stmt(block);
+                }
 
-                let prev_in_loop = self.in_loop;
-                self.in_loop = true;
+                if let Some(block) = else_block {
+                    self.visit_stmt(block);
+                }
+            },
+            Stmt::While(condition, block, label) => {
+                self.check_condition(condition);
+                self.visit_expr(condition);
 
+                self.loop_labels.push(label.as_ref().map(|label| label.lexeme.clone()));
                 self.visit_stmt(block);
+                self.loop_labels.pop();
+            },
+            Stmt::Try(_, body, name, handler) => {
+                self.visit_stmt(body);
+
+                self.begin_scope();
+                self.declare(name);
+                self.define(name);
+                self.resolve(unwrap_block(handler));
+                self.end_scope();
+            },
+            Stmt::With(_, resource, name, block) => {
+                self.visit_expr(resource);
 
-                self.in_loop = prev_in_loop;
+                self.begin_scope();
+                if let Some(name) = name {
+                    self.declare(name);
+                    self.define(name);
+                }
+
+                self.resolve(unwrap_block(block));
+
+                self.end_scope();
             },
         }
     }
@@ -219,36 +371,46 @@ impl<'a> Resolver<'a> {
                     self.visit_expr(arg);
                 }
             },
-            Expr::Dictionary(exprs) => {
+            Expr::Dictionary(_, exprs) => {
                 for (key, value) in exprs {
                     self.visit_expr(key);
                     self.visit_expr(value);
                 }
             },
-            Expr::Get(obj, _) => {
+            Expr::Get(obj, name) => {
+                self.check_private(obj, name);
                 self.visit_expr(obj);
             },
             Expr::Grouping(expr) => {
                 self.visit_expr(expr);
             },
             Expr::IfExpr(condition, then_branch, else_branch) => {
+                self.check_condition(condition);
                 self.visit_expr(condition);
                 self.visit_stmt(then_branch);
                 self.visit_stmt(else_branch);
             },
-            Expr::IndexGet(expr, index) => {
+            Expr::IndexGet(expr, _, index) => {
                 self.visit_expr(expr);
                 self.visit_expr(index);
             },
-            Expr::IndexSet(expr, index, value) => {
+            Expr::IndexSet(expr, _, index, _, value) => {
                 self.visit_expr(expr);
                 self.visit_expr(index);
                 self.visit_expr(value);
             },
-            Expr::Lambda(params, body) => {
+            Expr::Lambda(_, params, body) => {
                 self.visit_function(params, body, FunctionType::Function)
             },
             Expr::Literal(_) => (),
+            Expr::Range(start, _, end, step) => {
+                self.visit_expr(start);
+                self.visit_expr(end);
+
+                if let Some(step) = step {
+                    self.visit_expr(step);
+                }
+            },
             Expr::SelfExpr(token) => {
                 if self.current_class == ClassType::None {
                     self.error_handler.token_error(
@@ -259,7 +421,8 @@ impl<'a> Resolver<'a> {
 
                 self.resolve_local(&token, &token.lexeme);
             },
-            Expr::Set(obj, _, value) => {
+            Expr::Set(obj, name, _, value) => {
+                self.check_private(obj, name);
                 self.visit_expr(obj);
                 self.visit_expr(value);
             },
@@ -300,37 +463,91 @@ impl<'a> Resolver<'a> {
         }
     }
 
-    fn visit_function(&mut self, params: &Vec<Token>, body: &'a Stmt, function_type: FunctionType) {
+    fn visit_function(&mut self, params: &'a [Param], body: &'a Stmt, function_type: FunctionType) {
+        // Default values are evaluated in the closure of the function, outside its own scope.
+        for default in params.iter().filter_map(|param| param.default.as_ref()) {
+            self.visit_expr(default);
+        }
+
         let enclosing_function = self.current_function;
         self.current_function = function_type;
 
-        // Set in loop to false to disallow top level break/continue in functions
-        let prev_in_loop = self.in_loop;
-        self.in_loop = false;
+        // Loops outside the function do not enclose its body, to disallow top level break/continue in functions.
+        let enclosing_loops = std::mem::take(&mut self.loop_labels);
 
         self.begin_scope();
 
         for param in params {
-            self.declare(param);
-            self.define(param);
+            self.declare(&param.name);
+            self.define(&param.name);
         }
 
         // We don't directly visit the block since we already created a new scope here with params
         self.resolve(unwrap_block(body));
         self.end_scope();
 
-        self.in_loop = prev_in_loop;
+        self.loop_labels = enclosing_loops;
         self.current_function = enclosing_function;
     }
 }
 
 impl<'a> Resolver<'a> {
+    /// Report a `break` or `continue` label that no enclosing loop has.
+    fn check_label(&mut self, label: &Token) {
+        if !self.loop_labels.iter().any(|loop_label| loop_label.as_deref() == Some(label.lexeme.as_str())) {
+            self.error_handler.token_error(
+                label.clone(),
+                format!("There is no enclosing loop labeled '{}'.", label.lexeme),
+            );
+        }
+    }
+
+    /// Report fields and methods whose names start with an underscore used outside of the class
+    /// declaring them, since they are private. They can always be used on `self`, and within their
+    /// class or its subclasses on other instances too.
+    fn check_private(&mut self, object: &Expr, name: &Token) {
+        if !name.lexeme.starts_with('_') {
+            return;
+        }
+
+        match (self.private_members.last_mut(), object) {
+            (Some(private_members), Expr::SelfExpr(_)) => {
+                private_members.declared.insert(name.lexeme.clone());
+            },
+            (Some(private_members), _) => private_members.used.push(name.clone()),
+            (None, Expr::SelfExpr(_)) => (),
+            (None, _) => self.private_member_error(name.clone()),
+        }
+    }
+
+    fn private_member_error(&mut self, name: Token) {
+        let message = format!("Cannot access the private member '{}' outside of its class.", name.lexeme);
+        self.error_handler.token_error(name, message);
+    }
+
+    /// Warn about `=` used as a condition, which is most likely meant to be `==`.
+    fn check_condition(&mut self, condition: &Expr) {
+        if let Expr::Assign(_, operator, _) = condition {
+            if operator.token_type == TokenType::EQUAL {
+                let message = "Assignment used as a condition.".to_string();
+                let help = "use '==' to compare values".to_string();
+                if self.interpreter.options().strict {
+                    self.error_handler.token_error_with_help(operator.clone(), message, help);
+                } else {
+                    self.error_handler.token_warning(operator.clone(), message, help);
+                }
+            }
+        }
+    }
+
     fn begin_scope(&mut self) {
         self.scopes.push(HashMap::new());
+        self.declarations.push(HashMap::new());
     }
 
     fn end_scope(&mut self) {
         self.scopes.pop();
+        self.declarations.pop();
     }
 
     fn declare(&mut self, token: &Token) {
@@ -346,6 +563,16 @@ impl<'a> Resolver<'a> {
                 scope.insert(name.clone(), false);
             }
         }
+
+        if self.symbols.is_some() {
+            match self.declarations.last_mut() {
+                Some(declarations) => {
+                    declarations.insert(name.clone(), token.id);
+                    self.record_symbol(token, Symbol::Local(token.id));
+                },
+                None => self.record_symbol(token, Symbol::Global(name.clone())),
+            }
+        }
     }
 
     fn define(&mut self, token: &Token) {
@@ -366,11 +593,17 @@ impl<'a> Resolver<'a> {
         for (depth, scope) in self.scopes.iter().rev().enumerate() {
             if scope.contains_key(name) {
                 self.interpreter.resolve(token, depth);
+
+                let index = self.scopes.len() - 1 - depth;
+                if let Some(&declaration) = self.declarations[index].get(name) {
+                    self.record_symbol(token, Symbol::Local(declaration));
+                }
                 return;
             }
         }
 
         // Not found, assume it is global
+        self.record_symbol(token, Symbol::Global(name.clone()));
     }
 
 }
@@ -382,3 +615,45 @@ fn unwrap_block(block: &Stmt) -> &Vec<Stmt> {
         _ => panic!(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::dove_output::DoveOutput;
+    use crate::isolate::Isolate;
+    use crate::options::InterpreterOptions;
+
+    struct NoOutput;
+
+    impl DoveOutput for NoOutput {
+        fn print(&self, _message: String) {}
+        fn warning(&self, _message: String) {}
+        fn error(&self, _message: String) {}
+    }
+
+    const ACCOUNT: &str = "
+class Account {
+    fun init(secret) { self._secret = secret }
+    fun same(other) { return self._secret == other._secret }
+}
+";
+
+    fn resolves(source: &str) -> bool {
+        Isolate::new(Rc::new(NoOutput), InterpreterOptions::default())
+            .run(&format!("{}{}", ACCOUNT, source))
+            .is_ok()
+    }
+
+    #[test]
+    fn private_members_within_their_class() {
+        assert!(resolves("Account(42).same(Account(42))"));
+        assert!(resolves("class Savings from Account { fun peek(other) { return other._secret } }"));
+    }
+
+    #[test]
+    fn private_members_outside_their_class() {
+        assert!(!resolves("Account(42)._secret"));
+        assert!(!resolves("class Thief { fun steal(a) { return a._secret } }"));
+    }
+}
@@ -1,16 +1,44 @@
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::token::*;
 use crate::error_handler::*;
-use crate::constants::keywords::KEYWORD_TOKENS;
+use crate::constants::keywords::{self, KEYWORD_TOKENS};
+use crate::version::LanguageVersion;
 use crate::dove_output::DoveOutput;
 
+/// The only state shared between interpreters: ids stay unique across all of them,
+/// which is harmless because each interpreter keeps its own resolved locals.
+static NEXT_TOKEN_ID: AtomicUsize = AtomicUsize::new(1);
+
 pub struct Scanner {
     source: Vec<char>,
     tokens: Vec<Token>,
     start: usize,
     current: usize,
     line: usize,
+    /// Index in `source` of the first character of the current line.
+    line_start: usize,
+    /// Byte offset of `start` in the source.
+    start_byte: usize,
+    /// Line and column where the current lexeme starts.
+    start_line: usize,
+    start_column: usize,
+    /// Name of the scanned file, attached to every token for error reporting.
+    file: Option<Rc<str>>,
+    /// Language version of the source, decides which words are keywords.
+    version: LanguageVersion,
+    /// Whether comments become tokens instead of being skipped.
+    keep_comments: bool,
+
+    /// Number of `(`, `[` and `{` not closed yet.
+    open_delimiters: usize,
+    /// Whether a closing delimiter was found without an opening one.
+    unbalanced: bool,
+    /// Whether the source is REPL input, see `scan_input`.
+    is_input: bool,
+    /// Whether REPL input ends inside a string or block comment.
+    unterminated: bool,
 
     error_handler: CompiletimeErrorHandler,
 }
@@ -20,34 +48,96 @@ impl Scanner {
         Scanner{
             source,
             tokens: Vec::new(),
-            start: 0, current: 0, line: 1,
+            start: 0, current: 0, line: 1, line_start: 0, start_byte: 0,
+            start_line: 1, start_column: 1,
+            file: None,
+            version: LanguageVersion::CURRENT,
+            keep_comments: false,
+            open_delimiters: 0,
+            unbalanced: false,
+            is_input: false,
+            unterminated: false,
             error_handler: CompiletimeErrorHandler::new(output),
         }
     }
+
+    /// Create a scanner for the source of the file at `path`.
+    pub fn with_file(source: Vec<char>, path: &str, output: Rc<dyn DoveOutput>) -> Scanner {
+        let mut scanner = Scanner::new(source, output);
+        scanner.file = Some(Rc::from(path));
+        scanner
+    }
+
+    /// Emit comments as tokens, for tools such as formatters that must not lose them.
+    /// The parser skips them.
+    pub fn keep_comments(mut self) -> Scanner {
+        self.keep_comments = true;
+        self
+    }
+}
+
+/// The tokens of a line of REPL input, see `Scanner::scan_input`.
+pub enum ScannedInput {
+    Complete(Vec<Token>),
+    /// The input ends inside a string, a block comment, or brackets, so it continues on the next line.
+    Unfinished,
+    /// The errors were reported.
+    Invalid,
 }
 
 impl Scanner {
     pub fn scan_tokens(mut self) -> Vec<Token> {
+        self.scan();
+        self.tokens
+    }
+
+    /// Scan the tokens, returning `None` if any error was reported.
+    pub fn try_scan_tokens(mut self) -> Option<Vec<Token>> {
+        if self.scan() { None } else { Some(self.tokens) }
+    }
+
+    /// Scan input of the REPL, which is unfinished rather than wrong if it ends inside a string,
+    /// a block comment, or unclosed brackets.
+    pub fn scan_input(mut self) -> ScannedInput {
+        self.is_input = true;
+        if self.scan() {
+            ScannedInput::Invalid
+        } else if self.unterminated || (self.open_delimiters > 0 && !self.unbalanced) {
+            ScannedInput::Unfinished
+        } else {
+            ScannedInput::Complete(self.tokens)
+        }
+    }
+
+    /// Scan all tokens, returning whether any error was reported.
+    fn scan(&mut self) -> bool {
         while !self.is_at_end() && !self.error_handler.had_error {
             // At the beginning of the next lexeme.
-            self.start = self.current;
+            self.move_start();
+            self.start_line = self.line;
+            self.start_column = self.current - self.line_start + 1;
             self.scan_token();
         }
 
+        self.move_start();
         self.tokens.push(Token::new(
             self.token_id(),
             TokenType::EOF,
             "".to_string(),
             None,
-            self.line
-        ));
+            self.line,
+            self.current - self.line_start + 1,
+            self.file.clone(),
+        ).with_offset(self.start_byte));
 
-        self.tokens
+        self.error_handler.had_error
     }
 
     /// Generate the unique id for a token. It is at least 1, so code generated tokens can have id 0.
+    /// Ids are unique across scanners, since tokens of separately scanned sources
+    /// (REPL lines, imports, `eval`) are resolved into the same interpreter.
     fn token_id(&self) -> usize {
-        self.tokens.len() + 1
+        NEXT_TOKEN_ID.fetch_add(1, Ordering::Relaxed)
     }
 
     fn scan_token(&mut self) {
@@ -63,6 +153,11 @@ impl Scanner {
             ',' => { self.add_token(TokenType::COMMA, None); }
             ':' => { self.add_token(TokenType::COLON, None); }
             '%' => { self.add_token(TokenType::PERCENT, None); }
+            '?' => { self.add_token(TokenType::QUESTION, None); }
+            '&' => { self.add_token(TokenType::AMPERSAND, None); }
+            '|' => { self.add_token(TokenType::PIPE, None); }
+            '^' => { self.add_token(TokenType::CARET, None); }
+            '~' => { self.add_token(TokenType::TILDE, None); }
             // May be one or two characters.
             '+' => {
                 if self.match_char('=') {
@@ -74,7 +169,13 @@ impl Scanner {
                 }
             }
             '*' => {
-                let token_type = if self.match_char('=') { TokenType::STAR_EQUAL } else { TokenType::STAR };
+                let token_type = if self.match_char('*') {
+                    TokenType::STAR_STAR
+                } else if self.match_char('=') {
+                    TokenType::STAR_EQUAL
+                } else {
+                    TokenType::STAR
+                };
                 self.add_token(token_type, None);
             }
             '!' => {
@@ -86,11 +187,23 @@ impl Scanner {
                 self.add_token(token_type, None);
             }
             '<' => {
-                let token_type = if self.match_char('=') { TokenType::LESS_EQUAL } else { TokenType::LESS };
+                let token_type = if self.match_char('=') {
+                    TokenType::LESS_EQUAL
+                } else if self.match_char('<') {
+                    TokenType::LESS_LESS
+                } else {
+                    TokenType::LESS
+                };
                 self.add_token(token_type, None);
             }
             '>' => {
-                let token_type = if self.match_char('=') { TokenType::GREATER_EQUAL } else { TokenType::GREATER };
+                let token_type = if self.match_char('=') {
+                    TokenType::GREATER_EQUAL
+                } else if self.match_char('>') {
+                    TokenType::GREATER_GREATER
+                } else {
+                    TokenType::GREATER
+                };
                 self.add_token(token_type, None);
             }
             // May be one or two or three characters.
@@ -119,7 +232,10 @@ impl Scanner {
                 } else if self.match_char('<') {
                     self.add_token(TokenType::SLASH_LESS, None);
                 } else if self.match_char('/') {
+                    // `///` starts a doc comment, but `////` does not.
+                    let is_doc = self.peek() == '/' && self.peek_next() != '/';
                     while self.peek() != '\n' && !self.is_at_end() { self.advance(); }
+                    self.add_comment(if is_doc { TokenType::DOC_COMMENT } else { TokenType::LINE_COMMENT });
                 } else if self.match_char('*') {
                     self.block_comment();
                 } else {
@@ -130,17 +246,18 @@ impl Scanner {
             ' ' | '\r' | '\t' => {}
             '\n' => {
                 self.add_token(TokenType::NEWLINE, None);
-                self.line += 1;
+                self.new_line();
             }
             '"' => { self.string(); }
+            '#' => { self.pragma(); }
 
             _ => {
                 if c.is_digit(10) {
                     self.number();
-                } else if c.is_alphabetic() {
+                } else if c.is_alphabetic() || c == '_' {
                     self.identifier();
                 } else {
-                    self.error_handler.line_error(self.line, format!("Unexpected character: '{}'.", c));
+                    self.error_handler.location_error(self.location(), format!("Unexpected character: '{}'.", c));
                 }
             }
         }
@@ -154,36 +271,59 @@ impl Scanner {
         // Check if identifier is reserved.
         let lexeme_slice: String = self.source[self.start..self.current].iter().collect();
         let token_type: TokenType = match KEYWORD_TOKENS.get(&lexeme_slice) {
-            Some(v) => *v,
-            None => TokenType::IDENTIFIER,
+            Some(v) if keywords::is_keyword_in(&lexeme_slice, self.version) => *v,
+            _ => TokenType::IDENTIFIER,
         };
         self.add_token(token_type, None);
     }
 
+    /// Scan a pragma until the end of line. A valid version pragma before any code
+    /// sets the language version, the parser reports invalid or misplaced ones.
+    fn pragma(&mut self) {
+        while self.peek() != '\n' && !self.is_at_end() { self.advance(); }
+
+        let is_first = self.tokens.iter().all(|token| token.token_type == TokenType::NEWLINE);
+        let pragma: String = self.source[self.start + 1..self.current].iter().collect();
+        if let (true, Ok(version)) = (is_first, LanguageVersion::from_pragma(&pragma)) {
+            self.version = version;
+        }
+
+        self.add_token(TokenType::PRAGMA, None);
+    }
+
     fn number(&mut self) {
         while self.peek().is_digit(10) { self.advance(); }
 
         // Check if has fractional part.
+        let mut is_int = true;
         if self.peek() == '.' && self.peek_next().is_digit(10) {
             // Consume the dot.
             self.advance();
             while self.peek().is_digit(10) { self.advance(); }
+            is_int = false;
         }
 
         let lexeme_slice: String = self.source[self.start..self.current].iter().collect();
-        let literal_val: f64 = lexeme_slice.parse().unwrap();
-        self.add_token(TokenType::NUMBER, Some(Literals::Number(literal_val)));
+        // Integers too large for an `Int` are numbers.
+        let literal_val = match lexeme_slice.parse::<i64>() {
+            Ok(int) if is_int => Literals::Int(int),
+            _ => Literals::Number(lexeme_slice.parse().unwrap()),
+        };
+        self.add_token(TokenType::NUMBER, Some(literal_val));
     }
 
     fn string(&mut self) {
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' { self.line += 1; }
-            self.advance();
+            if self.advance() == '\n' { self.new_line(); }
         }
 
         // Unterminated string found.
         if self.is_at_end() {
-            self.error_handler.line_error(self.line, "Unterminated string.".to_string());
+            if self.is_input {
+                self.unterminated = true;
+                return;
+            }
+            self.error_handler.location_error(self.location(), "Unterminated string.".to_string());
             return;
         }
 
@@ -191,23 +331,33 @@ impl Scanner {
         self.advance();
 
         let literal_val: String = self.source[(self.start + 1)..(self.current - 1)].iter().collect();
-        self.add_token(TokenType::STRING, Some(Literals::String(literal_val)));
+        self.add_token(TokenType::STRING, Some(Literals::String(literal_val.into())));
     }
 
     fn block_comment(&mut self) {
         while !(self.peek() == '*' && self.peek_next() == '/') && !self.is_at_end() {
-            if self.peek() == '\n' { self.line += 1; }
-            self.advance();
+            if self.advance() == '\n' { self.new_line(); }
         }
 
         // Unterminated block comment found.
         if self.is_at_end() {
-            self.error_handler.line_error(self.line, "Unterminated block comment.".to_string());
+            if self.is_input {
+                self.unterminated = true;
+                return;
+            }
+            self.error_handler.location_error(self.location(), "Unterminated block comment.".to_string());
             return;
         }
 
         // Consume closing '*/'
         self.current += 2;
+        self.add_comment(TokenType::BLOCK_COMMENT);
+    }
+
+    fn add_comment(&mut self, token_type: TokenType) {
+        if self.keep_comments {
+            self.add_token(token_type, None);
+        }
     }
 
     //--- Helpers end.
@@ -222,14 +372,49 @@ impl Scanner {
     }
 
     fn add_token(&mut self, token_type: TokenType, literal: Option<Literals>) {
+        match token_type {
+            TokenType::LEFT_PAREN | TokenType::LEFT_BRACKET | TokenType::LEFT_BRACE => self.open_delimiters += 1,
+            TokenType::RIGHT_PAREN | TokenType::RIGHT_BRACKET | TokenType::RIGHT_BRACE => {
+                match self.open_delimiters.checked_sub(1) {
+                    Some(open) => self.open_delimiters = open,
+                    None => self.unbalanced = true,
+                }
+            },
+            _ => (),
+        }
+
         let lexeme_slice: String = self.source[self.start..self.current].iter().collect();
         self.tokens.push(Token::new(
             self.token_id(),
             token_type,
             lexeme_slice,
             literal,
-            self.line
-        ))
+            self.start_line,
+            self.start_column,
+            self.file.clone(),
+        ).with_offset(self.start_byte))
+    }
+
+    /// Start the next lexeme at `current`.
+    fn move_start(&mut self) {
+        self.start_byte += self.source[self.start..self.current].iter().map(|c| c.len_utf8()).sum::<usize>();
+        self.start = self.current;
+    }
+
+    /// Update the line counters after consuming a '\n'.
+    fn new_line(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
+    }
+
+    /// Location of the start of the current lexeme.
+    fn location(&self) -> SourceLocation {
+        SourceLocation {
+            file: self.file.clone(),
+            line: self.start_line,
+            column: Some(self.start_column),
+            length: 1,
+        }
     }
 
     fn match_char(&mut self, expected: char) -> bool {
@@ -0,0 +1,99 @@
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+
+use indexmap::IndexMap;
+
+use crate::dove_callable::DoveCallable;
+use crate::dove_class::DoveInstance;
+use crate::token::{DictKey, Literals};
+
+/// Numbers of values created by scripts that are still alive, see `Interpreter::runtime_stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuntimeStats {
+    pub arrays: usize,
+    pub dictionaries: usize,
+    pub instances: usize,
+    pub functions: usize,
+}
+
+thread_local! {
+    /// The tracker of the interpreter evaluating on this thread. It holds no tracker until the
+    /// interpreter is first asked for its stats, so values are not tracked when nobody asks.
+    static CURRENT: RefCell<Option<Rc<RefCell<Option<ValueTracker>>>>> = const { RefCell::new(None) };
+}
+
+/// Track a new array, dictionary, instance or function for the interpreter evaluating on this thread.
+pub(crate) fn track(value: &Literals) {
+    CURRENT.with(|current| {
+        if let Some(tracker) = &*current.borrow() {
+            if let Some(tracker) = tracker.borrow_mut().as_mut() {
+                tracker.track(value);
+            }
+        }
+    });
+}
+
+/// Make `tracker` the one new values are tracked by, returning the previous one to restore later.
+pub(crate) fn replace_tracker(tracker: Option<Rc<RefCell<Option<ValueTracker>>>>) -> Option<Rc<RefCell<Option<ValueTracker>>>> {
+    CURRENT.with(|current| current.replace(tracker))
+}
+
+/// Weak references to the values created by an interpreter, to count the ones still alive.
+#[derive(Default)]
+pub(crate) struct ValueTracker {
+    arrays: Tracked<RefCell<Vec<Literals>>>,
+    dictionaries: Tracked<RefCell<IndexMap<DictKey, Literals>>>,
+    instances: Tracked<RefCell<DoveInstance>>,
+    functions: Tracked<dyn DoveCallable>,
+}
+
+impl ValueTracker {
+    /// Track an array, dictionary, instance or function, other values are ignored.
+    pub fn track(&mut self, value: &Literals) {
+        match value {
+            Literals::Array(array) => self.arrays.track(array),
+            Literals::Dictionary(dict) => self.dictionaries.track(dict),
+            Literals::Instance(instance) => self.instances.track(instance),
+            Literals::Function(function) => self.functions.track(function),
+            _ => {},
+        }
+    }
+
+    pub fn stats(&mut self) -> RuntimeStats {
+        RuntimeStats {
+            arrays: self.arrays.prune(),
+            dictionaries: self.dictionaries.prune(),
+            instances: self.instances.prune(),
+            functions: self.functions.prune(),
+        }
+    }
+}
+
+struct Tracked<T: ?Sized> {
+    values: Vec<Weak<T>>,
+    /// Number of values alive when the list was last pruned.
+    live: usize,
+}
+
+impl<T: ?Sized> Default for Tracked<T> {
+    fn default() -> Self {
+        Tracked { values: vec![], live: 0 }
+    }
+}
+
+impl<T: ?Sized> Tracked<T> {
+    fn track(&mut self, value: &Rc<T>) {
+        // Forget dropped values whenever the list doubles, so it stays proportional to the live ones.
+        if self.values.len() >= 2 * self.live.max(32) {
+            self.prune();
+        }
+        self.values.push(Rc::downgrade(value));
+    }
+
+    /// Forget dropped values, returning the number of live ones.
+    fn prune(&mut self) -> usize {
+        self.values.retain(|value| value.strong_count() > 0);
+        self.live = self.values.len();
+        self.live
+    }
+}
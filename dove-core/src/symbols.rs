@@ -0,0 +1,42 @@
+use crate::token::Token;
+
+/// A variable as the resolver sees it. Locals are identified by the id of the token declaring them,
+/// globals by their name, since they are looked up by name when the program runs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    Local(usize),
+    Global(String),
+}
+
+/// Every declaration and use of a variable name found by the resolver, in the order they were resolved.
+/// Collected for tools such as `dove rename`, see `Resolver::collect_symbols`.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    occurrences: Vec<(Token, Symbol)>,
+}
+
+impl SymbolTable {
+    pub(crate) fn add(&mut self, token: &Token, symbol: Symbol) {
+        self.occurrences.push((token.clone(), symbol));
+    }
+
+    pub fn occurrences(&self) -> &[(Token, Symbol)] {
+        &self.occurrences
+    }
+
+    /// The symbol whose name covers the position, with `column` starting from 1.
+    pub fn symbol_at(&self, line: usize, column: usize) -> Option<&Symbol> {
+        self.occurrences.iter()
+            .find(|(token, _)| {
+                token.line == line && token.column <= column && column < token.column + token.lexeme.chars().count()
+            })
+            .map(|(_, symbol)| symbol)
+    }
+
+    /// The tokens declaring or using the symbol.
+    pub fn tokens_of<'a>(&'a self, symbol: &'a Symbol) -> impl Iterator<Item = &'a Token> {
+        self.occurrences.iter()
+            .filter(move |(_, other)| other == symbol)
+            .map(|(token, _)| token)
+    }
+}
@@ -1,10 +1,19 @@
-use std::collections::HashMap;
+use std::any::Any;
+use std::collections::VecDeque;
 use std::cell::RefCell;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
+
+use indexmap::IndexMap;
 
 use crate::dove_callable::DoveCallable;
 use crate::dove_class::{DoveClass, DoveInstance};
 use crate::data_types::DoveObject;
+use crate::data_types::frozen::Frozen;
+use crate::data_types::heap::Heap;
+use crate::data_types::host::HostValue;
+use crate::data_types::module::Module;
+use crate::data_types::nil::Nil;
+use crate::stats;
 
 #[derive(Debug, Clone)]
 pub struct Token {
@@ -13,18 +22,52 @@ pub struct Token {
     pub lexeme: String,
     pub literal: Option<Literals>,
     pub line: usize,
+    /// Column of the first character of the lexeme, starting from 1.
+    pub column: usize,
+    /// The file the token was scanned from, if any.
+    pub file: Option<Rc<str>>,
+    /// Byte offset of the lexeme in the scanned source, 0 for tokens generated by code.
+    /// A `u32` fits in padding, so tokens, and errors holding them, do not grow.
+    pub offset: u32,
 }
 
 impl Token {
-    pub fn new(id: usize, token_type: TokenType, lexeme: String, literal: Option<Literals>, line: usize) -> Token {
+    pub fn new(id: usize, token_type: TokenType, lexeme: String, literal: Option<Literals>, line: usize, column: usize, file: Option<Rc<str>>) -> Token {
         Token {
             id,
             token_type,
             lexeme,
             literal,
             line,
+            column,
+            file,
+            offset: 0,
         }
     }
+
+    pub fn with_offset(mut self, offset: usize) -> Token {
+        self.offset = offset as u32;
+        self
+    }
+
+    /// Where the lexeme is in the scanned source.
+    pub fn span(&self) -> Span {
+        let start = self.offset as usize;
+        Span { start, end: start + self.lexeme.len() }
+    }
+}
+
+/// Byte offsets of a lexeme in its source, from `start` up to but not including `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
 }
 
 
@@ -33,18 +76,19 @@ impl Token {
 pub enum TokenType {
     // Single-character tokens.
     LEFT_PAREN, RIGHT_PAREN, LEFT_BRACE, RIGHT_BRACE, LEFT_BRACKET, RIGHT_BRACKET,
-    COMMA, COLON, NEWLINE, PERCENT,
+    COMMA, COLON, NEWLINE, PERCENT, QUESTION,
+    AMPERSAND, PIPE, CARET, TILDE,
 
     // One or two character tokens.
     SLASH, SLASH_EQUAL, SLASH_LESS, SLASH_GREATER,
-    STAR, STAR_EQUAL,
+    STAR, STAR_EQUAL, STAR_STAR,
     BACKSLASH,
     BANG, BANG_EQUAL,
     EQUAL, EQUAL_EQUAL,
-    GREATER, GREATER_EQUAL,
+    GREATER, GREATER_EQUAL, GREATER_GREATER,
     PLUS, PLUS_EQUAL, PLUS_PLUS,
     MINUS, MINUS_EQUAL, MINUS_GREATER, MINUS_MINUS,
-    LESS, LESS_EQUAL,
+    LESS, LESS_EQUAL, LESS_LESS,
 
     // One or two or three character tokens.
     DOT, DOT_DOT, DOT_DOT_DOT,
@@ -52,26 +96,56 @@ pub enum TokenType {
     // Literals.
     IDENTIFIER, STRING, NUMBER,
 
+    // A `#` line, such as the `#dove 0.2` version pragma.
+    PRAGMA,
+
+    // `// ...`, `/* ... */` and `/// ...` comments, only scanned when comments are kept.
+    LINE_COMMENT, BLOCK_COMMENT, DOC_COMMENT,
+
     // Keywords.
-    AND, BREAK, CLASS, CONTINUE, ELSE, FALSE, FUN, FOR, FROM, IMPORT, IN, IF, LAMBDA, LET, NIL, NOT, OR,
-    PRINT, RETURN, SUPER, SELF, TRUE, WHILE,
+    AND, AS, BREAK, BY, CASE, CATCH, CLASS, CONTINUE, DEL, ELSE, FALSE, FUN, FOR, FROM, IMPORT, IN, IF, LAMBDA, LET, MATCH, NIL, NOT, OR,
+    PRINT, RETURN, SUPER, SELF, SWITCH, TRUE, TRY, WHILE, WITH,
 
     // End of file.
     EOF
 }
 
+impl TokenType {
+    pub fn is_comment(self) -> bool {
+        matches!(self, TokenType::LINE_COMMENT | TokenType::BLOCK_COMMENT | TokenType::DOC_COMMENT)
+    }
+}
+
 #[derive(Clone)]
 pub enum Literals {
     Array(Rc<RefCell<Vec<Literals>>>),
-    Dictionary(Rc<RefCell<HashMap<DictKey, Literals>>>),
-    String(String),
+    Bytes(Rc<Vec<u8>>),
+    /// Entries keep their insertion order, so dictionaries print and iterate the same way every run.
+    Dictionary(Rc<RefCell<IndexMap<DictKey, Literals>>>),
+    Deque(Rc<RefCell<VecDeque<Literals>>>),
+    /// A read-only view of an array or dictionary, created by `freeze`.
+    Frozen(Box<Literals>),
+    Heap(Rc<RefCell<Heap>>),
+    /// Strings are immutable, so they are shared and cloning one, e.g. evaluating a literal, does not copy it.
+    String(Rc<str>),
     Tuple(Box<Vec<Literals>>),
     Number(f64),
+    /// An integer, e.g. `42`. Arithmetic on two integers is exact and gives an integer,
+    /// mixing one with a `Number` gives a `Number`.
+    Int(i64),
     Boolean(bool),
     Nil,
     Function(Rc<dyn DoveCallable>),
     Class(Rc<DoveClass>),
     Instance(Rc<RefCell<DoveInstance>>),
+    /// The definitions of an imported file, see `import "path" as name`.
+    Module(Rc<Module>),
+    /// A Rust value exposed to scripts, see `HostClass`.
+    Object(Rc<dyn HostValue>),
+    /// An opaque Rust value, e.g. a database handle, which scripts can only store and pass around.
+    Native(Rc<dyn Any>),
+    /// A reference to an instance that does not keep it alive, created by `weak_ref`.
+    WeakRef(Weak<RefCell<DoveInstance>>),
 }
 
 impl std::fmt::Debug for Literals {
@@ -84,31 +158,97 @@ impl Literals {
     pub fn to_string(&self) -> String {
         match self {
             Literals::Array(_) => "Array".to_string(),
+            Literals::Bytes(_) => "Bytes".to_string(),
             Literals::Dictionary(_) => "Dictionary".to_string(),
+            Literals::Deque(_) => "Deque".to_string(),
+            Literals::Frozen(value) => format!("Frozen{}", value.to_string()),
+            Literals::Heap(_) => "Heap".to_string(),
             Literals::String(_) => "String".to_string(),
             Literals::Tuple(_) => "Tuple".to_string(),
             Literals::Number(_) => "Number".to_string(),
+            Literals::Int(_) => "Int".to_string(),
             Literals::Boolean(_) => "Boolean".to_string(),
             Literals::Nil => "Nil".to_string(),
             Literals::Function(_) => "Function".to_string(),
             Literals::Class(_) => "Class".to_string(),
             Literals::Instance(_) => "Instance".to_string(),
+            Literals::Module(_) => "Module".to_string(),
+            Literals::Object(object) => object.class_name().to_string(),
+            Literals::Native(_) => "Native".to_string(),
+            Literals::WeakRef(_) => "WeakRef".to_string(),
+        }
+    }
+
+    /// An opaque handle to a Rust value.
+    pub fn native<T: Any>(value: T) -> Literals {
+        Literals::Native(Rc::new(value))
+    }
+
+    /// The Rust value of a native handle, if it has type `T`.
+    pub fn downcast_native<T: Any>(&self) -> Option<Rc<T>> {
+        match self {
+            Literals::Native(value) => Rc::clone(value).downcast().ok(),
+            _ => None,
+        }
+    }
+
+    /// A new array, counted by `runtime_stats`.
+    pub fn array(items: Vec<Literals>) -> Literals {
+        let array = Literals::Array(Rc::new(RefCell::new(items)));
+        stats::track(&array);
+        array
+    }
+
+    /// A new dictionary, counted by `runtime_stats`.
+    pub fn dictionary(entries: IndexMap<DictKey, Literals>) -> Literals {
+        let dictionary = Literals::Dictionary(Rc::new(RefCell::new(entries)));
+        stats::track(&dictionary);
+        dictionary
+    }
+
+    /// A read-only view of an array or dictionary, other values are returned as they are.
+    pub fn freeze(self) -> Literals {
+        match self {
+            Literals::Array(_) | Literals::Dictionary(_) => Literals::Frozen(Box::new(self)),
+            _ => self,
+        }
+    }
+
+    /// The value a frozen view reads from, or the value itself. Only use it for reading.
+    pub fn unfreeze(self) -> Literals {
+        match self {
+            Literals::Frozen(value) => *value,
+            _ => self,
         }
     }
 
     pub fn unwrap_string(self) -> Result<String, ()> {
         match self {
-            Literals::String(s) => Ok(s),
+            Literals::String(s) => Ok(s.to_string()),
             _ => Err(())
         }
     }
+    /// The value of a number or an integer, as a number.
     pub fn unwrap_number(self) -> Result<f64, ()> {
         match self {
             Literals::Number(n) => Ok(n),
+            Literals::Int(n) => Ok(n as f64),
             _ => Err(())
         }
     }
+    /// The value of an integer, or of a number without a fractional part within the range of integers.
+    pub fn unwrap_int(self) -> Option<i64> {
+        match self {
+            Literals::Int(n) => Some(n),
+            Literals::Number(n) if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 => Some(n as i64),
+            _ => None,
+        }
+    }
     pub fn unwrap_usize(self) -> Result<usize, ()> {
+        if let Literals::Int(n) = self {
+            return if n >= 0 { Ok(n as usize) } else { Err(()) };
+        }
+
         match self.unwrap_number() {
             Ok(n) => {
                 if n.fract() != 0.0 || n < 0.0 { return Err(()); }
@@ -127,11 +267,23 @@ impl Literals {
     pub fn as_object(&self) -> Box<dyn DoveObject> {
         match self {
             Literals::Number(number) => Box::new(*number),
-            Literals::String(string) => Box::new(string.clone()),
+            Literals::Int(int) => Box::new(*int),
+            Literals::Boolean(boolean) => Box::new(*boolean),
+            Literals::Nil => Box::new(Nil),
+            Literals::String(string) => Box::new(string.to_string()),
             Literals::Instance(instance) => Box::new(Rc::clone(instance)),
+            Literals::Class(class) => Box::new(Rc::clone(class)),
             Literals::Array(array) => Box::new(Rc::clone(array)),
+            Literals::Tuple(tuple) => Box::new(tuple.clone()),
+            Literals::Bytes(bytes) => Box::new(Rc::clone(bytes)),
             Literals::Dictionary(dict) => Box::new(Rc::clone(dict)),
-            _ => unimplemented!(),
+            Literals::Deque(deque) => Box::new(Rc::clone(deque)),
+            Literals::Frozen(value) => Box::new(Frozen(*value.clone())),
+            Literals::Heap(heap) => Box::new(Rc::clone(heap)),
+            Literals::Module(module) => Box::new(Rc::clone(module)),
+            Literals::Object(object) => Rc::clone(object).object(),
+            // Values without any properties.
+            _ => Box::new(()),
         }
     }
 }
@@ -139,10 +291,28 @@ impl Literals {
 #[derive(Debug, Clone, Hash)]
 pub enum DictKey {
     StringKey(String),
-    NumberKey(isize),
+    NumberKey(i64),
 }
 
 impl DictKey {
+    /// The key for a string or integer value. Numbers are keys only if they are exact integers,
+    /// so two different numbers never give the same key.
+    pub fn from_literal(value: &Literals) -> Option<DictKey> {
+        match value {
+            Literals::String(s) => Some(DictKey::StringKey(s.to_string())),
+            Literals::Number(_) | Literals::Int(_) => value.clone().unwrap_int().map(DictKey::NumberKey),
+            _ => None,
+        }
+    }
+
+    /// The string or number value of the key.
+    pub fn to_literal(&self) -> Literals {
+        match self {
+            DictKey::StringKey(s) => Literals::String(s.as_str().into()),
+            DictKey::NumberKey(n) => Literals::Int(*n),
+        }
+    }
+
     pub fn stringify(&self) -> String {
         match self {
             DictKey::StringKey(s) => format!("\"{}\"", s),
@@ -0,0 +1,57 @@
+use std::fmt;
+
+/// A version of the Dove language, which scripts can target with a `#dove 0.2` pragma.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct LanguageVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl LanguageVersion {
+    /// The version scripts without a pragma target.
+    pub const CURRENT: LanguageVersion = LanguageVersion::new(0, 2);
+    pub const OLDEST: LanguageVersion = LanguageVersion::new(0, 1);
+
+    pub const fn new(major: u32, minor: u32) -> LanguageVersion {
+        LanguageVersion { major, minor }
+    }
+
+    /// Parse a version such as `0.2`.
+    pub fn parse(version: &str) -> Option<LanguageVersion> {
+        let mut parts = version.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+
+        match parts.next() {
+            Some(_) => None,
+            None => Some(LanguageVersion::new(major, minor)),
+        }
+    }
+
+    /// Parse and check the text after '#' of a pragma, e.g. `dove 0.2`.
+    pub fn from_pragma(pragma: &str) -> Result<LanguageVersion, String> {
+        let version = match pragma.trim().strip_prefix("dove") {
+            Some(version) if version.starts_with(char::is_whitespace) => version.trim(),
+            _ => return Err(format!("Unknown pragma '#{}'.", pragma.trim())),
+        };
+
+        let version = match LanguageVersion::parse(version) {
+            Some(version) => version,
+            None => return Err(format!("Invalid language version '{}', expected e.g. '{}'.", version, LanguageVersion::CURRENT)),
+        };
+
+        if version > LanguageVersion::CURRENT {
+            Err(format!("This script targets Dove {}, but the newest supported version is {}.", version, LanguageVersion::CURRENT))
+        } else if version < LanguageVersion::OLDEST {
+            Err(format!("This script targets Dove {}, but the oldest supported version is {}.", version, LanguageVersion::OLDEST))
+        } else {
+            Ok(version)
+        }
+    }
+}
+
+impl fmt::Display for LanguageVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
@@ -0,0 +1,212 @@
+//! Macros exposing Rust types to Dove scripts, re-exported by `dove-core` with its `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, FnArg, ImplItem, ItemImpl, LitStr, Visibility};
+
+/// Implement `HostClass`, so values of a struct can be passed to scripts with `ToDove`.
+///
+/// Fields marked `#[dove]` become properties scripts can read and set, `#[dove(readonly)]` ones can only be read.
+/// Their types must implement `Clone` and `ToDove`, and `FromDove` unless they are read-only.
+/// On the struct, `#[dove(name = "Name")]` changes the printed class name and `#[dove(methods)]`
+/// also exposes the methods of its `#[dove_methods]` block.
+#[proc_macro_derive(DoveClass, attributes(dove))]
+pub fn derive_dove_class(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    dove_class(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Expose the `pub` methods taking `&self` or `&mut self` of an `impl` block to scripts,
+/// for a struct deriving `DoveClass` with `#[dove(methods)]`. Argument and return types
+/// are converted like those of `dove_core::marshal::native`.
+#[proc_macro_attribute]
+pub fn dove_methods(_args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemImpl);
+    dove_methods_impl(&item).unwrap_or_else(Error::into_compile_error).into()
+}
+
+struct Property {
+    ident: syn::Ident,
+    readonly: bool,
+}
+
+fn dove_class(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "DoveClass cannot be derived for generic types."));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unit => return dove_class_with(input, vec![]),
+            Fields::Unnamed(_) => return Err(Error::new_spanned(&input.ident, "DoveClass needs named fields.")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "DoveClass can only be derived for structs.")),
+    };
+
+    let mut properties = vec![];
+    for field in fields {
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("dove")) {
+            let mut readonly = false;
+            if !matches!(attr.meta, syn::Meta::Path(_)) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("readonly") {
+                        readonly = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("Expected 'readonly'."))
+                    }
+                })?;
+            }
+
+            let ident = field.ident.clone().expect("Named fields have identifiers.");
+            properties.push(Property { ident, readonly });
+        }
+    }
+
+    dove_class_with(input, properties)
+}
+
+fn dove_class_with(input: &DeriveInput, properties: Vec<Property>) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let mut name = ident.to_string();
+    let mut methods = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("dove")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("methods") {
+                methods = true;
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("Expected 'methods' or 'name'."))
+            }
+        })?;
+    }
+
+    let get_names = properties.iter().map(|property| property.ident.to_string());
+    let get_fields = properties.iter().map(|property| &property.ident);
+
+    let writable: Vec<_> = properties.iter().filter(|property| !property.readonly).collect();
+    let set_names = writable.iter().map(|property| property.ident.to_string());
+    let set_fields = writable.iter().map(|property| &property.ident);
+
+    let fallback = if methods {
+        quote! {
+            <Self as ::dove_core::data_types::host::HostMethods>::method(this, name)
+                .ok_or(::dove_core::data_types::Error::CannotGetProperty)
+        }
+    } else {
+        quote! { Err(::dove_core::data_types::Error::CannotGetProperty) }
+    };
+
+    Ok(quote! {
+        impl ::dove_core::data_types::host::HostClass for #ident {
+            fn class_name() -> &'static str {
+                #name
+            }
+
+            fn get_property(
+                this: &::std::rc::Rc<::std::cell::RefCell<Self>>,
+                name: &str,
+            ) -> ::dove_core::data_types::Result<::dove_core::token::Literals> {
+                match name {
+                    #(
+                        #get_names => Ok(::dove_core::marshal::ToDove::to_dove(
+                            ::std::clone::Clone::clone(&this.borrow().#get_fields),
+                        )),
+                    )*
+                    _ => #fallback,
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn set_property(
+                this: &::std::rc::Rc<::std::cell::RefCell<Self>>,
+                name: &str,
+                value: ::dove_core::token::Literals,
+            ) -> ::dove_core::data_types::Result<()> {
+                match name {
+                    #(
+                        #set_names => match ::dove_core::marshal::FromDove::from_dove(value) {
+                            Some(value) => {
+                                this.borrow_mut().#set_fields = value;
+                                Ok(())
+                            },
+                            None => Err(::dove_core::data_types::Error::CannotSetProperty),
+                        },
+                    )*
+                    _ => Err(::dove_core::data_types::Error::CannotSetProperty),
+                }
+            }
+        }
+
+        impl ::dove_core::marshal::ToDove for #ident {
+            fn to_dove(self) -> ::dove_core::token::Literals {
+                ::dove_core::data_types::host::host_value(self)
+            }
+        }
+    })
+}
+
+fn dove_methods_impl(item: &ItemImpl) -> syn::Result<TokenStream2> {
+    if !item.generics.params.is_empty() {
+        return Err(Error::new_spanned(&item.generics, "dove_methods cannot be used on generic impl blocks."));
+    }
+
+    let mut arms = vec![];
+    for impl_item in item.items.iter() {
+        let method = match impl_item {
+            ImplItem::Fn(method) => method,
+            _ => continue,
+        };
+
+        // Only public, non-generic methods taking `&self` or `&mut self`.
+        let receiver = match method.sig.receiver() {
+            Some(receiver) if receiver.reference.is_some() => receiver,
+            _ => continue,
+        };
+        if !matches!(method.vis, Visibility::Public(_)) || !method.sig.generics.params.is_empty() {
+            continue;
+        }
+
+        let borrow = if receiver.mutability.is_some() { quote!(borrow_mut) } else { quote!(borrow) };
+        let ident = &method.sig.ident;
+        let name = ident.to_string();
+
+        let types: Vec<_> = method.sig.inputs.iter()
+            .filter_map(|input| match input {
+                FnArg::Typed(arg) => Some(&arg.ty),
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+        let args: Vec<_> = (0..types.len()).map(|index| format_ident!("arg{}", index)).collect();
+
+        arms.push(quote! {
+            #name => {
+                let this = ::std::rc::Rc::clone(this);
+                let function = ::dove_core::marshal::native(move |#( #args: #types ),*| this.#borrow().#ident(#( #args ),*));
+                Some(::dove_core::token::Literals::Function(::std::rc::Rc::new(function)))
+            },
+        });
+    }
+
+    let self_ty = &item.self_ty;
+    Ok(quote! {
+        #item
+
+        impl ::dove_core::data_types::host::HostMethods for #self_ty {
+            fn method(
+                this: &::std::rc::Rc<::std::cell::RefCell<Self>>,
+                name: &str,
+            ) -> Option<::dove_core::token::Literals> {
+                match name {
+                    #( #arms )*
+                    _ => None,
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,59 @@
+use std::rc::Rc;
+
+use dove_core::{dove_methods, DoveClass, DoveOutput, FromDove, InterpreterOptions, Isolate, ToDove};
+use dove_core::data_types::host::host_ref;
+
+#[derive(DoveClass)]
+#[dove(name = "Point", methods)]
+struct Point {
+    #[dove]
+    x: i64,
+    #[dove(readonly)]
+    y: i64,
+    label: String,
+}
+
+#[dove_methods]
+impl Point {
+    pub fn sum(&self) -> i64 {
+        self.x + self.y
+    }
+
+    pub fn shift(&mut self, by: i64) {
+        self.x += by;
+    }
+}
+
+struct NoOutput;
+
+impl DoveOutput for NoOutput {
+    fn print(&self, _message: String) {}
+    fn warning(&self, _message: String) {}
+    fn error(&self, _message: String) {}
+}
+
+fn isolate_with_point() -> Isolate {
+    let mut isolate = Isolate::new(Rc::new(NoOutput), InterpreterOptions::default());
+    isolate.set_global("point", Point { x: 1, y: 2, label: "a".to_string() }.to_dove());
+    isolate
+}
+
+#[test]
+fn round_trip() {
+    let mut isolate = isolate_with_point();
+    let sum = isolate.run("point.x = point.x + 10\npoint.shift(5)\npoint.sum()").unwrap();
+    assert_eq!(i64::from_dove(sum), Some(18));
+
+    let point = host_ref::<Point>(&isolate.global("point").unwrap()).unwrap();
+    assert_eq!((point.borrow().x, point.borrow().y), (16, 2));
+    assert_eq!(point.borrow().label, "a");
+}
+
+#[test]
+fn unexposed_and_readonly_fields() {
+    let mut isolate = isolate_with_point();
+    assert!(isolate.run("point.y").is_ok());
+    assert!(isolate.run("point.y = 3").is_err());
+    assert!(isolate.run("point.label").is_err());
+    assert!(isolate.run("point.x = \"a\"").is_err());
+}
@@ -5,7 +5,7 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use js_sys::Array;
 
-use dove_core::{Scanner, Interpreter, Parser, Resolver, DoveOutput};
+use dove_core::{Scanner, Interpreter, Parser, Resolver, DoveOutput, BufferedOutput, InterpreterOptions, ReplSession};
 
 #[wasm_bindgen]
 extern "C" {
@@ -41,29 +41,38 @@ impl DoveOutput for Output {
     }
 }
 
+/// The wasm stack is much smaller than a native one.
+const MAX_CALL_DEPTH: usize = 200;
+
+/// Printed text kept for the page by a run or REPL input, so endless printing stops the program instead of the tab.
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
 /// Run the source and return the output as an array of strings.
 #[wasm_bindgen]
 pub fn run(source: String) -> StringArray {
     let output_raw = Rc::new(Output::new());
-    let output = Rc::clone(&output_raw) as Rc<dyn DoveOutput>;
+    let buffered = Rc::new(BufferedOutput::new(Rc::clone(&output_raw) as Rc<dyn DoveOutput>, Some(MAX_OUTPUT_BYTES)));
+    let output = Rc::clone(&buffered) as Rc<dyn DoveOutput>;
 
     let chars = source.chars().collect();
     let scanner = Scanner::new(chars, Rc::clone(&output));
     let tokens = scanner.scan_tokens();
 
-    let mut parser = Parser::new(tokens, false, Rc::clone(&output));
+    let mut parser = Parser::new(tokens, Rc::clone(&output));
     let statements = parser.program();
 
     // Stops if there is a syntax error.
     // if self.had_error {
     //     return self;
     // }
-    let mut interpreter = Interpreter::new(Rc::clone(&output));
+    let options = InterpreterOptions::default().max_call_depth(Some(MAX_CALL_DEPTH));
+    let mut interpreter = Interpreter::new(Rc::clone(&output), options);
 
     let mut resolver = Resolver::new(&mut interpreter, Rc::clone(&output));
     resolver.resolve(&statements);
 
     interpreter.interpret(statements);
+    buffered.flush();
 
     let str_arr = output_raw.lines.borrow().iter()
         .map(JsValue::from)
@@ -72,3 +81,62 @@ pub fn run(source: String) -> StringArray {
 
     str_arr
 }
+
+/// A REPL session for the playground, which handles input like the command line REPL.
+#[wasm_bindgen]
+pub struct Repl {
+    session: ReplSession,
+    output: Rc<Output>,
+    buffered: Rc<BufferedOutput>,
+}
+
+#[wasm_bindgen]
+impl Repl {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Repl {
+        let output = Rc::new(Output::new());
+        let buffered = Rc::new(BufferedOutput::new(Rc::clone(&output) as Rc<dyn DoveOutput>, Some(MAX_OUTPUT_BYTES)));
+        let options = InterpreterOptions::default().max_call_depth(Some(MAX_CALL_DEPTH));
+
+        Repl {
+            session: ReplSession::new(Rc::clone(&buffered) as Rc<dyn DoveOutput>, options),
+            output,
+            buffered,
+        }
+    }
+
+    /// The lines to show when the terminal starts.
+    pub fn banner(&self) -> StringArray {
+        self.session.banner().into_iter()
+            .map(JsValue::from)
+            .collect::<Array>()
+            .unchecked_into::<StringArray>()
+    }
+
+    /// The prompt to show before the next line.
+    pub fn prompt(&self) -> String {
+        self.session.prompt().to_string()
+    }
+
+    /// Whether an input called `exit`, after which the terminal should stop taking input.
+    pub fn exited(&mut self) -> bool {
+        self.session.interpreter().exit_status().is_some()
+    }
+
+    /// Run a line of input and return what it printed.
+    pub fn input(&mut self, line: String) -> StringArray {
+        self.session.input(&line);
+        self.buffered.flush();
+
+        self.output.lines.borrow_mut().drain(..)
+            .map(JsValue::from)
+            .collect::<Array>()
+            .unchecked_into::<StringArray>()
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Repl::new()
+    }
+}